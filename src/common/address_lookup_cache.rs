@@ -1,6 +1,6 @@
 use solana_sdk::{message::AddressLookupTableAccount, pubkey::Pubkey};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::Mutex;
 
 /// AddressLookupTableInfo 结构体，存储地址表相关信息
 pub struct AddressLookupTableInfo {
@@ -12,25 +12,24 @@ pub struct AddressLookupTableInfo {
     pub lock: bool,
 }
 
-/// AddressLookupTableCache 单例，用于存储和管理地址表
+/// AddressLookupTableCache 用于存储和管理地址表，由 `TradeContext` 持有
 pub struct AddressLookupTableCache {
     /// 内部存储的地址表数据，键为地址表地址
     tables: Mutex<HashMap<Pubkey, AddressLookupTableInfo>>,
 }
 
-// 使用静态 OnceLock 确保单例模式的线程安全性
-static ADDRESS_LOOKUP_TABLE_CACHE: OnceLock<Arc<AddressLookupTableCache>> = OnceLock::new();
+impl Default for AddressLookupTableCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl AddressLookupTableCache {
-    /// 获取 AddressLookupTableCache 单例实例
-    pub fn get_instance() -> Arc<AddressLookupTableCache> {
-        ADDRESS_LOOKUP_TABLE_CACHE
-            .get_or_init(|| {
-                Arc::new(AddressLookupTableCache {
-                    tables: Mutex::new(HashMap::new()),
-                })
-            })
-            .clone()
+    /// 创建空的地址表缓存
+    pub fn new() -> Self {
+        Self {
+            tables: Mutex::new(HashMap::new()),
+        }
     }
 
     /// 添加或更新地址表信息
@@ -148,11 +147,3 @@ impl AddressLookupTableCache {
             })
     }
 }
-
-/// 获取地址表账户
-pub async fn get_address_lookup_table_account(
-    lookup_table_address: &Pubkey,
-) -> AddressLookupTableAccount {
-    let cache = AddressLookupTableCache::get_instance();
-    return cache.get_table_content(&lookup_table_address);
-}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::native_token::{sol_str_to_lamports, LAMPORTS_PER_SOL};
+
+/// 以 lamports 为单位的 SOL 数量
+///
+/// 小费、买入金额等都使用该类型，避免 SOL 与 lamports 混用，
+/// 也避免浮点数转换失败时静默变成 0。
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Lamports(u64);
+
+impl Lamports {
+    pub const ZERO: Self = Self(0);
+
+    pub const fn new(lamports: u64) -> Self {
+        Self(lamports)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// 从 SOL 数量换算，按四舍五入取整到 lamports
+    ///
+    /// 数值为负、非有限值或超出 `u64` 范围时返回错误。
+    pub fn from_sol(sol: f64) -> Result<Self> {
+        if !sol.is_finite() || sol < 0.0 {
+            return Err(anyhow!("Invalid SOL amount: {}", sol));
+        }
+        let lamports = (sol * LAMPORTS_PER_SOL as f64).round();
+        if lamports > u64::MAX as f64 {
+            return Err(anyhow!("SOL amount out of range: {}", sol));
+        }
+        Ok(Self(lamports as u64))
+    }
+
+    /// 解析十进制 SOL 字符串，例如 `"0.001"`
+    pub fn from_sol_str(sol: &str) -> Result<Self> {
+        sol_str_to_lamports(sol).map(Self).ok_or_else(|| anyhow!("Invalid SOL amount: {}", sol))
+    }
+
+    pub const fn checked_add(self, other: Self) -> Option<Self> {
+        match self.0.checked_add(other.0) {
+            Some(lamports) => Some(Self(lamports)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, other: Self) -> Option<Self> {
+        match self.0.checked_sub(other.0) {
+            Some(lamports) => Some(Self(lamports)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_mul(self, factor: u64) -> Option<Self> {
+        match self.0.checked_mul(factor) {
+            Some(lamports) => Some(Self(lamports)),
+            None => None,
+        }
+    }
+
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    pub const fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    /// 以 SOL 为单位显示
+    pub const fn sol(self) -> Sol {
+        Sol(self)
+    }
+}
+
+impl From<u64> for Lamports {
+    fn from(lamports: u64) -> Self {
+        Self(lamports)
+    }
+}
+
+impl From<Lamports> for u64 {
+    fn from(lamports: Lamports) -> Self {
+        lamports.0
+    }
+}
+
+impl fmt::Display for Lamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lamports", self.0)
+    }
+}
+
+/// 以 SOL 为单位显示 lamports 数量，直接按整数拆分，不经过浮点换算
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sol(pub Lamports);
+
+impl fmt::Display for Sol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lamports = self.0.as_u64();
+        let whole = lamports / LAMPORTS_PER_SOL;
+        let fraction = lamports % LAMPORTS_PER_SOL;
+        if fraction == 0 {
+            return write!(f, "{} SOL", whole);
+        }
+        let fraction = format!("{:09}", fraction);
+        write!(f, "{}.{} SOL", whole, fraction.trim_end_matches('0'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lamports_from_sol() {
+        assert_eq!(Lamports::from_sol(0.0006).unwrap(), Lamports::new(600_000));
+        assert_eq!(Lamports::from_sol(1.0).unwrap(), Lamports::new(LAMPORTS_PER_SOL));
+        assert_eq!(Lamports::from_sol_str("0.00001").unwrap(), Lamports::new(10_000));
+        assert!(Lamports::from_sol(-0.1).is_err());
+        assert!(Lamports::from_sol(f64::NAN).is_err());
+        assert!(Lamports::from_sol(f64::MAX).is_err());
+        assert!(Lamports::from_sol_str("abc").is_err());
+    }
+
+    #[test]
+    fn test_lamports_checked_arithmetic() {
+        let a = Lamports::new(5);
+        assert_eq!(a.checked_add(Lamports::new(3)), Some(Lamports::new(8)));
+        assert_eq!(a.checked_sub(Lamports::new(6)), None);
+        assert_eq!(Lamports::new(u64::MAX).checked_mul(2), None);
+        assert_eq!(a.saturating_sub(Lamports::new(6)), Lamports::ZERO);
+    }
+
+    #[test]
+    fn test_sol_display() {
+        assert_eq!(Lamports::new(600_000).sol().to_string(), "0.0006 SOL");
+        assert_eq!(Lamports::new(2 * LAMPORTS_PER_SOL).sol().to_string(), "2 SOL");
+        assert_eq!(Lamports::new(1_500_000_001).sol().to_string(), "1.500000001 SOL");
+    }
+}
@@ -0,0 +1,458 @@
+use std::{future::Future, time::Duration};
+
+use anyhow::{anyhow, Result};
+use reqwest::multipart::{Form, Part};
+use serde_json::{json, Map, Value};
+use solana_sdk::hash::{hash, Hash};
+
+use crate::trading::TokenMetadata;
+
+/// 默认的 IPFS 网关，上传服务只返回 CID 时用于拼接访问地址
+pub const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// 创建代币前需要上传的图片和描述信息
+#[derive(Clone, Debug, Default)]
+pub struct MetadataInput {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub image: Vec<u8>,
+    /// 图片文件名，根据扩展名确定图片类型
+    pub image_file_name: String,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+}
+
+/// 元数据上传器，把图片和描述转换为 create 指令使用的元数据地址
+#[async_trait::async_trait]
+pub trait MetadataUploader: Send + Sync {
+    /// 上传图片和 Metaplex 格式的 JSON，返回可直接用于创建代币的元数据
+    async fn upload(&self, input: &MetadataInput) -> Result<TokenMetadata>;
+}
+
+/// 上传接口的请求格式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UploadFormat {
+    /// IPFS pinning 服务：图片和 JSON 分两次以 `file` 字段上传，响应返回 `uri`/`url` 或 CID
+    #[default]
+    Pinning,
+    /// pump 的 `/api/ipfs`：图片和 name/symbol/description/社交链接放在同一个表单中上传，
+    /// 由服务端生成 JSON 并返回 `metadataUri`
+    PumpForm,
+}
+
+/// 基于 HTTP multipart 上传的实现，兼容 IPFS pinning 服务和 pump 风格的上传接口
+///
+/// `Pinning` 格式下图片和 JSON 依次以 `file` 字段上传到 `upload_url`，响应中的 `uri`/`url`
+/// 直接作为地址，只返回 `cid`/`Hash`/`IpfsHash` 时拼接到网关后面，上传后会重新下载内容校验哈希。
+/// `PumpForm` 格式只发送一个表单，JSON 由服务端生成，因此不做哈希校验。
+/// 失败时按 `retry_delay` 递增间隔重试。
+pub struct HttpMetadataUploader {
+    client: reqwest::Client,
+    upload_url: String,
+    gateway_url: String,
+    format: UploadFormat,
+    max_retries: u32,
+    retry_delay: Duration,
+    verify_content: bool,
+}
+
+impl HttpMetadataUploader {
+    pub fn new(upload_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            upload_url: upload_url.into(),
+            gateway_url: DEFAULT_IPFS_GATEWAY.to_string(),
+            format: UploadFormat::Pinning,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+            verify_content: true,
+        }
+    }
+
+    pub fn with_gateway(mut self, gateway_url: impl Into<String>) -> Self {
+        self.gateway_url = gateway_url.into();
+        self
+    }
+
+    /// 使用 pump 风格的单表单上传，例如 `https://pump.fun/api/ipfs`
+    pub fn pump(upload_url: impl Into<String>) -> Self {
+        Self::new(upload_url).with_format(UploadFormat::PumpForm)
+    }
+
+    pub fn with_format(mut self, format: UploadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_retries(mut self, max_retries: u32, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// 是否在上传后下载内容校验哈希，网关同步较慢时可以关闭，`PumpForm` 格式下不生效
+    pub fn with_content_verification(mut self, verify_content: bool) -> Self {
+        self.verify_content = verify_content;
+        self
+    }
+
+    /// 执行一次上传，失败时按递增间隔重试
+    async fn with_retry<F, Fut>(&self, label: &str, upload: F) -> Result<String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match upload().await {
+                Ok(uri) => return Ok(uri),
+                Err(e) if attempt < self.max_retries => {
+                    attempt += 1;
+                    println!("上传{}失败，第{}次重试: {}", label, attempt, e);
+                    tokio::time::sleep(self.retry_delay * attempt).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// 上传一个文件并返回访问地址，失败时重试
+    async fn upload_file(
+        &self,
+        data: &[u8],
+        file_name: &str,
+        content_type: &str,
+    ) -> Result<String> {
+        self.with_retry(file_name, || self.try_upload_file(data, file_name, content_type)).await
+    }
+
+    async fn try_upload_file(
+        &self,
+        data: &[u8],
+        file_name: &str,
+        content_type: &str,
+    ) -> Result<String> {
+        let part =
+            Part::bytes(data.to_vec()).file_name(file_name.to_string()).mime_str(content_type)?;
+        let form = Form::new().part("file", part);
+        let response = self.client.post(&self.upload_url).multipart(form).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Upload failed with status {}", response.status()));
+        }
+        let body: Value = response.json().await?;
+        let uri = parse_upload_response(&body, &self.gateway_url)?;
+
+        if self.verify_content {
+            let downloaded =
+                self.client.get(&uri).send().await?.error_for_status()?.bytes().await?;
+            check_content_hash(&hash(data), &downloaded)?;
+        }
+        Ok(uri)
+    }
+
+    /// 以 pump 的表单格式上传图片和描述，返回服务端生成的 `metadataUri`
+    async fn try_upload_pump_form(
+        &self,
+        input: &MetadataInput,
+        content_type: &str,
+    ) -> Result<String> {
+        let part = Part::bytes(input.image.clone())
+            .file_name(input.image_file_name.clone())
+            .mime_str(content_type)?;
+        let mut form = Form::new()
+            .part("file", part)
+            .text("name", input.name.clone())
+            .text("symbol", input.symbol.clone())
+            .text("description", input.description.clone())
+            .text("showName", "true");
+        for (field, value) in [
+            ("twitter", &input.twitter),
+            ("telegram", &input.telegram),
+            ("website", &input.website),
+        ] {
+            if let Some(value) = value {
+                form = form.text(field, value.clone());
+            }
+        }
+        let response = self.client.post(&self.upload_url).multipart(form).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Upload failed with status {}", response.status()));
+        }
+        let body: Value = response.json().await?;
+        body.get("metadataUri")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Upload response does not contain a metadataUri: {}", body))
+    }
+}
+
+#[async_trait::async_trait]
+impl MetadataUploader for HttpMetadataUploader {
+    async fn upload(&self, input: &MetadataInput) -> Result<TokenMetadata> {
+        if input.image.is_empty() {
+            return Err(anyhow!("Token image is empty"));
+        }
+        let content_type = image_content_type(&input.image_file_name)?;
+        if self.format == UploadFormat::PumpForm {
+            let uri = self
+                .with_retry(&input.image_file_name, || {
+                    self.try_upload_pump_form(input, content_type)
+                })
+                .await?;
+            return Ok(TokenMetadata {
+                name: input.name.clone(),
+                symbol: input.symbol.clone(),
+                uri,
+            });
+        }
+        let image_uri =
+            self.upload_file(&input.image, &input.image_file_name, content_type).await?;
+
+        let metadata_json =
+            serde_json::to_vec(&build_metadata_json(input, &image_uri, content_type))?;
+        let uri = self.upload_file(&metadata_json, "metadata.json", "application/json").await?;
+
+        Ok(TokenMetadata { name: input.name.clone(), symbol: input.symbol.clone(), uri })
+    }
+}
+
+/// 构建 Metaplex 兼容的元数据 JSON，社交链接按 pump 的格式放在顶层
+pub fn build_metadata_json(input: &MetadataInput, image_uri: &str, content_type: &str) -> Value {
+    let mut metadata = Map::new();
+    metadata.insert("name".to_string(), json!(input.name));
+    metadata.insert("symbol".to_string(), json!(input.symbol));
+    metadata.insert("description".to_string(), json!(input.description));
+    metadata.insert("image".to_string(), json!(image_uri));
+    if let Some(website) = &input.website {
+        metadata.insert("external_url".to_string(), json!(website));
+        metadata.insert("website".to_string(), json!(website));
+    }
+    if let Some(twitter) = &input.twitter {
+        metadata.insert("twitter".to_string(), json!(twitter));
+    }
+    if let Some(telegram) = &input.telegram {
+        metadata.insert("telegram".to_string(), json!(telegram));
+    }
+    metadata.insert(
+        "properties".to_string(),
+        json!({
+            "files": [{ "uri": image_uri, "type": content_type }],
+            "category": "image",
+        }),
+    );
+    Value::Object(metadata)
+}
+
+/// 从上传响应中取出访问地址
+fn parse_upload_response(body: &Value, gateway_url: &str) -> Result<String> {
+    for field in ["uri", "url"] {
+        if let Some(uri) = body.get(field).and_then(Value::as_str) {
+            return Ok(uri.to_string());
+        }
+    }
+    for field in ["cid", "Hash", "IpfsHash"] {
+        if let Some(cid) = body.get(field).and_then(Value::as_str) {
+            return Ok(format!("{}/{}", gateway_url.trim_end_matches('/'), cid));
+        }
+    }
+    Err(anyhow!("Upload response does not contain a uri: {}", body))
+}
+
+/// 校验下载内容与上传内容的 SHA-256 是否一致
+fn check_content_hash(expected: &Hash, downloaded: &[u8]) -> Result<()> {
+    let actual = hash(downloaded);
+    if actual != *expected {
+        return Err(anyhow!("Content hash mismatch: expected {}, got {}", expected, actual));
+    }
+    Ok(())
+}
+
+/// 根据文件扩展名确定图片类型
+fn image_content_type(file_name: &str) -> Result<&'static str> {
+    let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => Ok("image/png"),
+        Some("jpg") | Some("jpeg") => Ok("image/jpeg"),
+        Some("gif") => Ok("image/gif"),
+        Some("webp") => Ok("image/webp"),
+        Some("svg") => Ok("image/svg+xml"),
+        _ => Err(anyhow!("Unsupported image type: {}", file_name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    /// 按顺序返回预设响应的本地 HTTP 服务，结束后返回收到的请求内容
+    fn serve(listener: TcpListener, responses: Vec<(u16, Vec<u8>)>) -> JoinHandle<Vec<String>> {
+        tokio::spawn(async move {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buf = [0u8; 4096];
+                let header_end = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                let head = String::from_utf8_lossy(&request[..header_end]).to_ascii_lowercase();
+                let content_length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map_or(0, |len| len.trim().parse::<usize>().unwrap());
+                while request.len() < header_end + content_length {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                requests.push(String::from_utf8_lossy(&request).to_string());
+
+                let head = format!(
+                    "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(&body).await.unwrap();
+            }
+            requests
+        })
+    }
+
+    async fn bind() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        (listener, url)
+    }
+
+    fn input() -> MetadataInput {
+        MetadataInput {
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            description: "desc".to_string(),
+            image: b"image-bytes".to_vec(),
+            image_file_name: "logo.png".to_string(),
+            twitter: Some("https://x.com/token".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pump_form_upload_retries_after_server_error() {
+        let (listener, url) = bind().await;
+        let server = serve(
+            listener,
+            vec![
+                (500, b"{}".to_vec()),
+                (200, br#"{"metadataUri":"https://ipfs.io/ipfs/QmMeta"}"#.to_vec()),
+            ],
+        );
+        let uploader = HttpMetadataUploader::pump(format!("{}/api/ipfs", url))
+            .with_retries(1, Duration::from_millis(1));
+
+        let metadata = uploader.upload(&input()).await.unwrap();
+        assert_eq!(metadata.uri, "https://ipfs.io/ipfs/QmMeta");
+        assert_eq!(metadata.symbol, "TKN");
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        let form = &requests[1];
+        assert!(form.starts_with("POST /api/ipfs"));
+        assert!(form.contains("name=\"file\"; filename=\"logo.png\""));
+        assert!(form.contains("image-bytes"));
+        assert!(form.contains("name=\"symbol\"\r\n\r\nTKN"));
+        assert!(form.contains("name=\"twitter\"\r\n\r\nhttps://x.com/token"));
+        assert!(!form.contains("name=\"website\""));
+    }
+
+    #[tokio::test]
+    async fn test_pinning_upload_verifies_content() {
+        let (listener, url) = bind().await;
+        let image_response = format!(r#"{{"uri":"{}/image"}}"#, url).into_bytes();
+        let json_response = format!(r#"{{"uri":"{}/json"}}"#, url).into_bytes();
+        let image_json = serde_json::to_vec(&build_metadata_json(
+            &input(),
+            &format!("{}/image", url),
+            "image/png",
+        ))
+        .unwrap();
+        let server = serve(
+            listener,
+            vec![
+                (200, image_response),
+                (200, b"image-bytes".to_vec()),
+                (200, json_response),
+                (200, image_json),
+            ],
+        );
+        let uploader =
+            HttpMetadataUploader::new(format!("{}/upload", url)).with_retries(0, Duration::ZERO);
+
+        let metadata = uploader.upload(&input()).await.unwrap();
+        assert_eq!(metadata.uri, format!("{}/json", url));
+        assert_eq!(server.await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_pinning_upload_rejects_hash_mismatch() {
+        let (listener, url) = bind().await;
+        let upload_response = format!(r#"{{"uri":"{}/image"}}"#, url).into_bytes();
+        let server = serve(listener, vec![(200, upload_response), (200, b"other-bytes".to_vec())]);
+        let uploader =
+            HttpMetadataUploader::new(format!("{}/upload", url)).with_retries(0, Duration::ZERO);
+
+        let err = uploader.upload(&input()).await.unwrap_err();
+        assert!(err.to_string().contains("Content hash mismatch"), "{}", err);
+        assert_eq!(server.await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_build_metadata_json() {
+        let input = MetadataInput {
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            description: "desc".to_string(),
+            website: Some("https://example.com".to_string()),
+            ..Default::default()
+        };
+        let json = build_metadata_json(&input, "https://ipfs.io/ipfs/img", "image/png");
+        assert_eq!(json["name"], "Token");
+        assert_eq!(json["image"], "https://ipfs.io/ipfs/img");
+        assert_eq!(json["external_url"], "https://example.com");
+        assert_eq!(json["properties"]["files"][0]["type"], "image/png");
+        assert!(json.get("twitter").is_none());
+    }
+
+    #[test]
+    fn test_parse_upload_response() {
+        let gateway = "https://gateway.local/ipfs/";
+        assert_eq!(
+            parse_upload_response(&json!({ "uri": "https://x/1" }), gateway).unwrap(),
+            "https://x/1"
+        );
+        assert_eq!(
+            parse_upload_response(&json!({ "IpfsHash": "Qm123" }), gateway).unwrap(),
+            "https://gateway.local/ipfs/Qm123"
+        );
+        assert!(parse_upload_response(&json!({ "ok": true }), gateway).is_err());
+        assert!(parse_upload_response(&json!({ "metadataUri": "https://x/2" }), gateway).is_err());
+    }
+
+    #[test]
+    fn test_content_hash_and_image_type() {
+        assert!(check_content_hash(&hash(b"abc"), b"abc").is_ok());
+        assert!(check_content_hash(&hash(b"abc"), b"abd").is_err());
+        assert_eq!(image_content_type("logo.PNG").unwrap(), "image/png");
+        assert!(image_content_type("logo").is_err());
+    }
+}
@@ -2,9 +2,21 @@ pub mod address_lookup;
 pub mod address_lookup_cache;
 pub mod bonding_curve;
 pub mod global;
+pub mod lamports;
+pub mod metadata_uploader;
 pub mod nonce_cache;
+pub mod signer;
 pub mod subscription_handle;
 pub mod tip_cache;
+pub mod trade_context;
 pub mod types;
+pub mod vanity;
+pub mod wallet_pool;
 
+pub use lamports::{Lamports, Sol};
+pub use metadata_uploader::{HttpMetadataUploader, MetadataInput, MetadataUploader, UploadFormat};
+pub use signer::{AsyncTradeSigner, TradeSigner};
+pub use trade_context::TradeContext;
 pub use types::*;
+pub use vanity::{grind_vanity_keypairs, VanityKeypairPool, VanityPattern};
+pub use wallet_pool::WalletPool;
@@ -1,7 +1,7 @@
 use solana_hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::Mutex;
 
 /// NonceInfo 结构体，存储 nonce 相关信息
 pub struct NonceInfo {
@@ -17,31 +17,30 @@ pub struct NonceInfo {
     pub used: bool,
 }
 
-/// NonceInfoStore 单例，用于存储和管理 NonceInfo
+/// NonceCache 用于存储和管理 NonceInfo，由 `TradeContext` 持有
 pub struct NonceCache {
     /// 内部存储的 NonceInfo 数据
     nonce_info: Mutex<NonceInfo>,
 }
 
-// 使用静态 OnceLock 确保单例模式的线程安全性
-static NONCE_CACHE: OnceLock<Arc<NonceCache>> = OnceLock::new();
+impl Default for NonceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl NonceCache {
-    /// 获取 NonceInfoStore 单例实例
-    pub fn get_instance() -> Arc<NonceCache> {
-        NONCE_CACHE
-            .get_or_init(|| {
-                Arc::new(NonceCache {
-                    nonce_info: Mutex::new(NonceInfo {
-                        nonce_account: None,
-                        current_nonce: Hash::default(),
-                        next_buy_time: 0,
-                        lock: false,
-                        used: false,
-                    }),
-                })
-            })
-            .clone()
+    /// 创建未配置 nonce 账户的缓存
+    pub fn new() -> Self {
+        Self {
+            nonce_info: Mutex::new(NonceInfo {
+                nonce_account: None,
+                current_nonce: Hash::default(),
+                next_buy_time: 0,
+                lock: false,
+                used: false,
+            }),
+        }
     }
 
     /// 初始化 nonce 信息
@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Result};
+use futures::future::try_join_all;
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+
+/// 同步签名者，签名在当前进程内完成
+pub trait TradeSigner: Send + Sync {
+    /// 签名账户地址
+    fn pubkey(&self) -> Pubkey;
+
+    /// 对序列化后的交易消息签名
+    fn sign_message(&self, message: &[u8]) -> Result<Signature>;
+}
+
+/// 异步签名者，可以把签名委托给远程签名服务或隔离的密钥进程
+///
+/// 所有 `TradeSigner` 都自动实现该 trait，交易构建统一使用 `AsyncTradeSigner`。
+#[async_trait::async_trait]
+pub trait AsyncTradeSigner: Send + Sync {
+    /// 签名账户地址，构建指令时会频繁调用，实现时应直接返回缓存值
+    fn pubkey(&self) -> Pubkey;
+
+    /// 对序列化后的交易消息签名
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature>;
+}
+
+#[async_trait::async_trait]
+impl<T: TradeSigner> AsyncTradeSigner for T {
+    fn pubkey(&self) -> Pubkey {
+        TradeSigner::pubkey(self)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        TradeSigner::sign_message(self, message)
+    }
+}
+
+impl TradeSigner for Keypair {
+    fn pubkey(&self) -> Pubkey {
+        Signer::pubkey(self)
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        Ok(Signer::try_sign_message(self, message)?)
+    }
+}
+
+/// 使用给定的签名者对消息签名，签名顺序与消息中的签名账户一致
+///
+/// 所有签名并发请求，缺少任一签名账户时返回错误。
+pub async fn sign_versioned_message(
+    message: VersionedMessage,
+    signers: &[&dyn AsyncTradeSigner],
+) -> Result<VersionedTransaction> {
+    let message_data = message.serialize();
+    let num_required_signatures = message.header().num_required_signatures as usize;
+    let signer_keys = message
+        .static_account_keys()
+        .get(..num_required_signatures)
+        .ok_or_else(|| anyhow!("Message has fewer accounts than required signatures"))?;
+
+    let signatures = try_join_all(signer_keys.iter().map(|key| {
+        let signer = signers.iter().find(|signer| signer.pubkey() == *key);
+        let message_data = &message_data;
+        async move {
+            match signer {
+                Some(signer) => signer.sign_message(message_data).await,
+                None => Err(anyhow!("Missing signer for {}", key)),
+            }
+        }
+    }))
+    .await?;
+
+    Ok(VersionedTransaction { signatures, message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_hash::Hash;
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        message::v0,
+    };
+    use solana_system_interface::instruction::transfer;
+
+    /// 模拟远程签名服务，只实现异步接口
+    struct MockRemoteSigner {
+        pubkey: Pubkey,
+        keypair: Keypair,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncTradeSigner for MockRemoteSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.pubkey
+        }
+
+        async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+            tokio::task::yield_now().await;
+            Ok(Signer::try_sign_message(&self.keypair, message)?)
+        }
+    }
+
+    fn transfer_message(from: &Pubkey, to: &Pubkey) -> VersionedMessage {
+        let instruction = transfer(from, to, 1);
+        VersionedMessage::V0(
+            v0::Message::try_compile(from, &[instruction], &[], Hash::new_unique()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sign_with_remote_and_local_signers() {
+        let keypair = Keypair::new();
+        let remote = MockRemoteSigner { pubkey: Signer::pubkey(&keypair), keypair };
+        let local = Keypair::new();
+
+        let from = AsyncTradeSigner::pubkey(&remote);
+        let to = AsyncTradeSigner::pubkey(&local);
+        // 两个账户都需要签名，分别由远程和本地签名者完成
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(from, true), AccountMeta::new(to, true)],
+        );
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&from, &[instruction], &[], Hash::new_unique()).unwrap(),
+        );
+
+        let transaction = sign_versioned_message(message, &[&local, &remote]).await.unwrap();
+        assert_eq!(transaction.signatures.len(), 2);
+        assert!(transaction.verify_with_results().into_iter().all(|ok| ok));
+    }
+
+    #[tokio::test]
+    async fn test_sign_fails_without_required_signer() {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let message = transfer_message(&Signer::pubkey(&payer), &Pubkey::new_unique());
+
+        let result = sign_versioned_message(message, &[&other]).await;
+        assert!(result.is_err());
+    }
+}
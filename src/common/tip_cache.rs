@@ -1,38 +1,43 @@
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::Mutex;
 
-/// TipCache 单例，用于存储和管理 tip 金额
+use super::Lamports;
+
+/// 默认 tip 金额，0.001 SOL
+const DEFAULT_TIP: Lamports = Lamports::new(1_000_000);
+
+/// TipCache 用于存储和管理 tip 金额，由 `TradeContext` 持有
 pub struct TipCache {
     /// tip 金额
-    tip_amount: Mutex<f64>,
+    tip_amount: Mutex<Lamports>,
 }
 
-static TIP_CACHE: OnceLock<Arc<TipCache>> = OnceLock::new();
+impl Default for TipCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl TipCache {
-    /// 获取 TipCache 单例实例
-    pub fn get_instance() -> Arc<TipCache> {
-        TIP_CACHE
-            .get_or_init(|| {
-                Arc::new(TipCache {
-                    tip_amount: Mutex::new(0.001),
-                })
-            })
-            .clone()
+    /// 创建使用默认 tip 金额的缓存
+    pub fn new() -> Self {
+        Self {
+            tip_amount: Mutex::new(DEFAULT_TIP),
+        }
     }
 
     /// 初始化 tip 金额
-    pub fn init(&self, tip_amount: Option<f64>) {
-        let amount = tip_amount.unwrap_or(0.001);
+    pub fn init(&self, tip_amount: Option<Lamports>) {
+        let amount = tip_amount.unwrap_or(DEFAULT_TIP);
         self.update_tip(amount);
     }
 
     /// 获取 tip 金额
-    pub fn get_tip(&self) -> f64 {
+    pub fn get_tip(&self) -> Lamports {
         *self.tip_amount.lock().unwrap()
     }
 
     /// 更新 tip 金额
-    pub fn update_tip(&self, amount: f64) {
+    pub fn update_tip(&self, amount: Lamports) {
         *self.tip_amount.lock().unwrap() = amount;
     }
 }
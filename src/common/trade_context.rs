@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use super::{
+    address_lookup_cache::AddressLookupTableCache, nonce_cache::NonceCache, tip_cache::TipCache,
+};
+
+/// 交易运行时状态
+///
+/// nonce、tip 和地址查找表缓存都归属于某个 `SolanaTrade` 实例，
+/// 同一进程中的多个实例互不影响。克隆只复制 `Arc`，克隆后共享同一份状态。
+#[derive(Clone, Default)]
+pub struct TradeContext {
+    pub nonce_cache: Arc<NonceCache>,
+    pub tip_cache: Arc<TipCache>,
+    pub lookup_table_cache: Arc<AddressLookupTableCache>,
+}
+
+impl TradeContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_contexts_do_not_share_state() {
+        let a = TradeContext::new();
+        let b = TradeContext::new();
+        let nonce_account = Pubkey::new_unique();
+
+        a.nonce_cache.init(Some(nonce_account.to_string()));
+        a.lookup_table_cache.add_or_update_table(nonce_account, None, None);
+
+        assert_eq!(a.nonce_cache.get_nonce_info().nonce_account, Some(nonce_account));
+        assert!(b.nonce_cache.get_nonce_info().nonce_account.is_none());
+        assert!(!b.lookup_table_cache.table_exists(&nonce_account));
+
+        // 克隆共享同一份状态
+        let shared = a.clone();
+        shared.nonce_cache.mark_used();
+        assert!(a.nonce_cache.get_nonce_info().used);
+    }
+}
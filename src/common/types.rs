@@ -1,12 +1,15 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use crate::{
+    constants::swqos::SWQOS_KEEPALIVE_INTERVAL,
     constants::trade::trade::{
         DEFAULT_BUY_TIP_FEE, DEFAULT_COMPUTE_UNIT_LIMIT, DEFAULT_COMPUTE_UNIT_PRICE,
         DEFAULT_RPC_UNIT_LIMIT, DEFAULT_RPC_UNIT_PRICE, DEFAULT_SELL_TIP_FEE,
     },
-    swqos::{SwqosClient, SwqosConfig},
+    swqos::{RateLimitConfig, SwqosClient, SwqosConfig, SwqosType},
 };
+
+use super::Lamports;
 use serde::Deserialize;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair};
@@ -18,6 +21,12 @@ pub struct TradeConfig {
     pub priority_fee: PriorityFee,
     pub commitment: CommitmentConfig,
     pub lookup_table_key: Option<Pubkey>,
+    /// 命名的服务商分组，交易时通过 `SolanaTrade::with_swqos_group` 选择
+    pub swqos_groups: HashMap<String, Vec<SwqosType>>,
+    /// 按服务商类型配置的限流，未配置的服务商不限流
+    pub rate_limits: HashMap<SwqosType, RateLimitConfig>,
+    /// 发送线程定期保活的间隔，为空时不保活
+    pub keepalive_interval: Option<Duration>,
 }
 
 impl TradeConfig {
@@ -34,8 +43,42 @@ impl TradeConfig {
             priority_fee,
             commitment,
             lookup_table_key,
+            swqos_groups: HashMap::new(),
+            rate_limits: HashMap::new(),
+            keepalive_interval: Some(SWQOS_KEEPALIVE_INTERVAL),
         }
     }
+
+    /// 添加命名的服务商分组，例如 "rpc_only" 或 "snipe"
+    pub fn with_swqos_group(
+        mut self,
+        name: impl Into<String>,
+        swqos_types: Vec<SwqosType>,
+    ) -> Self {
+        self.swqos_groups.insert(name.into(), swqos_types);
+        self
+    }
+
+    /// 为服务商配置限流，例如 `RateLimitConfig::for_tier(SwqosTier::Free)`
+    pub fn with_rate_limit(mut self, swqos_type: SwqosType, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limits.insert(swqos_type, rate_limit);
+        self
+    }
+
+    /// 设置保活间隔，传入 `None` 关闭保活
+    pub fn with_keepalive_interval(mut self, keepalive_interval: Option<Duration>) -> Self {
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+}
+
+/// 单个服务商的费用策略，未设置的字段沿用 `PriorityFee` 中的全局值
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+pub struct SwqosFeePolicy {
+    pub buy_tip_fee: Option<Lamports>,
+    pub sell_tip_fee: Option<Lamports>,
+    pub unit_price: Option<u64>,
+    pub unit_limit: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -44,10 +87,12 @@ pub struct PriorityFee {
     pub unit_price: u64,
     pub rpc_unit_limit: u32,
     pub rpc_unit_price: u64,
-    pub buy_tip_fee: f64,
-    pub buy_tip_fees: Vec<f64>,
-    pub smart_buy_tip_fee: f64,
-    pub sell_tip_fee: f64,
+    pub buy_tip_fee: Lamports,
+    pub smart_buy_tip_fee: Lamports,
+    pub sell_tip_fee: Lamports,
+    /// 按服务商类型覆盖小费和 CU 价格，买入和卖出都会生效
+    #[serde(default)]
+    pub swqos_fee_policies: HashMap<SwqosType, SwqosFeePolicy>,
 }
 
 impl Default for PriorityFee {
@@ -58,9 +103,55 @@ impl Default for PriorityFee {
             rpc_unit_limit: DEFAULT_RPC_UNIT_LIMIT,
             rpc_unit_price: DEFAULT_RPC_UNIT_PRICE,
             buy_tip_fee: DEFAULT_BUY_TIP_FEE,
-            buy_tip_fees: vec![],
-            smart_buy_tip_fee: 0.0,
+            smart_buy_tip_fee: Lamports::ZERO,
             sell_tip_fee: DEFAULT_SELL_TIP_FEE,
+            swqos_fee_policies: HashMap::new(),
+        }
+    }
+}
+
+impl PriorityFee {
+    pub fn with_fee_policy(mut self, swqos_type: SwqosType, policy: SwqosFeePolicy) -> Self {
+        self.swqos_fee_policies.insert(swqos_type, policy);
+        self
+    }
+
+    /// 合并指定服务商的费用策略，得到该服务商实际使用的费用
+    pub fn for_swqos(&self, swqos_type: SwqosType) -> PriorityFee {
+        let mut priority_fee = self.clone();
+        if let Some(policy) = self.swqos_fee_policies.get(&swqos_type) {
+            if let Some(buy_tip_fee) = policy.buy_tip_fee {
+                priority_fee.buy_tip_fee = buy_tip_fee;
+            }
+            if let Some(sell_tip_fee) = policy.sell_tip_fee {
+                priority_fee.sell_tip_fee = sell_tip_fee;
+            }
+            // RPC 交易使用 rpc_unit_*，其余使用 unit_*，这里两者一起覆盖
+            if let Some(unit_price) = policy.unit_price {
+                priority_fee.unit_price = unit_price;
+                priority_fee.rpc_unit_price = unit_price;
+            }
+            if let Some(unit_limit) = policy.unit_limit {
+                priority_fee.unit_limit = unit_limit;
+                priority_fee.rpc_unit_limit = unit_limit;
+            }
+        }
+        priority_fee
+    }
+
+    /// 所有服务商统一使用指定的买入小费
+    pub fn override_buy_tip_fee(&mut self, buy_tip_fee: Lamports) {
+        self.buy_tip_fee = buy_tip_fee;
+        for policy in self.swqos_fee_policies.values_mut() {
+            policy.buy_tip_fee = None;
+        }
+    }
+
+    /// 所有服务商统一使用指定的卖出小费
+    pub fn override_sell_tip_fee(&mut self, sell_tip_fee: Lamports) {
+        self.sell_tip_fee = sell_tip_fee;
+        for policy in self.swqos_fee_policies.values_mut() {
+            policy.sell_tip_fee = None;
         }
     }
 }
@@ -0,0 +1,274 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// base58 字母表，不包含 0、O、I、l
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// 靓号地址匹配规则
+#[derive(Clone, Debug, Default)]
+pub struct VanityPattern {
+    pub prefix: String,
+    pub suffix: String,
+    pub case_sensitive: bool,
+}
+
+impl VanityPattern {
+    /// 创建匹配规则，前后缀中出现 base58 以外的字符时返回错误
+    pub fn new(
+        prefix: impl Into<String>,
+        suffix: impl Into<String>,
+        case_sensitive: bool,
+    ) -> Result<Self> {
+        let pattern = Self { prefix: prefix.into(), suffix: suffix.into(), case_sensitive };
+        if pattern.prefix.is_empty() && pattern.suffix.is_empty() {
+            return Err(anyhow!("Vanity pattern needs a prefix or a suffix"));
+        }
+        for c in pattern.prefix.chars().chain(pattern.suffix.chars()) {
+            let valid = if case_sensitive {
+                BASE58_ALPHABET.contains(c)
+            } else {
+                BASE58_ALPHABET.contains(c.to_ascii_lowercase())
+                    || BASE58_ALPHABET.contains(c.to_ascii_uppercase())
+            };
+            if !valid {
+                return Err(anyhow!("'{}' can never appear in a base58 address", c));
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// 地址是否符合规则
+    pub fn matches(&self, address: &str) -> bool {
+        if self.case_sensitive {
+            return address.starts_with(&self.prefix) && address.ends_with(&self.suffix);
+        }
+        let len = address.len();
+        len >= self.prefix.len() + self.suffix.len()
+            && address[..self.prefix.len()].eq_ignore_ascii_case(&self.prefix)
+            && address[len - self.suffix.len()..].eq_ignore_ascii_case(&self.suffix)
+    }
+}
+
+/// 多线程生成符合规则的密钥对，只使用 CPU
+///
+/// 找到 `count` 个或 `cancel` 被置位时返回，取消时返回已找到的部分。
+/// `threads` 为 0 时使用全部 CPU 核心。
+pub fn grind_vanity_keypairs(
+    pattern: &VanityPattern,
+    count: usize,
+    threads: usize,
+    cancel: &AtomicBool,
+    show_progress: bool,
+) -> Vec<Keypair> {
+    let threads = if threads == 0 {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    } else {
+        threads
+    };
+    let found = Mutex::new(Vec::with_capacity(count));
+    let attempts = AtomicU64::new(0);
+    let done = AtomicBool::new(count == 0);
+
+    let progress = if show_progress {
+        let progress = ProgressBar::new_spinner();
+        progress.set_style(
+            ProgressStyle::with_template("{spinner} [{elapsed_precise}] {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        progress.enable_steady_tick(Duration::from_millis(100));
+        Some(progress)
+    } else {
+        None
+    };
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) && !cancel.load(Ordering::Relaxed) {
+                    let keypair = Keypair::new();
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    if !pattern.matches(&keypair.pubkey().to_string()) {
+                        continue;
+                    }
+                    let mut found = found.lock().unwrap();
+                    if found.len() < count {
+                        found.push(keypair);
+                    }
+                    if found.len() >= count {
+                        done.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+
+        // 主线程负责刷新进度
+        let started = Instant::now();
+        while !done.load(Ordering::Relaxed) && !cancel.load(Ordering::Relaxed) {
+            if let Some(progress) = &progress {
+                let attempts = attempts.load(Ordering::Relaxed);
+                let rate = attempts as f64 / started.elapsed().as_secs_f64().max(0.001);
+                progress.set_message(format!(
+                    "已尝试 {} 个，找到 {}/{}，{:.0}/s",
+                    attempts,
+                    found.lock().unwrap().len(),
+                    count,
+                    rate
+                ));
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    });
+
+    if let Some(progress) = progress {
+        progress.finish_and_clear();
+    }
+    found.into_inner().unwrap()
+}
+
+/// 预先生成并保存到磁盘的靓号密钥对池
+///
+/// 文件格式为密钥对字节数组的 JSON 列表，与 `solana-keygen` 的密钥文件一致。
+/// 每次取出都会立即写回文件，同一个密钥对不会被使用两次。
+pub struct VanityKeypairPool {
+    path: PathBuf,
+    keypairs: Mutex<Vec<Keypair>>,
+}
+
+impl VanityKeypairPool {
+    /// 打开密钥对池，文件不存在时创建空池
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let keypairs = if path.exists() {
+            let data: Vec<Vec<u8>> = serde_json::from_slice(&fs::read(&path)?)?;
+            data.iter()
+                .map(|bytes| {
+                    Keypair::try_from(bytes.as_slice())
+                        .map_err(|e| anyhow!("Invalid keypair: {}", e))
+                })
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            vec![]
+        };
+        Ok(Self { path, keypairs: Mutex::new(keypairs) })
+    }
+
+    pub fn len(&self) -> usize {
+        self.keypairs.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 生成密钥对直到池中数量达到 `target_len`，返回新增数量
+    pub fn fill(
+        &self,
+        pattern: &VanityPattern,
+        target_len: usize,
+        threads: usize,
+        cancel: &AtomicBool,
+        show_progress: bool,
+    ) -> Result<usize> {
+        let missing = target_len.saturating_sub(self.len());
+        let keypairs = grind_vanity_keypairs(pattern, missing, threads, cancel, show_progress);
+        let added = keypairs.len();
+        let mut pool = self.keypairs.lock().unwrap();
+        pool.extend(keypairs);
+        self.save(&pool)?;
+        Ok(added)
+    }
+
+    /// 取出一个密钥对并写回文件
+    pub fn take(&self) -> Result<Option<Keypair>> {
+        let mut pool = self.keypairs.lock().unwrap();
+        let Some(keypair) = pool.pop() else {
+            return Ok(None);
+        };
+        self.save(&pool)?;
+        Ok(Some(keypair))
+    }
+
+    /// 先写临时文件再替换，避免写入中断导致密钥丢失
+    fn save(&self, keypairs: &[Keypair]) -> Result<()> {
+        let data: Vec<Vec<u8>> =
+            keypairs.iter().map(|keypair| keypair.to_bytes().to_vec()).collect();
+        let tmp_path = self.path.with_extension("tmp");
+        write_private_file(&tmp_path, &serde_json::to_vec(&data)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// 写入只有当前用户可读的文件
+fn write_private_file(path: &Path, data: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_matching() {
+        let pattern = VanityPattern::new("", "pump", false).unwrap();
+        assert!(pattern.matches("So1aNaAddressPUMP"));
+        assert!(pattern.matches("So1aNaAddresspump"));
+        let pattern = VanityPattern::new("Ab", "pump", true).unwrap();
+        assert!(pattern.matches("Abcdefpump"));
+        assert!(!pattern.matches("abcdefpump"));
+        assert!(!pattern.matches("Abpum"));
+
+        assert!(VanityPattern::new("0x", "", true).is_err());
+        assert!(VanityPattern::new("", "", false).is_err());
+        // 不区分大小写时 'l' 可以匹配 'L'
+        assert!(VanityPattern::new("l", "", false).is_ok());
+        assert!(VanityPattern::new("l", "", true).is_err());
+    }
+
+    #[test]
+    fn test_grind_and_persist_pool() {
+        let path = std::env::temp_dir().join(format!("vanity-pool-{}.json", std::process::id()));
+        let pattern = VanityPattern::new("", "a", false).unwrap();
+        let cancel = AtomicBool::new(false);
+
+        let pool = VanityKeypairPool::open(&path).unwrap();
+        assert_eq!(pool.fill(&pattern, 2, 2, &cancel, false).unwrap(), 2);
+
+        let reopened = VanityKeypairPool::open(&path).unwrap();
+        assert_eq!(reopened.len(), 2);
+        let keypair = reopened.take().unwrap().unwrap();
+        assert!(pattern.matches(&keypair.pubkey().to_string()));
+        assert_eq!(VanityKeypairPool::open(&path).unwrap().len(), 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_grind_cancelled() {
+        let pattern = VanityPattern::new("zzzzzzzz", "", true).unwrap();
+        let cancel = AtomicBool::new(true);
+        assert!(grind_vanity_keypairs(&pattern, 1, 1, &cancel, false).is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use super::{AsyncTradeSigner, Lamports, SolanaRpcClient};
+use crate::trading::common::utils::{get_sol_balance, get_token_balance};
+
+/// 钱包池中的单个钱包及其余额缓存
+struct PoolWallet {
+    signer: Arc<dyn AsyncTradeSigner>,
+    pubkey: Pubkey,
+    sol_balance: Mutex<Option<Lamports>>,
+    token_balances: Mutex<HashMap<Pubkey, u64>>,
+}
+
+/// 多钱包管理
+///
+/// 持有同一策略下的多个签名者，支持指定钱包、轮询选择和拆单。
+/// 余额缓存只在 `refresh_*` 或 `set_*` 时更新，交易后会被清空，需要时重新刷新。
+pub struct WalletPool {
+    wallets: Vec<PoolWallet>,
+    next: AtomicUsize,
+}
+
+impl Default for WalletPool {
+    fn default() -> Self {
+        Self::new(vec![])
+    }
+}
+
+impl WalletPool {
+    pub fn new(signers: Vec<Arc<dyn AsyncTradeSigner>>) -> Self {
+        let wallets = signers
+            .into_iter()
+            .map(|signer| PoolWallet {
+                pubkey: signer.pubkey(),
+                signer,
+                sol_balance: Mutex::new(None),
+                token_balances: Mutex::new(HashMap::new()),
+            })
+            .collect();
+        Self { wallets, next: AtomicUsize::new(0) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.wallets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    /// 所有钱包地址，顺序与加入时一致
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.wallets.iter().map(|wallet| wallet.pubkey).collect()
+    }
+
+    /// 按序号获取钱包
+    pub fn get(&self, index: usize) -> Option<Arc<dyn AsyncTradeSigner>> {
+        self.wallets.get(index).map(|wallet| wallet.signer.clone())
+    }
+
+    /// 按地址获取钱包
+    pub fn find(&self, pubkey: &Pubkey) -> Option<Arc<dyn AsyncTradeSigner>> {
+        self.wallet(pubkey).map(|wallet| wallet.signer.clone())
+    }
+
+    /// 轮询获取下一个钱包
+    pub fn next_wallet(&self) -> Option<Arc<dyn AsyncTradeSigner>> {
+        if self.wallets.is_empty() {
+            return None;
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.wallets.len();
+        self.get(index)
+    }
+
+    /// 缓存的 SOL 余额，未刷新过时为空
+    pub fn cached_sol_balance(&self, pubkey: &Pubkey) -> Option<Lamports> {
+        self.wallet(pubkey).and_then(|wallet| *wallet.sol_balance.lock().unwrap())
+    }
+
+    /// 缓存的代币余额，未刷新过时为空
+    pub fn cached_token_balance(&self, pubkey: &Pubkey, mint: &Pubkey) -> Option<u64> {
+        self.wallet(pubkey)
+            .and_then(|wallet| wallet.token_balances.lock().unwrap().get(mint).copied())
+    }
+
+    pub fn set_sol_balance(&self, pubkey: &Pubkey, balance: Lamports) {
+        if let Some(wallet) = self.wallet(pubkey) {
+            *wallet.sol_balance.lock().unwrap() = Some(balance);
+        }
+    }
+
+    pub fn set_token_balance(&self, pubkey: &Pubkey, mint: &Pubkey, balance: u64) {
+        if let Some(wallet) = self.wallet(pubkey) {
+            wallet.token_balances.lock().unwrap().insert(*mint, balance);
+        }
+    }
+
+    /// 清空指定钱包的余额缓存，交易或转账后调用
+    pub fn invalidate(&self, pubkey: &Pubkey) {
+        if let Some(wallet) = self.wallet(pubkey) {
+            *wallet.sol_balance.lock().unwrap() = None;
+            wallet.token_balances.lock().unwrap().clear();
+        }
+    }
+
+    /// 从链上刷新所有钱包的 SOL 余额
+    pub async fn refresh_sol_balances(&self, rpc: &SolanaRpcClient) -> Result<()> {
+        for wallet in &self.wallets {
+            let balance = get_sol_balance(rpc, &wallet.pubkey).await?;
+            *wallet.sol_balance.lock().unwrap() = Some(Lamports::new(balance));
+        }
+        Ok(())
+    }
+
+    /// 从链上刷新所有钱包指定代币的余额，没有代币账户时记为 0
+    pub async fn refresh_token_balances(&self, rpc: &SolanaRpcClient, mint: &Pubkey) {
+        for wallet in &self.wallets {
+            let balance = get_token_balance(rpc, &wallet.pubkey, mint).await.unwrap_or(0);
+            wallet.token_balances.lock().unwrap().insert(*mint, balance);
+        }
+    }
+
+    fn wallet(&self, pubkey: &Pubkey) -> Option<&PoolWallet> {
+        self.wallets.iter().find(|wallet| wallet.pubkey == *pubkey)
+    }
+}
+
+/// 将总金额平均拆分为 `parts` 份，余数依次分给前面的份额
+pub fn split_amount(total: Lamports, parts: usize) -> Result<Vec<Lamports>> {
+    if parts == 0 {
+        return Err(anyhow!("Cannot split an amount into zero parts"));
+    }
+    let base = total.as_u64() / parts as u64;
+    let remainder = (total.as_u64() % parts as u64) as usize;
+    if base == 0 {
+        return Err(anyhow!("Amount {} is too small to split into {} parts", total, parts));
+    }
+    Ok((0..parts).map(|i| Lamports::new(base + if i < remainder { 1 } else { 0 })).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+
+    fn pool(size: usize) -> WalletPool {
+        WalletPool::new(
+            (0..size).map(|_| Arc::new(Keypair::new()) as Arc<dyn AsyncTradeSigner>).collect(),
+        )
+    }
+
+    #[test]
+    fn test_round_robin_wraps() {
+        let pool = pool(3);
+        let pubkeys = pool.pubkeys();
+        let picked: Vec<Pubkey> = (0..4).map(|_| pool.next_wallet().unwrap().pubkey()).collect();
+        assert_eq!(picked, vec![pubkeys[0], pubkeys[1], pubkeys[2], pubkeys[0]]);
+        assert!(WalletPool::default().next_wallet().is_none());
+    }
+
+    #[test]
+    fn test_balance_cache_invalidate() {
+        let pool = pool(2);
+        let wallet = pool.pubkeys()[1];
+        let mint = Pubkey::new_unique();
+        pool.set_sol_balance(&wallet, Lamports::new(10));
+        pool.set_token_balance(&wallet, &mint, 5);
+        assert_eq!(pool.cached_sol_balance(&wallet), Some(Lamports::new(10)));
+        assert_eq!(pool.cached_token_balance(&wallet, &mint), Some(5));
+
+        pool.invalidate(&wallet);
+        assert_eq!(pool.cached_sol_balance(&wallet), None);
+        assert_eq!(pool.cached_token_balance(&wallet, &mint), None);
+    }
+
+    #[test]
+    fn test_split_amount() {
+        let parts = split_amount(Lamports::new(10), 3).unwrap();
+        assert_eq!(parts, vec![Lamports::new(4), Lamports::new(3), Lamports::new(3)]);
+        assert_eq!(parts.iter().map(|p| p.as_u64()).sum::<u64>(), 10);
+        assert!(split_amount(Lamports::new(2), 3).is_err());
+        assert!(split_amount(Lamports::new(2), 0).is_err());
+    }
+}
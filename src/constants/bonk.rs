@@ -27,6 +27,9 @@ pub mod accounts {
     pub const EVENT_AUTHORITY: Pubkey = pubkey!("2DPAtwB8L12vrMRExbLuyGnC7n2J5LNoZQSejeQGpwkr");
     pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
     pub const BONK: Pubkey = pubkey!("LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj");
+    pub const METADATA_PROGRAM: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+    pub const SYSTEM_PROGRAM: Pubkey = pubkey!("11111111111111111111111111111111");
+    pub const RENT: Pubkey = pubkey!("SysvarRent111111111111111111111111111111111");
 
     pub const PLATFORM_FEE_RATE: u128 = 100; // 1%
     pub const PROTOCOL_FEE_RATE: u128 = 25; // 0.25%
@@ -35,3 +38,16 @@ pub mod accounts {
 
 pub const BUY_EXECT_IN_DISCRIMINATOR: [u8; 8] = [250, 234, 13, 123, 213, 156, 19, 236];
 pub const SELL_EXECT_IN_DISCRIMINATOR: [u8; 8] = [149, 39, 222, 155, 211, 124, 152, 26];
+pub const BUY_EXACT_OUT_DISCRIMINATOR: [u8; 8] = [24, 211, 116, 40, 105, 3, 153, 56];
+pub const SELL_EXACT_OUT_DISCRIMINATOR: [u8; 8] = [95, 200, 71, 34, 8, 9, 11, 166];
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+/// Default launch parameters, same as letsbonk
+pub mod launch {
+    pub const DEFAULT_DECIMALS: u8 = 6;
+    pub const DEFAULT_SUPPLY: u64 = 1_000_000_000_000_000;
+    pub const DEFAULT_TOTAL_BASE_SELL: u64 = 793_100_000_000_000;
+    pub const DEFAULT_TOTAL_QUOTE_FUND_RAISING: u64 = 85_000_000_000;
+    /// 0 = Raydium AMM v4, 1 = Raydium CPMM
+    pub const DEFAULT_MIGRATE_TYPE: u8 = 1;
+}
@@ -1,6 +1,8 @@
 pub mod bonk;
 pub mod pumpfun;
 pub mod pumpswap;
+pub mod raydium_amm_v4;
+pub mod raydium_clmm;
 pub mod raydium_cpmm;
 pub mod swqos;
 pub mod trade;
@@ -10,4 +12,6 @@ pub mod trade_platform {
     pub const PUMPFUN_SWAP: &'static str = "pumpswap";
     pub const BONK: &'static str = "bonk";
     pub const RAYDIUM_CPMM: &'static str = "raydium_cpmm";
+    pub const RAYDIUM_AMM_V4: &'static str = "raydium_amm_v4";
+    pub const RAYDIUM_CLMM: &'static str = "raydium_clmm";
 }
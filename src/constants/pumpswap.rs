@@ -26,6 +26,12 @@ pub mod seeds {
 
     pub const USER_VOLUME_ACCUMULATOR_SEED: &[u8] = b"user_volume_accumulator";
     pub const GLOBAL_VOLUME_ACCUMULATOR_SEED: &[u8] = b"global_volume_accumulator";
+
+    /// Seed for PumpSwap pool PDAs
+    pub const POOL_SEED: &[u8] = b"pool";
+
+    /// Seed for the LP mint PDA of a pool
+    pub const POOL_LP_MINT_SEED: &[u8] = b"pool_lp_mint";
 }
 
 /// Constants related to program accounts and authorities
@@ -49,6 +55,9 @@ pub mod accounts {
     /// Token Program ID
     pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
 
+    /// Token-2022 Program ID, owner of the pool LP mints
+    pub const TOKEN_2022_PROGRAM: Pubkey = spl_token_2022::ID;
+
     /// Associated Token Program ID
     pub const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
         pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
@@ -70,3 +79,6 @@ pub mod accounts {
 
 pub const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
 pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+pub const CREATE_POOL_DISCRIMINATOR: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
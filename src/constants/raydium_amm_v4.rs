@@ -0,0 +1,31 @@
+//! Constants used by the crate.
+//!
+//! This module contains various constants used throughout the crate, including:
+//!
+//! - Seeds for deriving Program Derived Addresses (PDAs)
+//! - Program account addresses and public keys
+//!
+//! The constants are organized into submodules for better organization:
+//!
+//! - `seeds`: Contains seed values used for PDA derivation
+//! - `accounts`: Contains important program account addresses
+
+/// Constants used as seeds for deriving PDAs (Program Derived Addresses)
+pub mod seeds {
+    pub const AMM_AUTHORITY_SEED: &[u8] = b"amm authority";
+}
+
+/// Constants related to program accounts and authorities
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+    pub const RAYDIUM_AMM_V4: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+    pub const AUTHORITY: Pubkey = pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    /// OpenBook 市场程序，AMM v4 池子绑定的市场都属于它
+    pub const OPENBOOK_PROGRAM: Pubkey = pubkey!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+    pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
+    pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+}
+
+/// AMM v4 不是 Anchor 程序，指令数据以 1 字节的 tag 开头
+pub const SWAP_BASE_IN_TAG: u8 = 9;
+pub const SWAP_BASE_OUT_TAG: u8 = 11;
@@ -15,6 +15,8 @@ pub mod seeds {
     pub const POOL_SEED: &[u8] = b"pool";
     pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
     pub const OBSERVATION_STATE_SEED: &[u8] = b"observation";
+    pub const POOL_LP_MINT_SEED: &[u8] = b"pool_lp_mint";
+    pub const AMM_CONFIG_SEED: &[u8] = b"amm_config";
 }
 
 /// Constants related to program accounts and authorities
@@ -23,9 +25,24 @@ pub mod accounts {
     pub const AUTHORITY: Pubkey = pubkey!("GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL");
     pub const AMM_CONFIG: Pubkey = pubkey!("D4FPEruKEHrG5TenZ2mpDGEfu1iUvTiqBxvpU8HLBvC2");
     pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
+    pub const TOKEN_2022_PROGRAM: Pubkey = spl_token_2022::ID;
+    pub const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
+        pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+    pub const SYSTEM_PROGRAM: Pubkey = pubkey!("11111111111111111111111111111111");
+    pub const RENT: Pubkey = pubkey!("SysvarRent111111111111111111111111111111111");
+    pub const MEMO_PROGRAM: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+    /// 创建池子时收取手续费的账户
+    pub const CREATE_POOL_FEE_RECEIVER: Pubkey =
+        pubkey!("DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8");
     pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
     pub const RAYDIUM_CPMM: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
 }
 
 pub const SWAP_BASE_IN_DISCRIMINATOR: &[u8] = &[143, 190, 90, 218, 196, 30, 51, 222];
 pub const SWAP_BASE_OUT_DISCRIMINATOR: &[u8] = &[55, 217, 98, 86, 163, 74, 180, 173];
+pub const INITIALIZE_DISCRIMINATOR: &[u8] = &[175, 175, 109, 31, 13, 152, 155, 237];
+pub const DEPOSIT_DISCRIMINATOR: &[u8] = &[242, 35, 198, 137, 82, 225, 242, 182];
+pub const WITHDRAW_DISCRIMINATOR: &[u8] = &[183, 18, 70, 156, 148, 109, 161, 34];
+
+/// 创建池子时永久锁定的 LP 数量
+pub const LOCK_LP_AMOUNT: u64 = 100;
@@ -1,9 +1,12 @@
 pub mod trade {
+    use crate::common::Lamports;
+
     pub const DEFAULT_SLIPPAGE: u64 = 1000; // 10%
     pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 78000;
     pub const DEFAULT_COMPUTE_UNIT_PRICE: u64 = 500000;
-    pub const DEFAULT_BUY_TIP_FEE: f64 = 0.0006;
-    pub const DEFAULT_SELL_TIP_FEE: f64 = 0.0001;
+    pub const DEFAULT_BUY_TIP_FEE: Lamports = Lamports::new(600_000); // 0.0006 SOL
+    pub const DEFAULT_SELL_TIP_FEE: Lamports = Lamports::new(100_000); // 0.0001 SOL
     pub const DEFAULT_RPC_UNIT_LIMIT: u32 = 78000;
     pub const DEFAULT_RPC_UNIT_PRICE: u64 = 500000;
+    pub const SIGNATURE_FEE: Lamports = Lamports::new(5_000); // 每个签名的基础手续费
 }
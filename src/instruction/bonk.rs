@@ -1,21 +1,33 @@
 use anyhow::{anyhow, Result};
-use solana_sdk::{instruction::Instruction, signer::Signer};
+use borsh::BorshSerialize;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 use solana_system_interface::instruction::transfer;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use spl_token::instruction::close_account;
 
 use crate::{
-    constants::bonk::{accounts, BUY_EXECT_IN_DISCRIMINATOR, SELL_EXECT_IN_DISCRIMINATOR},
+    common::SolanaRpcClient,
+    constants::bonk::{
+        accounts, BUY_EXACT_OUT_DISCRIMINATOR, BUY_EXECT_IN_DISCRIMINATOR,
+        INITIALIZE_DISCRIMINATOR, SELL_EXACT_OUT_DISCRIMINATOR, SELL_EXECT_IN_DISCRIMINATOR,
+    },
     constants::trade::trade::DEFAULT_SLIPPAGE,
     trading::bonk::{
-        common::{get_amount_out, get_pool_pda, get_vault_pda},
-        pool::Pool,
+        common::{
+            get_amount_in, get_amount_out, get_initial_reserves, get_pool_pda, get_sell_amount_in,
+            get_vault_pda,
+        },
+        pool::{CurveParams, MintParams, Pool, VestingParams},
     },
     trading::common::utils::get_token_balance,
     trading::core::{
-        params::{BonkParams, BuyParams, SellParams},
+        params::{BonkCreateParams, BonkParams, BuyParams, CreateParams, SellParams, SwapMode},
         traits::InstructionBuilder,
     },
+    trading::pumpfun::common::get_metadata_pda,
 };
 
 /// Bonk协议的指令构建器
@@ -24,7 +36,7 @@ pub struct BonkInstructionBuilder;
 #[async_trait::async_trait]
 impl InstructionBuilder for BonkInstructionBuilder {
     async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
-        if params.sol_amount == 0 {
+        if params.sol_amount.is_zero() {
             return Err(anyhow!("Amount cannot be zero"));
         }
         self.build_buy_instructions_with_accounts(params).await
@@ -33,142 +45,143 @@ impl InstructionBuilder for BonkInstructionBuilder {
     async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
         self.build_sell_instructions_with_accounts(params).await
     }
-}
 
-impl BonkInstructionBuilder {
-    /// 使用提供的账户信息构建买入指令
-    async fn build_buy_instructions_with_accounts(
-        &self,
-        params: &BuyParams,
-    ) -> Result<Vec<Instruction>> {
+    async fn build_create_instructions(&self, params: &CreateParams) -> Result<Vec<Instruction>> {
         let protocol_params = params
             .protocol_params
             .as_any()
-            .downcast_ref::<BonkParams>()
-            .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
-
-        let pool_state = get_pool_pda(&params.mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
-
-        // 创建用户代币账户
-        let user_base_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &params.mint,
-        );
-        let user_quote_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &accounts::WSOL_TOKEN_ACCOUNT,
-        );
-
-        // 获取池的代币账户
-        let base_vault_account = get_vault_pda(&pool_state, &params.mint).unwrap();
-        let quote_vault_account =
-            get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
-
-        let mut virtual_base = protocol_params.virtual_base.unwrap_or(0);
-        let mut virtual_quote = protocol_params.virtual_quote.unwrap_or(0);
-        let mut real_base = protocol_params.real_base.unwrap_or(0);
-        let mut real_quote = protocol_params.real_quote.unwrap_or(0);
-
-        if virtual_base == 0 || virtual_quote == 0 || real_base == 0 || real_quote == 0 {
-            let pool = Pool::fetch(params.rpc.as_ref().unwrap(), &pool_state).await?;
-            virtual_base = pool.virtual_base as u128;
-            virtual_quote = pool.virtual_quote as u128;
-            real_base = pool.real_base as u128;
-            real_quote = pool.real_quote as u128;
+            .downcast_ref::<BonkCreateParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for Bonk create"))?;
+        let payer = params.payer.pubkey();
+        let mint = params.mint.pubkey();
+
+        let mut instructions = vec![initialize(
+            &payer,
+            &params.creator,
+            &mint,
+            &protocol_params.platform_config,
+            Initialize {
+                mint_params: MintParams {
+                    decimals: protocol_params.decimals,
+                    name: params.metadata.name.clone(),
+                    symbol: params.metadata.symbol.clone(),
+                    uri: params.metadata.uri.clone(),
+                },
+                curve_params: protocol_params.curve.clone(),
+                vesting_params: protocol_params.vesting.clone(),
+            },
+        )?];
+        if params.dev_buy_sol_amount.is_zero() {
+            return Ok(instructions);
         }
 
-        let amount_in: u64 = params.sol_amount;
-        let share_fee_rate: u64 = 0;
-        let minimum_amount_out: u64 = get_amount_out(
+        // 初始买入按刚初始化的曲线计算，此时实际储备为 0
+        let CurveParams::Constant { supply, total_base_sell, total_quote_fund_raising, .. } =
+            protocol_params.curve
+        else {
+            return Err(anyhow!("Initial buy is only supported on the constant curve"));
+        };
+        let (virtual_base, virtual_quote) = get_initial_reserves(
+            supply,
+            total_base_sell,
+            total_quote_fund_raising,
+            protocol_params.vesting.total_locked_amount,
+        )
+        .ok_or_else(|| anyhow!("Invalid curve params"))?;
+        let amount_in = params.dev_buy_sol_amount.as_u64();
+        let minimum_amount_out = get_amount_out(
             amount_in,
             accounts::PROTOCOL_FEE_RATE,
             accounts::PLATFORM_FEE_RATE,
             accounts::SHARE_FEE_RATE,
             virtual_base,
             virtual_quote,
-            real_base,
-            real_quote,
+            0,
+            0,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
         );
+        instructions.extend(buy_instructions(
+            &payer,
+            &mint,
+            &protocol_params.platform_config,
+            SwapMode::ExactIn,
+            amount_in,
+            minimum_amount_out,
+            protocol_params.auto_handle_wsol,
+        ));
 
-        let mut instructions = vec![];
-
-        if protocol_params.auto_handle_wsol {
-            // 插入wsol
-            instructions.push(
-                // 创建wSOL ATA账户，如果不存在
-                create_associated_token_account_idempotent(
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &accounts::WSOL_TOKEN_ACCOUNT,
-                    &accounts::TOKEN_PROGRAM,
-                ),
-            );
-            instructions.push(
-                // 将SOL转入wSOL ATA账户
-                transfer(&params.payer.pubkey(), &user_quote_token_account, amount_in),
-            );
+        Ok(instructions)
+    }
+}
 
-            // 同步wSOL余额
-            instructions.push(
-                spl_token::instruction::sync_native(
-                    &accounts::TOKEN_PROGRAM,
-                    &user_quote_token_account,
-                )
-                .unwrap(),
-            );
-        }
+impl BonkInstructionBuilder {
+    /// 使用提供的账户信息构建买入指令
+    async fn build_buy_instructions_with_accounts(
+        &self,
+        params: &BuyParams,
+    ) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<BonkParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
 
-        // 创建用户的基础代币账户
-        instructions.push(create_associated_token_account_idempotent(
-            &params.payer.pubkey(),
+        let (virtual_base, virtual_quote, real_base, real_quote) =
+            get_reserves(params.rpc.as_deref(), protocol_params, &params.mint).await?;
+
+        let amount_in: u64 = params.sol_amount.as_u64();
+        let minimum_amount_out: u64 = match params.swap_mode {
+            SwapMode::ExactIn => get_amount_out(
+                amount_in,
+                accounts::PROTOCOL_FEE_RATE,
+                accounts::PLATFORM_FEE_RATE,
+                accounts::SHARE_FEE_RATE,
+                virtual_base,
+                virtual_quote,
+                real_base,
+                real_quote,
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+            ),
+            SwapMode::ExactOut(amount_out) => {
+                // sol_amount 是最多花费的数量，报价已超过上限时直接返回错误
+                let available = virtual_base
+                    .checked_sub(real_base)
+                    .ok_or_else(|| anyhow!("Pool real base reserve exceeds the virtual reserve"))?;
+                if amount_out as u128 >= available {
+                    return Err(anyhow!("Not enough tokens left in the pool"));
+                }
+                let required = get_amount_in(
+                    amount_out,
+                    accounts::PROTOCOL_FEE_RATE,
+                    accounts::PLATFORM_FEE_RATE,
+                    accounts::SHARE_FEE_RATE,
+                    virtual_base,
+                    virtual_quote,
+                    real_base,
+                    real_quote,
+                    0,
+                );
+                if required > amount_in {
+                    return Err(anyhow!(
+                        "Buying {} tokens costs {} lamports, more than the limit {}",
+                        amount_out,
+                        required,
+                        amount_in
+                    ));
+                }
+                0
+            }
+        };
+
+        Ok(buy_instructions(
             &params.payer.pubkey(),
             &params.mint,
-            &accounts::TOKEN_PROGRAM,
-        ));
-
-        // 创建买入指令
-        let accounts = vec![
-            solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::GLOBAL_CONFIG, false), // Global Config (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::PLATFORM_CONFIG, false), // Platform Config (readonly)
-            solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
-            solana_sdk::instruction::AccountMeta::new(user_base_token_account, false), // User Base Token
-            solana_sdk::instruction::AccountMeta::new(user_quote_token_account, false), // User Quote Token
-            solana_sdk::instruction::AccountMeta::new(base_vault_account, false), // Base Vault
-            solana_sdk::instruction::AccountMeta::new(quote_vault_account, false), // Quote Vault
-            solana_sdk::instruction::AccountMeta::new_readonly(params.mint, false), // Base Token Mint (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false), // Quote Token Mint (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Base Token Program (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Quote Token Program (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // Event Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::BONK, false), // Program (readonly)
-        ];
-        // 创建指令数据
-        let mut data = vec![];
-        data.extend_from_slice(&BUY_EXECT_IN_DISCRIMINATOR);
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-        data.extend_from_slice(&share_fee_rate.to_le_bytes());
-
-        instructions.push(Instruction { program_id: accounts::BONK, accounts, data });
-
-        if protocol_params.auto_handle_wsol {
-            // 关闭wSOL ATA账户，回收租金
-            instructions.push(
-                spl_token::instruction::close_account(
-                    &accounts::TOKEN_PROGRAM,
-                    &user_quote_token_account,
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &[],
-                )
-                .unwrap(),
-            );
-        }
-
-        Ok(instructions)
+            &accounts::PLATFORM_CONFIG,
+            params.swap_mode,
+            amount_in,
+            minimum_amount_out,
+            protocol_params.auto_handle_wsol,
+        ))
     }
 
     /// 使用提供的账户信息构建卖出指令
@@ -194,8 +207,38 @@ impl BonkInstructionBuilder {
             return Err(anyhow!("Amount cannot be zero"));
         }
 
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<BonkParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
+
         // 计算预期的SOL数量
         let minimum_amount_out: u64 = 1;
+        if let SwapMode::ExactOut(amount_out) = params.swap_mode {
+            // amount 是最多卖出的数量，报价已超过上限时直接返回错误
+            let (virtual_base, virtual_quote, real_base, real_quote) =
+                get_reserves(Some(rpc.as_ref()), protocol_params, &params.mint).await?;
+            let required = get_sell_amount_in(
+                amount_out,
+                accounts::PROTOCOL_FEE_RATE,
+                accounts::PLATFORM_FEE_RATE,
+                accounts::SHARE_FEE_RATE,
+                virtual_base,
+                virtual_quote,
+                real_base,
+                real_quote,
+            )
+            .ok_or_else(|| anyhow!("Not enough SOL in the pool"))?;
+            if required > amount {
+                return Err(anyhow!(
+                    "Receiving {} lamports needs {} tokens, more than the limit {}",
+                    amount_out,
+                    required,
+                    amount
+                ));
+            }
+        }
 
         let pool_state = get_pool_pda(&params.mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
 
@@ -214,8 +257,6 @@ impl BonkInstructionBuilder {
         let quote_vault_account =
             get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
 
-        let share_fee_rate: u64 = 0;
-
         let mut instructions = vec![];
 
         // 插入wsol
@@ -249,20 +290,16 @@ impl BonkInstructionBuilder {
         ];
 
         // 创建指令数据
-        let mut data = vec![];
-        data.extend_from_slice(&SELL_EXECT_IN_DISCRIMINATOR);
-        data.extend_from_slice(&amount.to_le_bytes());
-        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-        data.extend_from_slice(&share_fee_rate.to_le_bytes());
+        let data = swap_data(
+            SELL_EXECT_IN_DISCRIMINATOR,
+            SELL_EXACT_OUT_DISCRIMINATOR,
+            params.swap_mode,
+            amount,
+            minimum_amount_out,
+        );
 
         instructions.push(Instruction { program_id: accounts::BONK, accounts, data });
 
-        let protocol_params = params
-            .protocol_params
-            .as_any()
-            .downcast_ref::<BonkParams>()
-            .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
-
         if protocol_params.auto_handle_wsol {
             instructions.push(
                 close_account(
@@ -279,3 +316,275 @@ impl BonkInstructionBuilder {
         Ok(instructions)
     }
 }
+
+/// 取池子的 `(virtual_base, virtual_quote, real_base, real_quote)`，参数中没有时通过RPC查询
+async fn get_reserves(
+    rpc: Option<&SolanaRpcClient>,
+    protocol_params: &BonkParams,
+    mint: &Pubkey,
+) -> Result<(u128, u128, u128, u128)> {
+    let virtual_base = protocol_params.virtual_base.unwrap_or(0);
+    let virtual_quote = protocol_params.virtual_quote.unwrap_or(0);
+    let real_base = protocol_params.real_base.unwrap_or(0);
+    let real_quote = protocol_params.real_quote.unwrap_or(0);
+    if virtual_base != 0 && virtual_quote != 0 && real_base != 0 && real_quote != 0 {
+        return Ok((virtual_base, virtual_quote, real_base, real_quote));
+    }
+
+    let rpc = rpc.ok_or_else(|| anyhow!("RPC is not set"))?;
+    let pool_state = get_pool_pda(mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
+    let pool = Pool::fetch(rpc, &pool_state).await?;
+    Ok((
+        pool.virtual_base as u128,
+        pool.virtual_quote as u128,
+        pool.real_base as u128,
+        pool.real_quote as u128,
+    ))
+}
+
+/// 买卖指令数据，`ExactOut` 时 `amount_in` 作为最大输入，`minimum_amount_out` 不使用
+fn swap_data(
+    exact_in_discriminator: [u8; 8],
+    exact_out_discriminator: [u8; 8],
+    swap_mode: SwapMode,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Vec<u8> {
+    let share_fee_rate: u64 = 0;
+    let mut data = Vec::with_capacity(32);
+    match swap_mode {
+        SwapMode::ExactIn => {
+            data.extend_from_slice(&exact_in_discriminator);
+            data.extend_from_slice(&amount_in.to_le_bytes());
+            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        }
+        SwapMode::ExactOut(amount_out) => {
+            data.extend_from_slice(&exact_out_discriminator);
+            data.extend_from_slice(&amount_out.to_le_bytes());
+            data.extend_from_slice(&amount_in.to_le_bytes());
+        }
+    }
+    data.extend_from_slice(&share_fee_rate.to_le_bytes());
+    data
+}
+
+/// 构建 `buy_exact_in`/`buy_exact_out` 及其前后的 wSOL 处理指令
+///
+/// 转入 wSOL 账户的是 `amount_in`，`ExactOut` 时多余的部分在关闭账户时退回
+fn buy_instructions(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    platform_config: &Pubkey,
+    swap_mode: SwapMode,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    auto_handle_wsol: bool,
+) -> Vec<Instruction> {
+    let pool_state = get_pool_pda(mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
+    let user_base_token_account =
+        spl_associated_token_account::get_associated_token_address(payer, mint);
+    let user_quote_token_account = spl_associated_token_account::get_associated_token_address(
+        payer,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+    );
+    let base_vault_account = get_vault_pda(&pool_state, mint).unwrap();
+    let quote_vault_account = get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
+
+    let mut instructions = vec![];
+
+    if auto_handle_wsol {
+        // 插入wsol
+        instructions.push(
+            // 创建wSOL ATA账户，如果不存在
+            create_associated_token_account_idempotent(
+                payer,
+                payer,
+                &accounts::WSOL_TOKEN_ACCOUNT,
+                &accounts::TOKEN_PROGRAM,
+            ),
+        );
+        instructions.push(
+            // 将SOL转入wSOL ATA账户
+            transfer(payer, &user_quote_token_account, amount_in),
+        );
+
+        // 同步wSOL余额
+        instructions.push(
+            spl_token::instruction::sync_native(
+                &accounts::TOKEN_PROGRAM,
+                &user_quote_token_account,
+            )
+            .unwrap(),
+        );
+    }
+
+    // 创建用户的基础代币账户
+    instructions.push(create_associated_token_account_idempotent(
+        payer,
+        payer,
+        mint,
+        &accounts::TOKEN_PROGRAM,
+    ));
+
+    // 创建买入指令
+    let accounts = vec![
+        solana_sdk::instruction::AccountMeta::new(*payer, true), // Payer (signer)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::GLOBAL_CONFIG, false), // Global Config (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(*platform_config, false), // Platform Config (readonly)
+        solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
+        solana_sdk::instruction::AccountMeta::new(user_base_token_account, false), // User Base Token
+        solana_sdk::instruction::AccountMeta::new(user_quote_token_account, false), // User Quote Token
+        solana_sdk::instruction::AccountMeta::new(base_vault_account, false), // Base Vault
+        solana_sdk::instruction::AccountMeta::new(quote_vault_account, false), // Quote Vault
+        solana_sdk::instruction::AccountMeta::new_readonly(*mint, false), // Base Token Mint (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false), // Quote Token Mint (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Base Token Program (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Quote Token Program (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // Event Authority (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::BONK, false), // Program (readonly)
+    ];
+    // 创建指令数据
+    let data = swap_data(
+        BUY_EXECT_IN_DISCRIMINATOR,
+        BUY_EXACT_OUT_DISCRIMINATOR,
+        swap_mode,
+        amount_in,
+        minimum_amount_out,
+    );
+
+    instructions.push(Instruction { program_id: accounts::BONK, accounts, data });
+
+    if auto_handle_wsol {
+        // 关闭wSOL ATA账户，回收租金
+        instructions.push(
+            spl_token::instruction::close_account(
+                &accounts::TOKEN_PROGRAM,
+                &user_quote_token_account,
+                payer,
+                payer,
+                &[],
+            )
+            .unwrap(),
+        );
+    }
+
+    instructions
+}
+
+/// `initialize` 指令参数
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct Initialize {
+    pub mint_params: MintParams,
+    pub curve_params: CurveParams,
+    pub vesting_params: VestingParams,
+}
+
+impl Initialize {
+    pub fn data(&self) -> Result<Vec<u8>> {
+        let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// 构建 LaunchLab `initialize` 指令，以 WSOL 作为报价代币创建池子
+pub fn initialize(
+    payer: &Pubkey,
+    creator: &Pubkey,
+    mint: &Pubkey,
+    platform_config: &Pubkey,
+    args: Initialize,
+) -> Result<Instruction> {
+    let pool_state = get_pool_pda(mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
+    Ok(Instruction::new_with_bytes(
+        accounts::BONK,
+        &args.data()?,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new_readonly(accounts::GLOBAL_CONFIG, false),
+            AccountMeta::new_readonly(*platform_config, false),
+            AccountMeta::new_readonly(accounts::AUTHORITY, false),
+            AccountMeta::new(pool_state, false),
+            AccountMeta::new(*mint, true),
+            AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false),
+            AccountMeta::new(get_vault_pda(&pool_state, mint).unwrap(), false),
+            AccountMeta::new(
+                get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap(),
+                false,
+            ),
+            AccountMeta::new(get_metadata_pda(mint), false),
+            AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::METADATA_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::RENT, false),
+            AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(accounts::BONK, false),
+        ],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize_data_layout() {
+        let data = Initialize {
+            mint_params: MintParams {
+                decimals: 6,
+                name: "Bonk".to_string(),
+                symbol: "BK".to_string(),
+                uri: "u".to_string(),
+            },
+            curve_params: CurveParams::Constant {
+                supply: 1,
+                total_base_sell: 2,
+                total_quote_fund_raising: 3,
+                migrate_type: 1,
+            },
+            vesting_params: VestingParams::default(),
+        }
+        .data()
+        .unwrap();
+
+        assert_eq!(&data[..8], &INITIALIZE_DISCRIMINATOR);
+        // decimals + 三个 borsh 字符串
+        let mint_params_len = 1 + (4 + 4) + (4 + 2) + (4 + 1);
+        let curve = &data[8 + mint_params_len..];
+        // 枚举序号 0 = Constant，随后是四个字段
+        assert_eq!(curve[0], 0);
+        assert_eq!(&curve[1..9], &1u64.to_le_bytes());
+        assert_eq!(curve[25], 1);
+        assert_eq!(curve.len(), 1 + 8 * 3 + 1 + 8 * 3);
+    }
+
+    #[test]
+    fn test_swap_data_exact_out() {
+        let exact_in = swap_data(
+            BUY_EXECT_IN_DISCRIMINATOR,
+            BUY_EXACT_OUT_DISCRIMINATOR,
+            SwapMode::ExactIn,
+            100,
+            90,
+        );
+        assert_eq!(&exact_in[..8], &BUY_EXECT_IN_DISCRIMINATOR);
+        assert_eq!(&exact_in[8..16], &100u64.to_le_bytes());
+        assert_eq!(&exact_in[16..24], &90u64.to_le_bytes());
+
+        // sell_exact_out(amount_out, maximum_amount_in, share_fee_rate)
+        let exact_out = swap_data(
+            SELL_EXECT_IN_DISCRIMINATOR,
+            SELL_EXACT_OUT_DISCRIMINATOR,
+            SwapMode::ExactOut(5_000),
+            100,
+            0,
+        );
+        assert_eq!(&exact_out[..8], &SELL_EXACT_OUT_DISCRIMINATOR);
+        assert_eq!(&exact_out[8..16], &5_000u64.to_le_bytes());
+        assert_eq!(&exact_out[16..24], &100u64.to_le_bytes());
+        assert_eq!(exact_out.len(), 32);
+    }
+}
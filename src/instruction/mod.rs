@@ -1,4 +1,6 @@
 pub mod bonk;
 pub mod pumpfun;
 pub mod pumpswap;
+pub mod raydium_amm_v4;
+pub mod raydium_clmm;
 pub mod raydium_cpmm;
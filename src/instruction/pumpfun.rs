@@ -8,18 +8,20 @@ use spl_token::instruction::close_account;
 use crate::{
     constants,
     trading::pumpfun::common::{
-        get_bonding_curve_pda, get_global_volume_accumulator_pda, get_user_volume_accumulator_pda,
+        get_bonding_curve_pda, get_global_volume_accumulator_pda, get_metadata_pda,
+        get_mint_authority_pda, get_user_volume_accumulator_pda,
     },
 };
 
-use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer};
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
 
 use crate::{
+    common::bonding_curve::BondingCurveAccount,
     constants::pumpfun::global_constants::FEE_RECIPIENT,
     constants::trade::trade::DEFAULT_SLIPPAGE,
     trading::common::utils::calculate_with_slippage_buy,
     trading::core::{
-        params::{BuyParams, PumpFunParams, SellParams},
+        params::{BuyParams, CreateParams, PumpFunParams, SellParams, SwapMode},
         traits::InstructionBuilder,
     },
     trading::pumpfun::common::{get_buy_token_amount_from_sol_amount, get_creator_vault_pda},
@@ -38,9 +40,12 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             .downcast_ref::<PumpFunParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for PumpFun"))?;
 
-        if params.sol_amount == 0 {
+        if params.sol_amount.is_zero() {
             return Err(anyhow!("Amount cannot be zero"));
         }
+        if params.swap_mode != SwapMode::ExactIn {
+            return Err(anyhow!("Exact-out buy is not supported by PumpFun"));
+        }
 
         let bonding_curve = if protocol_params.bonding_curve.is_some() {
             protocol_params.bonding_curve.clone().unwrap()
@@ -48,21 +53,12 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             return Err(anyhow!("Bonding curve not found"));
         };
 
-        let max_sol_cost = calculate_with_slippage_buy(
-            params.sol_amount,
-            params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-        );
         let creator_vault_pda = bonding_curve.get_creator_vault_pda();
-
-        let mut buy_token_amount =
-            get_buy_token_amount_from_sol_amount(&bonding_curve, params.sol_amount);
-        if buy_token_amount <= 100 * 1_000_000_u64 {
-            buy_token_amount = if max_sol_cost > sol_str_to_lamports("0.01").unwrap_or(0) {
-                25547619 * 1_000_000_u64
-            } else {
-                255476 * 1_000_000_u64
-            };
-        }
+        let (buy_token_amount, max_sol_cost) = get_buy_amounts(
+            &bonding_curve,
+            params.sol_amount.as_u64(),
+            params.slippage_basis_points,
+        );
 
         let mut instructions = vec![];
 
@@ -76,7 +72,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
 
         // 创建买入指令
         instructions.push(buy(
-            params.payer.as_ref(),
+            &params.payer.pubkey(),
             &params.mint,
             &bonding_curve.account,
             &creator_vault_pda,
@@ -91,6 +87,9 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
     }
 
     async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        if params.swap_mode != SwapMode::ExactIn {
+            return Err(anyhow!("Exact-out sell is not supported by PumpFun"));
+        }
         let token_amount = if let Some(amount) = params.token_amount {
             if amount == 0 {
                 return Err(anyhow!("Amount cannot be zero"));
@@ -119,7 +118,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
         }
 
         let mut instructions = vec![sell(
-            params.payer.as_ref(),
+            &params.payer.pubkey(),
             &params.mint,
             &creator_vault_pda,
             &FEE_RECIPIENT,
@@ -142,6 +141,69 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
 
         Ok(instructions)
     }
+
+    async fn build_create_instructions(&self, params: &CreateParams) -> Result<Vec<Instruction>> {
+        let payer = params.payer.pubkey();
+        let mint = params.mint.pubkey();
+
+        let mut instructions = vec![create(
+            &payer,
+            &mint,
+            Create {
+                _name: params.metadata.name.clone(),
+                _symbol: params.metadata.symbol.clone(),
+                _uri: params.metadata.uri.clone(),
+                _creator: params.creator,
+            },
+        )];
+        if params.dev_buy_sol_amount.is_zero() {
+            return Ok(instructions);
+        }
+
+        // 开发者买入按初始联合曲线计算
+        let bonding_curve = BondingCurveAccount::from_dev_trade(&mint, 0, 0, params.creator);
+        let (buy_token_amount, max_sol_cost) = get_buy_amounts(
+            &bonding_curve,
+            params.dev_buy_sol_amount.as_u64(),
+            params.slippage_basis_points,
+        );
+        instructions.push(create_associated_token_account(
+            &payer,
+            &payer,
+            &mint,
+            &constants::pumpfun::accounts::TOKEN_PROGRAM,
+        ));
+        instructions.push(buy(
+            &payer,
+            &mint,
+            &bonding_curve.account,
+            &bonding_curve.get_creator_vault_pda(),
+            &FEE_RECIPIENT,
+            Buy { _amount: buy_token_amount, _max_sol_cost: max_sol_cost },
+        ));
+
+        Ok(instructions)
+    }
+}
+
+/// 计算买入指令的代币数量和最大SOL花费
+pub fn get_buy_amounts(
+    bonding_curve: &BondingCurveAccount,
+    sol_amount: u64,
+    slippage_basis_points: Option<u64>,
+) -> (u64, u64) {
+    let max_sol_cost =
+        calculate_with_slippage_buy(sol_amount, slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE));
+
+    let mut buy_token_amount = get_buy_token_amount_from_sol_amount(bonding_curve, sol_amount);
+    if buy_token_amount <= 100 * 1_000_000_u64 {
+        buy_token_amount = if max_sol_cost > sol_str_to_lamports("0.01").unwrap_or(0) {
+            25547619 * 1_000_000_u64
+        } else {
+            255476 * 1_000_000_u64
+        };
+    }
+    (buy_token_amount, max_sol_cost)
 }
 
 pub struct Buy {
@@ -152,7 +214,7 @@ pub struct Buy {
 impl Buy {
     pub fn data(&self) -> Vec<u8> {
         let mut data = Vec::with_capacity(8 + 8 + 8);
-        data.extend_from_slice(&[102, 6, 61, 18, 1, 218, 235, 234]); // discriminator
+        data.extend_from_slice(&constants::pumpfun::BUY_DISCRIMINATOR);
         data.extend_from_slice(&self._amount.to_le_bytes());
         data.extend_from_slice(&self._max_sol_cost.to_le_bytes());
         data
@@ -167,15 +229,65 @@ pub struct Sell {
 impl Sell {
     pub fn data(&self) -> Vec<u8> {
         let mut data = Vec::with_capacity(8 + 8 + 8);
-        data.extend_from_slice(&[51, 230, 133, 164, 1, 127, 131, 173]); // discriminator
+        data.extend_from_slice(&constants::pumpfun::SELL_DISCRIMINATOR);
         data.extend_from_slice(&self._amount.to_le_bytes());
         data.extend_from_slice(&self._min_sol_output.to_le_bytes());
         data
     }
 }
 
+pub struct Create {
+    pub _name: String,
+    pub _symbol: String,
+    pub _uri: String,
+    pub _creator: Pubkey,
+}
+
+impl Create {
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(
+            8 + 12 + self._name.len() + self._symbol.len() + self._uri.len() + 32,
+        );
+        data.extend_from_slice(&constants::pumpfun::CREATE_DISCRIMINATOR);
+        // 字符串按 borsh 编码：u32 长度 + UTF-8 字节
+        for field in [&self._name, &self._symbol, &self._uri] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data.extend_from_slice(self._creator.as_ref());
+        data
+    }
+}
+
+pub fn create(payer: &Pubkey, mint: &Pubkey, args: Create) -> Instruction {
+    let bonding_curve = get_bonding_curve_pda(mint).unwrap();
+    Instruction::new_with_bytes(
+        constants::pumpfun::accounts::PUMPFUN,
+        &args.data(),
+        vec![
+            AccountMeta::new(*mint, true),
+            AccountMeta::new_readonly(get_mint_authority_pda(), false),
+            AccountMeta::new(bonding_curve, false),
+            AccountMeta::new(get_associated_token_address(&bonding_curve, mint), false),
+            AccountMeta::new_readonly(constants::pumpfun::global_constants::GLOBAL_ACCOUNT, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::MPL_TOKEN_METADATA, false),
+            AccountMeta::new(get_metadata_pda(mint), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(
+                constants::pumpfun::accounts::ASSOCIATED_TOKEN_PROGRAM,
+                false,
+            ),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::RENT, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::PUMPFUN, false),
+        ],
+    )
+}
+
 pub fn buy(
-    payer: &Keypair,
+    payer: &Pubkey,
     mint: &Pubkey,
     bonding_curve_pda: &Pubkey,
     creator_vault_pda: &Pubkey,
@@ -191,21 +303,21 @@ pub fn buy(
             AccountMeta::new_readonly(*mint, false),
             AccountMeta::new(*bonding_curve_pda, false),
             AccountMeta::new(get_associated_token_address(bonding_curve_pda, mint), false),
-            AccountMeta::new(get_associated_token_address(&payer.pubkey(), mint), false),
-            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new(*payer, true),
             AccountMeta::new_readonly(constants::pumpfun::accounts::SYSTEM_PROGRAM, false),
             AccountMeta::new_readonly(constants::pumpfun::accounts::TOKEN_PROGRAM, false),
             AccountMeta::new(*creator_vault_pda, false),
             AccountMeta::new_readonly(constants::pumpfun::accounts::EVENT_AUTHORITY, false),
             AccountMeta::new_readonly(constants::pumpfun::accounts::PUMPFUN, false),
             AccountMeta::new(get_global_volume_accumulator_pda().unwrap(), false),
-            AccountMeta::new(get_user_volume_accumulator_pda(&payer.pubkey()).unwrap(), false),
+            AccountMeta::new(get_user_volume_accumulator_pda(payer).unwrap(), false),
         ],
     )
 }
 
 pub fn sell(
-    payer: &Keypair,
+    payer: &Pubkey,
     mint: &Pubkey,
     creator_vault_pda: &Pubkey,
     fee_recipient: &Pubkey,
@@ -221,15 +333,41 @@ pub fn sell(
             AccountMeta::new_readonly(*mint, false),
             AccountMeta::new(bonding_curve, false),
             AccountMeta::new(get_associated_token_address(&bonding_curve, mint), false),
-            AccountMeta::new(get_associated_token_address(&payer.pubkey(), mint), false),
-            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new(*payer, true),
             AccountMeta::new_readonly(constants::pumpfun::accounts::SYSTEM_PROGRAM, false),
             AccountMeta::new(*creator_vault_pda, false),
             AccountMeta::new_readonly(constants::pumpfun::accounts::TOKEN_PROGRAM, false),
             AccountMeta::new_readonly(constants::pumpfun::accounts::EVENT_AUTHORITY, false),
             AccountMeta::new_readonly(constants::pumpfun::accounts::PUMPFUN, false),
             AccountMeta::new(get_global_volume_accumulator_pda().unwrap(), false),
-            AccountMeta::new(get_user_volume_accumulator_pda(&payer.pubkey()).unwrap(), false),
+            AccountMeta::new(get_user_volume_accumulator_pda(payer).unwrap(), false),
         ],
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_data_layout() {
+        let creator = Pubkey::new_unique();
+        let data = Create {
+            _name: "Token".to_string(),
+            _symbol: "TKN".to_string(),
+            _uri: "https://x.io/m.json".to_string(),
+            _creator: creator,
+        }
+        .data();
+
+        assert_eq!(&data[..8], &constants::pumpfun::CREATE_DISCRIMINATOR);
+        assert_eq!(&data[8..12], &5u32.to_le_bytes());
+        assert_eq!(&data[12..17], b"Token");
+        assert_eq!(&data[17..21], &3u32.to_le_bytes());
+        assert_eq!(&data[21..24], b"TKN");
+        assert_eq!(&data[24..28], &19u32.to_le_bytes());
+        assert_eq!(&data[data.len() - 32..], creator.as_ref());
+        assert_eq!(data.len(), 8 + 4 + 5 + 4 + 3 + 4 + 19 + 32);
+    }
+}
@@ -1,29 +1,38 @@
 use anyhow::{anyhow, Result};
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 use solana_system_interface::instruction::transfer;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use spl_token::instruction::close_account;
 
 use crate::{
     constants::{
-        pumpswap::{accounts, BUY_DISCRIMINATOR, SELL_DISCRIMINATOR},
+        pumpswap::{
+            accounts, BUY_DISCRIMINATOR, CREATE_POOL_DISCRIMINATOR, DEPOSIT_DISCRIMINATOR,
+            SELL_DISCRIMINATOR, WITHDRAW_DISCRIMINATOR,
+        },
         trade::trade::DEFAULT_SLIPPAGE,
     },
     trading::{
-        common::utils::{
-            calculate_with_slippage_buy, calculate_with_slippage_sell, get_token_balance,
+        common::{
+            close_wsol,
+            utils::{calculate_with_slippage_buy, calculate_with_slippage_sell, get_token_balance},
+            wrap_wsol,
         },
         core::{
-            params::{BuyParams, PumpSwapParams, SellParams},
+            params::{BuyParams, PumpSwapParams, SellParams, SwapMode},
             traits::InstructionBuilder,
         },
         pumpswap::{
             self,
             common::{
                 coin_creator_vault_ata, coin_creator_vault_authority, fee_recipient_ata, find_pool,
-                get_global_volume_accumulator_pda, get_token_amount,
-                get_user_volume_accumulator_pda, get_wsol_amount,
+                get_buy_quote_amount_in, get_global_volume_accumulator_pda, get_lp_mint_pda,
+                get_pool_pda, get_token_amount, get_user_volume_accumulator_pda, get_wsol_amount,
             },
+            pool::Pool,
         },
     },
 };
@@ -41,7 +50,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             .downcast_ref::<PumpSwapParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for PumpSwap"))?;
 
-        if params.sol_amount == 0 {
+        if params.sol_amount.is_zero() {
             return Err(anyhow!("Amount cannot be zero"));
         }
 
@@ -83,6 +92,9 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
     }
 
     async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        if params.swap_mode != SwapMode::ExactIn {
+            return Err(anyhow!("Exact-out sell is not supported by PumpSwap"));
+        }
         // Get PumpSwap specific parameters
         let protocol_params = params
             .protocol_params
@@ -203,37 +215,69 @@ impl PumpSwapInstructionBuilder {
             return Err(anyhow!("RPC is not set"));
         }
         let quote_mint_is_wsol = quote_mint == accounts::WSOL_TOKEN_ACCOUNT;
-        // Calculate token amount
-        let mut token_amount = get_token_amount(
-            quote_mint_is_wsol,
-            pool_base_token_reserves,
-            pool_quote_token_reserves,
-            params.sol_amount,
-            accounts::LP_FEE_BASIS_POINTS,
-            accounts::PROTOCOL_FEE_BASIS_POINTS,
-            if params.creator == Pubkey::default() {
-                0
-            } else {
-                accounts::COIN_CREATOR_FEE_BASIS_POINTS
-            },
-        )
-        .await?;
-        if !quote_mint_is_wsol {
-            // min_quote_amount_out
-            token_amount = calculate_with_slippage_sell(
-                token_amount,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            );
-        }
-        let sol_amount = if quote_mint_is_wsol {
-            // max_quote_amount_in
-            calculate_with_slippage_buy(
-                params.sol_amount,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-        } else {
-            // base_amount_in
-            params.sol_amount
+        // Calculate token amount and the SOL limit
+        let (token_amount, sol_amount) = match params.swap_mode {
+            SwapMode::ExactIn => {
+                let mut token_amount = get_token_amount(
+                    quote_mint_is_wsol,
+                    pool_base_token_reserves,
+                    pool_quote_token_reserves,
+                    params.sol_amount.as_u64(),
+                    accounts::LP_FEE_BASIS_POINTS,
+                    accounts::PROTOCOL_FEE_BASIS_POINTS,
+                    if params.creator == Pubkey::default() {
+                        0
+                    } else {
+                        accounts::COIN_CREATOR_FEE_BASIS_POINTS
+                    },
+                )
+                .await?;
+                if !quote_mint_is_wsol {
+                    // min_quote_amount_out
+                    token_amount = calculate_with_slippage_sell(
+                        token_amount,
+                        params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                    );
+                }
+                let sol_amount = if quote_mint_is_wsol {
+                    // max_quote_amount_in
+                    calculate_with_slippage_buy(
+                        params.sol_amount.as_u64(),
+                        params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                    )
+                } else {
+                    // base_amount_in
+                    params.sol_amount.as_u64()
+                };
+                (token_amount, sol_amount)
+            }
+            SwapMode::ExactOut(base_amount_out) => {
+                // Base-out buy: receive exactly base_amount_out, sol_amount is the max quote in
+                if !quote_mint_is_wsol {
+                    return Err(anyhow!("Exact-out buy is only supported on WSOL-quoted pools"));
+                }
+                let required = get_buy_quote_amount_in(
+                    base_amount_out,
+                    pool_base_token_reserves,
+                    pool_quote_token_reserves,
+                    accounts::LP_FEE_BASIS_POINTS,
+                    accounts::PROTOCOL_FEE_BASIS_POINTS,
+                    if params.creator == Pubkey::default() {
+                        0
+                    } else {
+                        accounts::COIN_CREATOR_FEE_BASIS_POINTS
+                    },
+                )?;
+                if required > params.sol_amount.as_u64() {
+                    return Err(anyhow!(
+                        "Buying {} tokens costs {} lamports, more than the limit {}",
+                        base_amount_out,
+                        required,
+                        params.sol_amount.as_u64()
+                    ));
+                }
+                (base_amount_out, params.sol_amount.as_u64())
+            }
         };
 
         // Create user token accounts
@@ -533,3 +577,277 @@ impl PumpSwapInstructionBuilder {
         Ok(instructions)
     }
 }
+
+/// Arguments of the `create_pool` instruction
+#[derive(Debug, Clone, Copy)]
+pub struct CreatePool {
+    /// Lets one creator open several pools for the same pair
+    pub index: u16,
+    pub base_amount_in: u64,
+    pub quote_amount_in: u64,
+    /// Receives the coin creator fee of every trade in the pool
+    pub coin_creator: Pubkey,
+}
+
+impl CreatePool {
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = CREATE_POOL_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&self.index.to_le_bytes());
+        data.extend_from_slice(&self.base_amount_in.to_le_bytes());
+        data.extend_from_slice(&self.quote_amount_in.to_le_bytes());
+        data.extend_from_slice(self.coin_creator.as_ref());
+        data
+    }
+}
+
+/// Build `create_pool` together with the wSOL wrapping it needs
+///
+/// The creator funds both sides of the pool and receives the initial LP tokens. Returns the
+/// instructions and the address of the new pool.
+pub fn create_pool(
+    creator: &Pubkey,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+    args: CreatePool,
+    auto_handle_wsol: bool,
+) -> Result<(Vec<Instruction>, Pubkey)> {
+    let pool = get_pool_pda(args.index, creator, base_mint, quote_mint)
+        .ok_or_else(|| anyhow!("Failed to derive the pool address"))?;
+    let lp_mint = get_lp_mint_pda(&pool).ok_or_else(|| anyhow!("Failed to derive the LP mint"))?;
+
+    let mut instructions = vec![];
+    let wsol_amount =
+        wsol_side_amount(base_mint, quote_mint, args.base_amount_in, args.quote_amount_in);
+    if auto_handle_wsol {
+        if let Some(amount) = wsol_amount {
+            instructions.extend(wrap_wsol(creator, amount));
+        }
+    }
+
+    let accounts = vec![
+        AccountMeta::new(pool, false),
+        AccountMeta::new_readonly(accounts::GLOBAL_ACCOUNT, false),
+        AccountMeta::new(*creator, true),
+        AccountMeta::new_readonly(*base_mint, false),
+        AccountMeta::new_readonly(*quote_mint, false),
+        AccountMeta::new(lp_mint, false),
+        AccountMeta::new(user_token_account(creator, base_mint), false),
+        AccountMeta::new(user_token_account(creator, quote_mint), false),
+        AccountMeta::new(user_lp_token_account(creator, &lp_mint), false),
+        AccountMeta::new(pool_token_account(&pool, base_mint), false),
+        AccountMeta::new(pool_token_account(&pool, quote_mint), false),
+        AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // base token program
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // quote token program
+        AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(accounts::AMM_PROGRAM, false),
+    ];
+    instructions.push(Instruction::new_with_bytes(accounts::AMM_PROGRAM, &args.data(), accounts));
+
+    if auto_handle_wsol && wsol_amount.is_some() {
+        instructions.push(close_wsol(creator));
+    }
+    Ok((instructions, pool))
+}
+
+/// Build `deposit`: mint `lp_token_amount_out` LP tokens for at most the given base and quote
+///
+/// The user's LP token account is created if needed. With `auto_handle_wsol`, the maximum wSOL
+/// side is wrapped up front and the unused part is returned when the account is closed.
+pub fn deposit(
+    user: &Pubkey,
+    pool_address: &Pubkey,
+    pool: &Pool,
+    lp_token_amount_out: u64,
+    max_base_amount_in: u64,
+    max_quote_amount_in: u64,
+    auto_handle_wsol: bool,
+) -> Vec<Instruction> {
+    let mut instructions = vec![create_associated_token_account_idempotent(
+        user,
+        user,
+        &pool.lp_mint,
+        &accounts::TOKEN_2022_PROGRAM,
+    )];
+    let wsol_amount = wsol_side_amount(
+        &pool.base_mint,
+        &pool.quote_mint,
+        max_base_amount_in,
+        max_quote_amount_in,
+    );
+    if auto_handle_wsol {
+        if let Some(amount) = wsol_amount {
+            instructions.extend(wrap_wsol(user, amount));
+        }
+    }
+
+    let mut data = DEPOSIT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&lp_token_amount_out.to_le_bytes());
+    data.extend_from_slice(&max_base_amount_in.to_le_bytes());
+    data.extend_from_slice(&max_quote_amount_in.to_le_bytes());
+    instructions.push(Instruction::new_with_bytes(
+        accounts::AMM_PROGRAM,
+        &data,
+        liquidity_accounts(user, pool_address, pool),
+    ));
+
+    if auto_handle_wsol && wsol_amount.is_some() {
+        instructions.push(close_wsol(user));
+    }
+    instructions
+}
+
+/// Build `withdraw`: burn `lp_token_amount_in` LP tokens for at least the given base and quote
+///
+/// The user's base and quote token accounts are created if needed. With `auto_handle_wsol`, the
+/// received wSOL is unwrapped afterwards.
+pub fn withdraw(
+    user: &Pubkey,
+    pool_address: &Pubkey,
+    pool: &Pool,
+    lp_token_amount_in: u64,
+    min_base_amount_out: u64,
+    min_quote_amount_out: u64,
+    auto_handle_wsol: bool,
+) -> Vec<Instruction> {
+    let mut instructions = vec![
+        create_associated_token_account_idempotent(
+            user,
+            user,
+            &pool.base_mint,
+            &accounts::TOKEN_PROGRAM,
+        ),
+        create_associated_token_account_idempotent(
+            user,
+            user,
+            &pool.quote_mint,
+            &accounts::TOKEN_PROGRAM,
+        ),
+    ];
+
+    let mut data = WITHDRAW_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&lp_token_amount_in.to_le_bytes());
+    data.extend_from_slice(&min_base_amount_out.to_le_bytes());
+    data.extend_from_slice(&min_quote_amount_out.to_le_bytes());
+    instructions.push(Instruction::new_with_bytes(
+        accounts::AMM_PROGRAM,
+        &data,
+        liquidity_accounts(user, pool_address, pool),
+    ));
+
+    let has_wsol = pool.base_mint == accounts::WSOL_TOKEN_ACCOUNT
+        || pool.quote_mint == accounts::WSOL_TOKEN_ACCOUNT;
+    if auto_handle_wsol && has_wsol {
+        instructions.push(close_wsol(user));
+    }
+    instructions
+}
+
+/// Accounts shared by `deposit` and `withdraw`
+fn liquidity_accounts(user: &Pubkey, pool_address: &Pubkey, pool: &Pool) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*pool_address, false),
+        AccountMeta::new_readonly(accounts::GLOBAL_ACCOUNT, false),
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new_readonly(pool.base_mint, false),
+        AccountMeta::new_readonly(pool.quote_mint, false),
+        AccountMeta::new(pool.lp_mint, false),
+        AccountMeta::new(user_token_account(user, &pool.base_mint), false),
+        AccountMeta::new(user_token_account(user, &pool.quote_mint), false),
+        AccountMeta::new(user_lp_token_account(user, &pool.lp_mint), false),
+        AccountMeta::new(pool.pool_base_token_account, false),
+        AccountMeta::new(pool.pool_quote_token_account, false),
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false),
+        AccountMeta::new_readonly(accounts::AMM_PROGRAM, false),
+    ]
+}
+
+/// Amount to wrap when one side of the pair is wSOL
+fn wsol_side_amount(
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+    base_amount: u64,
+    quote_amount: u64,
+) -> Option<u64> {
+    if *quote_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        Some(quote_amount)
+    } else if *base_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        Some(base_amount)
+    } else {
+        None
+    }
+}
+
+fn user_token_account(user: &Pubkey, mint: &Pubkey) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address_with_program_id(
+        user,
+        mint,
+        &accounts::TOKEN_PROGRAM,
+    )
+}
+
+fn user_lp_token_account(user: &Pubkey, lp_mint: &Pubkey) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address_with_program_id(
+        user,
+        lp_mint,
+        &accounts::TOKEN_2022_PROGRAM,
+    )
+}
+
+fn pool_token_account(pool: &Pubkey, mint: &Pubkey) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address_with_program_id(
+        pool,
+        mint,
+        &accounts::TOKEN_PROGRAM,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_pool_data_layout() {
+        let coin_creator = Pubkey::new_unique();
+        let data =
+            CreatePool { index: 3, base_amount_in: 1_000, quote_amount_in: 2_000, coin_creator }
+                .data();
+        assert_eq!(&data[..8], &CREATE_POOL_DISCRIMINATOR);
+        assert_eq!(&data[8..10], &3u16.to_le_bytes());
+        assert_eq!(&data[10..18], &1_000u64.to_le_bytes());
+        assert_eq!(&data[18..26], &2_000u64.to_le_bytes());
+        assert_eq!(&data[26..], coin_creator.as_ref());
+    }
+
+    #[test]
+    fn test_deposit_wraps_wsol_side() {
+        let user = Pubkey::new_unique();
+        let pool = Pool {
+            pool_bump: 255,
+            index: 0,
+            creator: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            quote_mint: accounts::WSOL_TOKEN_ACCOUNT,
+            lp_mint: Pubkey::new_unique(),
+            pool_base_token_account: Pubkey::new_unique(),
+            pool_quote_token_account: Pubkey::new_unique(),
+            lp_supply: 1_000,
+            coin_creator: Pubkey::default(),
+        };
+        let instructions = deposit(&user, &Pubkey::new_unique(), &pool, 10, 100, 200, true);
+        // LP 账户、wSOL 包装三条、deposit、关闭 wSOL
+        assert_eq!(instructions.len(), 6);
+        let deposit_ix = &instructions[4];
+        assert_eq!(&deposit_ix.data[..8], &DEPOSIT_DISCRIMINATOR);
+        assert_eq!(&deposit_ix.data[16..24], &100u64.to_le_bytes());
+        assert_eq!(deposit_ix.accounts.len(), 15);
+        assert_eq!(
+            instructions[2].data,
+            transfer(&user, &user_token_account(&user, &pool.quote_mint), 200).data
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    common::SolanaRpcClient,
+    constants::{
+        raydium_amm_v4::{accounts, SWAP_BASE_IN_TAG, SWAP_BASE_OUT_TAG},
+        trade::trade::DEFAULT_SLIPPAGE,
+    },
+    trading::{
+        common::{
+            close_wsol, user_token_account,
+            utils::{calculate_with_slippage_sell, get_token_balance},
+            wrap_wsol,
+        },
+        core::{
+            params::{BuyParams, RaydiumAmmV4Params, SellParams, SwapMode},
+            traits::InstructionBuilder,
+        },
+        raydium_amm_v4::{
+            common::{get_amount_in, get_amount_out, get_pool_reserves},
+            pool::{AmmInfo, MarketState, PoolKeys},
+        },
+    },
+};
+
+/// RaydiumAmmV4协议的指令构建器
+pub struct RaydiumAmmV4InstructionBuilder;
+
+#[async_trait::async_trait]
+impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        if params.sol_amount.is_zero() {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumAmmV4Params>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
+
+        let rpc = params.rpc.as_deref();
+        let pool_keys = resolve_pool_keys(rpc, protocol_params, &params.mint).await?;
+        let (wsol_reserves, token_reserves) =
+            resolve_reserves(rpc, protocol_params, &pool_keys).await?;
+
+        let max_amount_in = params.sol_amount.as_u64();
+        let data = match params.swap_mode {
+            SwapMode::ExactIn => {
+                let amount_out = get_amount_out(
+                    max_amount_in,
+                    wsol_reserves,
+                    token_reserves,
+                    pool_keys.swap_fee_numerator,
+                    pool_keys.swap_fee_denominator,
+                )?;
+                let minimum_amount_out = calculate_with_slippage_sell(
+                    amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                );
+                swap_data(SwapMode::ExactIn, max_amount_in, minimum_amount_out)
+            }
+            SwapMode::ExactOut(amount_out) => {
+                // sol_amount 是最多花费的数量，报价已超过上限时直接返回错误
+                let required = get_amount_in(
+                    amount_out,
+                    wsol_reserves,
+                    token_reserves,
+                    pool_keys.swap_fee_numerator,
+                    pool_keys.swap_fee_denominator,
+                )?;
+                if required > max_amount_in {
+                    return Err(anyhow!(
+                        "Buying {} tokens costs {} lamports, more than the limit {}",
+                        amount_out,
+                        required,
+                        max_amount_in
+                    ));
+                }
+                swap_data(params.swap_mode, max_amount_in, 0)
+            }
+        };
+
+        let payer = params.payer.pubkey();
+        let wsol_token_account =
+            user_token_account(&payer, &accounts::WSOL_TOKEN_ACCOUNT, &accounts::TOKEN_PROGRAM);
+        let mint_token_account = user_token_account(&payer, &params.mint, &accounts::TOKEN_PROGRAM);
+
+        let mut instructions = vec![];
+        if protocol_params.auto_handle_wsol {
+            instructions.extend(wrap_wsol(&payer, max_amount_in));
+        }
+        // 创建用户的代币账户，如果不存在
+        instructions.push(create_associated_token_account_idempotent(
+            &payer,
+            &payer,
+            &params.mint,
+            &accounts::TOKEN_PROGRAM,
+        ));
+        instructions.push(Instruction {
+            program_id: accounts::RAYDIUM_AMM_V4,
+            accounts: swap_accounts(&pool_keys, &wsol_token_account, &mint_token_account, &payer),
+            data,
+        });
+        if protocol_params.auto_handle_wsol {
+            instructions.push(close_wsol(&payer));
+        }
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumAmmV4Params>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
+
+        let rpc = params.rpc.as_deref();
+        let payer = params.payer.pubkey();
+
+        // 获取代币余额
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount,
+            _ => {
+                let rpc = rpc.ok_or_else(|| anyhow!("RPC is not set"))?;
+                get_token_balance(rpc, &payer, &params.mint).await?
+            }
+        };
+        if amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+
+        let pool_keys = resolve_pool_keys(rpc, protocol_params, &params.mint).await?;
+        let (wsol_reserves, token_reserves) =
+            resolve_reserves(rpc, protocol_params, &pool_keys).await?;
+
+        let data = match params.swap_mode {
+            SwapMode::ExactIn => {
+                let amount_out = get_amount_out(
+                    amount,
+                    token_reserves,
+                    wsol_reserves,
+                    pool_keys.swap_fee_numerator,
+                    pool_keys.swap_fee_denominator,
+                )?;
+                let minimum_amount_out = calculate_with_slippage_sell(
+                    amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                );
+                swap_data(SwapMode::ExactIn, amount, minimum_amount_out)
+            }
+            SwapMode::ExactOut(amount_out) => {
+                // amount 是最多卖出的数量，报价已超过上限时直接返回错误
+                let required = get_amount_in(
+                    amount_out,
+                    token_reserves,
+                    wsol_reserves,
+                    pool_keys.swap_fee_numerator,
+                    pool_keys.swap_fee_denominator,
+                )?;
+                if required > amount {
+                    return Err(anyhow!(
+                        "Receiving {} lamports needs {} tokens, more than the limit {}",
+                        amount_out,
+                        required,
+                        amount
+                    ));
+                }
+                swap_data(params.swap_mode, amount, 0)
+            }
+        };
+
+        let wsol_token_account =
+            user_token_account(&payer, &accounts::WSOL_TOKEN_ACCOUNT, &accounts::TOKEN_PROGRAM);
+        let mint_token_account = user_token_account(&payer, &params.mint, &accounts::TOKEN_PROGRAM);
+
+        let mut instructions = vec![];
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(create_associated_token_account_idempotent(
+            &payer,
+            &payer,
+            &accounts::WSOL_TOKEN_ACCOUNT,
+            &accounts::TOKEN_PROGRAM,
+        ));
+        instructions.push(Instruction {
+            program_id: accounts::RAYDIUM_AMM_V4,
+            accounts: swap_accounts(&pool_keys, &mint_token_account, &wsol_token_account, &payer),
+            data,
+        });
+        if protocol_params.auto_handle_wsol {
+            instructions.push(close_wsol(&payer));
+        }
+
+        Ok(instructions)
+    }
+}
+
+/// 确定池子账户：优先使用传入的 `pool_keys`，其次读取 `amm`，都没有时按 mint 查找
+async fn resolve_pool_keys(
+    rpc: Option<&SolanaRpcClient>,
+    protocol_params: &RaydiumAmmV4Params,
+    mint: &Pubkey,
+) -> Result<PoolKeys> {
+    let pool_keys = match (protocol_params.pool_keys, protocol_params.amm, rpc) {
+        (Some(pool_keys), _, _) => pool_keys,
+        (None, Some(amm), Some(rpc)) => PoolKeys::fetch(rpc, &amm).await?,
+        (None, None, Some(rpc)) => {
+            let (amm, amm_info) = AmmInfo::find_by_mint(rpc, mint).await?;
+            let market = MarketState::fetch(rpc, &amm_info.market).await?;
+            PoolKeys::new(amm, &amm_info, &market)?
+        }
+        (None, _, None) => return Err(anyhow!("RPC is not set")),
+    };
+    let is_wsol_pair = (pool_keys.coin_mint == *mint
+        && pool_keys.pc_mint == accounts::WSOL_TOKEN_ACCOUNT)
+        || (pool_keys.pc_mint == *mint && pool_keys.coin_mint == accounts::WSOL_TOKEN_ACCOUNT);
+    if !is_wsol_pair {
+        return Err(anyhow!("Pool {} is not a WSOL pool for mint {}", pool_keys.amm, mint));
+    }
+    Ok(pool_keys)
+}
+
+/// 返回 (wsol_reserves, token_reserves)
+async fn resolve_reserves(
+    rpc: Option<&SolanaRpcClient>,
+    protocol_params: &RaydiumAmmV4Params,
+    pool_keys: &PoolKeys,
+) -> Result<(u64, u64)> {
+    let (coin_reserves, pc_reserves) = match (protocol_params.reserves, rpc) {
+        (Some(reserves), _) => reserves,
+        (None, Some(rpc)) => get_pool_reserves(rpc, &pool_keys.amm).await?,
+        (None, None) => return Err(anyhow!("RPC is not set")),
+    };
+    if pool_keys.coin_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        Ok((coin_reserves, pc_reserves))
+    } else {
+        Ok((pc_reserves, coin_reserves))
+    }
+}
+
+/// 交换指令数据，`ExactOut` 时使用 `swap_base_out(max_amount_in, amount_out)`
+fn swap_data(swap_mode: SwapMode, amount_in: u64, minimum_amount_out: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(17);
+    match swap_mode {
+        SwapMode::ExactIn => {
+            data.push(SWAP_BASE_IN_TAG);
+            data.extend_from_slice(&amount_in.to_le_bytes());
+            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        }
+        SwapMode::ExactOut(amount_out) => {
+            data.push(SWAP_BASE_OUT_TAG);
+            data.extend_from_slice(&amount_in.to_le_bytes());
+            data.extend_from_slice(&amount_out.to_le_bytes());
+        }
+    }
+    data
+}
+
+/// 交换指令的 18 个账户，买入和卖出只是交换了用户的输入输出账户
+fn swap_accounts(
+    pool_keys: &PoolKeys,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    user_owner: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Token Program (readonly)
+        AccountMeta::new(pool_keys.amm, false),                    // Amm
+        AccountMeta::new_readonly(accounts::AUTHORITY, false),     // Amm Authority (readonly)
+        AccountMeta::new(pool_keys.open_orders, false),            // Amm Open Orders
+        AccountMeta::new(pool_keys.target_orders, false),          // Amm Target Orders
+        AccountMeta::new(pool_keys.coin_vault, false),             // Pool Coin Vault
+        AccountMeta::new(pool_keys.pc_vault, false),               // Pool Pc Vault
+        AccountMeta::new_readonly(pool_keys.market_program, false), // Market Program (readonly)
+        AccountMeta::new(pool_keys.market, false),                 // Market
+        AccountMeta::new(pool_keys.market_bids, false),            // Market Bids
+        AccountMeta::new(pool_keys.market_asks, false),            // Market Asks
+        AccountMeta::new(pool_keys.market_event_queue, false),     // Market Event Queue
+        AccountMeta::new(pool_keys.market_coin_vault, false),      // Market Coin Vault
+        AccountMeta::new(pool_keys.market_pc_vault, false),        // Market Pc Vault
+        AccountMeta::new_readonly(pool_keys.market_vault_signer, false), // Market Vault Signer (readonly)
+        AccountMeta::new(*user_source, false), // User Source Token Account
+        AccountMeta::new(*user_destination, false), // User Destination Token Account
+        AccountMeta::new_readonly(*user_owner, true), // User Owner (signer)
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_data_and_accounts() {
+        let exact_in = swap_data(SwapMode::ExactIn, 100, 90);
+        assert_eq!(exact_in[0], SWAP_BASE_IN_TAG);
+        assert_eq!(&exact_in[1..9], &100u64.to_le_bytes());
+        assert_eq!(&exact_in[9..], &90u64.to_le_bytes());
+
+        let exact_out = swap_data(SwapMode::ExactOut(5_000), 100, 0);
+        assert_eq!(exact_out[0], SWAP_BASE_OUT_TAG);
+        assert_eq!(&exact_out[1..9], &100u64.to_le_bytes());
+        assert_eq!(&exact_out[9..], &5_000u64.to_le_bytes());
+
+        let pool_keys = PoolKeys {
+            amm: Pubkey::new_unique(),
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: accounts::WSOL_TOKEN_ACCOUNT,
+            coin_vault: Pubkey::new_unique(),
+            pc_vault: Pubkey::new_unique(),
+            open_orders: Pubkey::new_unique(),
+            target_orders: Pubkey::new_unique(),
+            market_program: accounts::OPENBOOK_PROGRAM,
+            market: Pubkey::new_unique(),
+            market_bids: Pubkey::new_unique(),
+            market_asks: Pubkey::new_unique(),
+            market_event_queue: Pubkey::new_unique(),
+            market_coin_vault: Pubkey::new_unique(),
+            market_pc_vault: Pubkey::new_unique(),
+            market_vault_signer: Pubkey::new_unique(),
+            swap_fee_numerator: 25,
+            swap_fee_denominator: 10_000,
+        };
+        let (source, destination, owner) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let metas = swap_accounts(&pool_keys, &source, &destination, &owner);
+        assert_eq!(metas.len(), 18);
+        assert_eq!(metas[1].pubkey, pool_keys.amm);
+        assert_eq!(metas[7].pubkey, accounts::OPENBOOK_PROGRAM);
+        assert!(!metas[14].is_writable);
+        assert_eq!(metas[15].pubkey, source);
+        assert_eq!(metas[16].pubkey, destination);
+        assert!(metas[17].is_signer && !metas[17].is_writable);
+    }
+}
@@ -0,0 +1,344 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    common::SolanaRpcClient,
+    constants::{
+        raydium_clmm::{accounts, SWAP_V2_DISCRIMINATOR},
+        trade::trade::DEFAULT_SLIPPAGE,
+    },
+    trading::{
+        common::{
+            close_wsol, user_token_account,
+            utils::{
+                calculate_with_slippage_sell, get_mint_token_program,
+                get_token_balance_with_program,
+            },
+            wrap_wsol,
+        },
+        core::{
+            params::{BuyParams, RaydiumClmmParams, SellParams, SwapMode},
+            traits::InstructionBuilder,
+        },
+        raydium_clmm::{
+            common::{SwapQuote, SwapState},
+            pool::PoolState,
+        },
+    },
+};
+
+/// RaydiumClmm协议的指令构建器
+pub struct RaydiumClmmInstructionBuilder;
+
+#[async_trait::async_trait]
+impl InstructionBuilder for RaydiumClmmInstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        if params.sol_amount.is_zero() {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumClmmParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumClmm"))?;
+
+        // 报价需要读取 tick array，必须提供 RPC
+        let rpc = params.rpc.as_deref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        // 输入 WSOL，WSOL 是 token_0 时价格下降
+        let zero_for_one = accounts::WSOL_TOKEN_ACCOUNT < params.mint;
+        let swap_state =
+            resolve_swap_state(rpc, protocol_params, &params.mint, zero_for_one).await?;
+
+        let max_amount_in = params.sol_amount.as_u64();
+        let (quote, data) = match params.swap_mode {
+            SwapMode::ExactIn => {
+                let quote = swap_state.quote(max_amount_in, zero_for_one, true)?;
+                let minimum_amount_out = calculate_with_slippage_sell(
+                    quote.amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                );
+                (quote, swap_data(max_amount_in, minimum_amount_out, true))
+            }
+            SwapMode::ExactOut(amount_out) => {
+                // sol_amount 是最多花费的数量，报价已超过上限时直接返回错误
+                let quote = swap_state.quote(amount_out, zero_for_one, false)?;
+                if quote.amount_in > max_amount_in {
+                    return Err(anyhow!(
+                        "Buying {} tokens costs {} lamports, more than the limit {}",
+                        amount_out,
+                        quote.amount_in,
+                        max_amount_in
+                    ));
+                }
+                (quote, swap_data(amount_out, max_amount_in, false))
+            }
+        };
+
+        let payer = params.payer.pubkey();
+        let mint_token_program = match protocol_params.mint_token_program {
+            Some(mint_token_program) => mint_token_program,
+            None => get_mint_token_program(rpc, &params.mint).await?,
+        };
+        let wsol_token_account =
+            user_token_account(&payer, &accounts::WSOL_TOKEN_ACCOUNT, &accounts::TOKEN_PROGRAM);
+        let mint_token_account = user_token_account(&payer, &params.mint, &mint_token_program);
+
+        let mut instructions = vec![];
+        if protocol_params.auto_handle_wsol {
+            instructions.extend(wrap_wsol(&payer, max_amount_in));
+        }
+        // 创建用户的代币账户，如果不存在
+        instructions.push(create_associated_token_account_idempotent(
+            &payer,
+            &payer,
+            &params.mint,
+            &mint_token_program,
+        ));
+        instructions.push(Instruction {
+            program_id: accounts::RAYDIUM_CLMM,
+            accounts: swap_accounts(
+                &swap_state,
+                &quote,
+                &payer,
+                &wsol_token_account,
+                &mint_token_account,
+                zero_for_one,
+            ),
+            data,
+        });
+        if protocol_params.auto_handle_wsol {
+            instructions.push(close_wsol(&payer));
+        }
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumClmmParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumClmm"))?;
+
+        let rpc = params.rpc.as_deref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let payer = params.payer.pubkey();
+
+        let mint_token_program = match protocol_params.mint_token_program {
+            Some(mint_token_program) => mint_token_program,
+            None => get_mint_token_program(rpc, &params.mint).await?,
+        };
+
+        // 获取代币余额
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount,
+            _ => {
+                get_token_balance_with_program(rpc, &payer, &params.mint, &mint_token_program)
+                    .await?
+            }
+        };
+        if amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+
+        // 输入代币，代币是 token_0 时价格下降
+        let zero_for_one = params.mint < accounts::WSOL_TOKEN_ACCOUNT;
+        let swap_state =
+            resolve_swap_state(rpc, protocol_params, &params.mint, zero_for_one).await?;
+
+        let (quote, data) = match params.swap_mode {
+            SwapMode::ExactIn => {
+                let quote = swap_state.quote(amount, zero_for_one, true)?;
+                let minimum_amount_out = calculate_with_slippage_sell(
+                    quote.amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                );
+                (quote, swap_data(amount, minimum_amount_out, true))
+            }
+            SwapMode::ExactOut(amount_out) => {
+                // amount 是最多卖出的数量，报价已超过上限时直接返回错误
+                let quote = swap_state.quote(amount_out, zero_for_one, false)?;
+                if quote.amount_in > amount {
+                    return Err(anyhow!(
+                        "Receiving {} lamports needs {} tokens, more than the limit {}",
+                        amount_out,
+                        quote.amount_in,
+                        amount
+                    ));
+                }
+                (quote, swap_data(amount_out, amount, false))
+            }
+        };
+
+        let wsol_token_account =
+            user_token_account(&payer, &accounts::WSOL_TOKEN_ACCOUNT, &accounts::TOKEN_PROGRAM);
+        let mint_token_account = user_token_account(&payer, &params.mint, &mint_token_program);
+
+        let mut instructions = vec![];
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(create_associated_token_account_idempotent(
+            &payer,
+            &payer,
+            &accounts::WSOL_TOKEN_ACCOUNT,
+            &accounts::TOKEN_PROGRAM,
+        ));
+        instructions.push(Instruction {
+            program_id: accounts::RAYDIUM_CLMM,
+            accounts: swap_accounts(
+                &swap_state,
+                &quote,
+                &payer,
+                &mint_token_account,
+                &wsol_token_account,
+                zero_for_one,
+            ),
+            data,
+        });
+        if protocol_params.auto_handle_wsol {
+            instructions.push(close_wsol(&payer));
+        }
+
+        Ok(instructions)
+    }
+}
+
+/// 确定池子并读取交换方向上的 tick array：优先使用传入的 `pool_state`，否则按 mint 查找
+async fn resolve_swap_state(
+    rpc: &SolanaRpcClient,
+    protocol_params: &RaydiumClmmParams,
+    mint: &Pubkey,
+    zero_for_one: bool,
+) -> Result<SwapState> {
+    let pool_state = match protocol_params.pool_state {
+        Some(pool_state) => pool_state,
+        None => PoolState::find_by_mint(rpc, mint).await?.0,
+    };
+    let swap_state = SwapState::fetch(rpc, &pool_state, zero_for_one).await?;
+    let pool = &swap_state.pool;
+    let is_wsol_pair = (pool.token_mint_0 == *mint
+        && pool.token_mint_1 == accounts::WSOL_TOKEN_ACCOUNT)
+        || (pool.token_mint_1 == *mint && pool.token_mint_0 == accounts::WSOL_TOKEN_ACCOUNT);
+    if !is_wsol_pair {
+        return Err(anyhow!("Pool {} is not a WSOL pool for mint {}", pool_state, mint));
+    }
+    Ok(swap_state)
+}
+
+/// swap_v2 指令数据
+///
+/// `is_base_input` 为 false 时 `amount` 是输出数量，`other_amount_threshold` 是最多输入的数量。
+fn swap_data(amount: u64, other_amount_threshold: u64, is_base_input: bool) -> Vec<u8> {
+    let mut data = Vec::with_capacity(41);
+    data.extend_from_slice(SWAP_V2_DISCRIMINATOR);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&other_amount_threshold.to_le_bytes());
+    // 价格限制传 0，由链上使用最小或最大价格
+    data.extend_from_slice(&0u128.to_le_bytes());
+    data.push(is_base_input as u8);
+    data
+}
+
+/// swap_v2 的 13 个固定账户，之后是 bitmap 扩展（存在时）和按交换方向排列的 tick array
+fn swap_accounts(
+    swap_state: &SwapState,
+    quote: &SwapQuote,
+    payer: &Pubkey,
+    input_token_account: &Pubkey,
+    output_token_account: &Pubkey,
+    zero_for_one: bool,
+) -> Vec<AccountMeta> {
+    let pool = &swap_state.pool;
+    let (input_vault, output_vault, input_mint, output_mint) = if zero_for_one {
+        (pool.token_vault_0, pool.token_vault_1, pool.token_mint_0, pool.token_mint_1)
+    } else {
+        (pool.token_vault_1, pool.token_vault_0, pool.token_mint_1, pool.token_mint_0)
+    };
+    let mut metas = vec![
+        AccountMeta::new(*payer, true),                    // Payer (signer)
+        AccountMeta::new_readonly(pool.amm_config, false), // Amm Config (readonly)
+        AccountMeta::new(swap_state.pool_state, false),    // Pool State
+        AccountMeta::new(*input_token_account, false),     // Input Token Account
+        AccountMeta::new(*output_token_account, false),    // Output Token Account
+        AccountMeta::new(input_vault, false),              // Input Vault
+        AccountMeta::new(output_vault, false),             // Output Vault
+        AccountMeta::new(pool.observation_key, false),     // Observation State
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Token Program (readonly)
+        AccountMeta::new_readonly(accounts::TOKEN_2022_PROGRAM, false), // Token Program 2022 (readonly)
+        AccountMeta::new_readonly(accounts::MEMO_PROGRAM, false),       // Memo Program (readonly)
+        AccountMeta::new_readonly(input_mint, false), // Input Vault Mint (readonly)
+        AccountMeta::new_readonly(output_mint, false), // Output Vault Mint (readonly)
+    ];
+    if let Some(bitmap_extension) = swap_state.bitmap_extension {
+        metas.push(AccountMeta::new_readonly(bitmap_extension, false));
+    }
+    metas.extend(
+        swap_state
+            .tick_array_addresses(quote)
+            .into_iter()
+            .map(|tick_array| AccountMeta::new(tick_array, false)),
+    );
+    metas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::raydium_clmm::{
+        common::get_tick_array_pda, pool::POOL_STATE_SIZE, tick_array::test_tick_array,
+    };
+
+    #[test]
+    fn test_swap_data_and_accounts() {
+        let data = swap_data(100, 90, true);
+        assert_eq!(data.len(), 41);
+        assert_eq!(&data[..8], SWAP_V2_DISCRIMINATOR);
+        assert_eq!(&data[8..16], &100u64.to_le_bytes());
+        assert_eq!(&data[16..24], &90u64.to_le_bytes());
+        assert_eq!(&data[24..40], &0u128.to_le_bytes());
+        assert_eq!(data[40], 1);
+        assert_eq!(swap_data(5_000, 100, false)[40], 0);
+
+        let mut pool = PoolState::from_bytes(&vec![0u8; POOL_STATE_SIZE]).unwrap();
+        pool.token_mint_0 = accounts::WSOL_TOKEN_ACCOUNT;
+        pool.token_mint_1 = Pubkey::new_unique();
+        pool.token_vault_0 = Pubkey::new_unique();
+        pool.token_vault_1 = Pubkey::new_unique();
+        let pool_state = Pubkey::new_unique();
+        let mut swap_state = SwapState {
+            pool_state,
+            pool,
+            trade_fee_rate: 2500,
+            bitmap_extension: None,
+            tick_arrays: vec![
+                test_tick_array(0, &[]),
+                test_tick_array(-600, &[]),
+                test_tick_array(-1200, &[]),
+            ],
+        };
+        let quote = SwapQuote { tick_arrays_used: 1, ..Default::default() };
+        let (payer, input, output) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        // 多传一个 tick array
+        let metas = swap_accounts(&swap_state, &quote, &payer, &input, &output, true);
+        assert_eq!(metas.len(), 15);
+        assert!(metas[0].is_signer);
+        assert_eq!(metas[5].pubkey, swap_state.pool.token_vault_0);
+        assert_eq!(metas[11].pubkey, accounts::WSOL_TOKEN_ACCOUNT);
+        assert_eq!(metas[13].pubkey, get_tick_array_pda(&pool_state, 0).unwrap());
+        assert_eq!(metas[14].pubkey, get_tick_array_pda(&pool_state, -600).unwrap());
+
+        // bitmap 扩展排在 tick array 之前，反方向交换时金库和 mint 互换
+        let extension = Pubkey::new_unique();
+        swap_state.bitmap_extension = Some(extension);
+        let metas = swap_accounts(&swap_state, &quote, &payer, &input, &output, false);
+        assert_eq!(metas[5].pubkey, swap_state.pool.token_vault_1);
+        assert_eq!(metas[12].pubkey, accounts::WSOL_TOKEN_ACCOUNT);
+        assert!(!metas[13].is_writable);
+        assert_eq!(metas[13].pubkey, extension);
+        assert_eq!(metas.len(), 16);
+    }
+}
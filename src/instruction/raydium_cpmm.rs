@@ -1,25 +1,33 @@
 use anyhow::{anyhow, Result};
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::{instruction::Instruction, signer::Signer};
+use solana_sdk::instruction::{AccountMeta, Instruction};
 use solana_system_interface::instruction::transfer;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use spl_token::instruction::close_account;
 
 use crate::{
+    common::SolanaRpcClient,
     constants::{
         raydium_cpmm::{
             accounts::{self, AMM_CONFIG},
-            SWAP_BASE_IN_DISCRIMINATOR,
+            DEPOSIT_DISCRIMINATOR, INITIALIZE_DISCRIMINATOR, SWAP_BASE_IN_DISCRIMINATOR,
+            SWAP_BASE_OUT_DISCRIMINATOR, WITHDRAW_DISCRIMINATOR,
         },
         trade::trade::DEFAULT_SLIPPAGE,
     },
     trading::{
-        common::utils::get_token_balance,
+        common::{close_wsol, user_token_account, utils::get_token_balance, wrap_wsol},
         core::{
-            params::{BuyParams, RaydiumCpmmParams, SellParams},
+            params::{BuyParams, RaydiumCpmmParams, SellParams, SwapMode},
             traits::InstructionBuilder,
         },
-        raydium_cpmm::common::{get_observation_state_pda, get_pool_pda, get_vault_pda},
+        raydium_cpmm::{
+            common::{
+                get_buy_sol_amount, get_lp_mint_pda, get_observation_state_pda, get_pool_pda,
+                get_sell_token_amount, get_vault_pda,
+            },
+            pool::Pool,
+        },
     },
 };
 
@@ -29,7 +37,7 @@ pub struct RaydiumCpmmInstructionBuilder;
 #[async_trait::async_trait]
 impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
     async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
-        if params.sol_amount == 0 {
+        if params.sol_amount.is_zero() {
             return Err(anyhow!("Amount cannot be zero"));
         }
         self.build_buy_instructions_with_accounts(params).await
@@ -52,28 +60,8 @@ impl RaydiumCpmmInstructionBuilder {
             .downcast_ref::<RaydiumCpmmParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumCpmm"))?;
 
-        let pool_state = if protocol_params.pool_state.is_some() {
-            protocol_params.pool_state.unwrap()
-        } else {
-            let mint_token_in_pool_state_index =
-                protocol_params.mint_token_in_pool_state_index.unwrap_or(1);
-            let mint_token_in_pool_state_index =
-                protocol_params.mint_token_in_pool_state_index.unwrap_or(1);
-            get_pool_pda(
-                &accounts::AMM_CONFIG,
-                if mint_token_in_pool_state_index == 1 {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                } else {
-                    &params.mint
-                },
-                if mint_token_in_pool_state_index == 1 {
-                    &params.mint
-                } else {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                },
-            )
-            .unwrap()
-        };
+        let (pool_state, amm_config) =
+            resolve_pool(params.rpc.as_deref(), protocol_params, &params.mint).await?;
 
         let wsol_token_account = spl_associated_token_account::get_associated_token_address(
             &params.payer.pubkey(),
@@ -90,23 +78,7 @@ impl RaydiumCpmmInstructionBuilder {
 
         let observation_state_account = get_observation_state_pda(&pool_state).unwrap();
 
-        let amount_in: u64 = params.sol_amount;
-        let mut minimum_amount_out: u64 = if protocol_params.minimum_amount_out.is_some() {
-            protocol_params.minimum_amount_out.unwrap()
-        } else {
-            println!("未提供minimum_amount_out，使用默认值0");
-            0
-        };
-        if minimum_amount_out != 0 {
-            let slippage_basis_points: u64 = if params.slippage_basis_points.is_some() {
-                params.slippage_basis_points.unwrap()
-            } else {
-                DEFAULT_SLIPPAGE
-            } as u64;
-            minimum_amount_out = minimum_amount_out * (10000 - slippage_basis_points) / 10000;
-            println!("slippage_basis_points: {}", slippage_basis_points);
-        }
-        println!("minimum_amount_out: {}", minimum_amount_out);
+        let amount_in: u64 = params.sol_amount.as_u64();
 
         let mut instructions = vec![];
 
@@ -145,8 +117,8 @@ impl RaydiumCpmmInstructionBuilder {
         let accounts = vec![
             solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(AMM_CONFIG.clone(), false), // Amm Config (readonly)
-            solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
+            solana_sdk::instruction::AccountMeta::new_readonly(amm_config, false), // Amm Config (readonly)
+            solana_sdk::instruction::AccountMeta::new(pool_state, false),          // Pool State
             solana_sdk::instruction::AccountMeta::new(wsol_token_account, false), // Input Token Account
             solana_sdk::instruction::AccountMeta::new(mint_token_account, false), // Output Token Account
             solana_sdk::instruction::AccountMeta::new(wsol_vault_account, false), // Input Vault Account
@@ -161,10 +133,16 @@ impl RaydiumCpmmInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(observation_state_account, false), // Observation State Account
         ];
         // 创建指令数据
-        let mut data = vec![];
-        data.extend_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        let data = quote_swap_data(
+            params.rpc.as_deref(),
+            &pool_state,
+            protocol_params,
+            params.slippage_basis_points,
+            params.swap_mode,
+            amount_in,
+            true,
+        )
+        .await?;
 
         instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
 
@@ -197,26 +175,7 @@ impl RaydiumCpmmInstructionBuilder {
             .downcast_ref::<RaydiumCpmmParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumCpmm"))?;
 
-        let pool_state = if protocol_params.pool_state.is_some() {
-            protocol_params.pool_state.unwrap()
-        } else {
-            let mint_token_in_pool_state_index =
-                protocol_params.mint_token_in_pool_state_index.unwrap_or(1);
-            get_pool_pda(
-                &accounts::AMM_CONFIG,
-                if mint_token_in_pool_state_index == 1 {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                } else {
-                    &params.mint
-                },
-                if mint_token_in_pool_state_index == 1 {
-                    &params.mint
-                } else {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                },
-            )
-            .unwrap()
-        };
+        let pool_state = resolve_pool_state(protocol_params, &params.mint, amm_config);
 
         let wsol_token_account = spl_associated_token_account::get_associated_token_address(
             &params.payer.pubkey(),
@@ -233,23 +192,7 @@ impl RaydiumCpmmInstructionBuilder {
 
         let observation_state_account = get_observation_state_pda(&pool_state).unwrap();
 
-        let amount_in: u64 = params.sol_amount;
-        let mut minimum_amount_out: u64 = if protocol_params.minimum_amount_out.is_some() {
-            protocol_params.minimum_amount_out.unwrap()
-        } else {
-            println!("未提供minimum_amount_out，使用默认值0");
-            0
-        };
-        if minimum_amount_out != 0 {
-            let slippage_basis_points: u64 = if params.slippage_basis_points.is_some() {
-                params.slippage_basis_points.unwrap()
-            } else {
-                DEFAULT_SLIPPAGE
-            } as u64;
-            minimum_amount_out = minimum_amount_out * (10000 - slippage_basis_points) / 10000;
-            println!("slippage_basis_points: {}", slippage_basis_points);
-        }
-        println!("minimum_amount_out: {}", minimum_amount_out);
+        let amount_in: u64 = params.sol_amount.as_u64();
 
         let mut instructions = vec![];
 
@@ -303,7 +246,7 @@ impl RaydiumCpmmInstructionBuilder {
         let accounts = vec![
             solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(amm_config.clone(), false), // Amm Config (readonly)
+            solana_sdk::instruction::AccountMeta::new_readonly(*amm_config, false), // Amm Config (readonly)
             solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
             solana_sdk::instruction::AccountMeta::new(wsol_token_account, false), // Input Token Account
             solana_sdk::instruction::AccountMeta::new(mint_token_account, false), // Output Token Account
@@ -319,10 +262,16 @@ impl RaydiumCpmmInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(observation_state_account, false), // Observation State Account
         ];
         // 创建指令数据
-        let mut data = vec![];
-        data.extend_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        let data = quote_swap_data(
+            params.rpc.as_deref(),
+            &pool_state,
+            protocol_params,
+            params.slippage_basis_points,
+            params.swap_mode,
+            amount_in,
+            true,
+        )
+        .await?;
 
         instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
 
@@ -372,45 +321,8 @@ impl RaydiumCpmmInstructionBuilder {
             return Err(anyhow!("Amount cannot be zero"));
         }
 
-        let mut minimum_amount_out: u64 = if protocol_params.minimum_amount_out.is_some() {
-            protocol_params.minimum_amount_out.unwrap()
-        } else {
-            println!("未提供minimum_amount_out，使用默认值0");
-            0
-        };
-        if minimum_amount_out != 0 {
-            let slippage_basis_points: u64 = if params.slippage_basis_points.is_some() {
-                params.slippage_basis_points.unwrap()
-            } else {
-                DEFAULT_SLIPPAGE
-            } as u64;
-            minimum_amount_out = minimum_amount_out * (10000 - slippage_basis_points) / 10000;
-            println!("slippage_basis_points: {}", slippage_basis_points);
-        }
-        println!("minimum_amount_out: {}", minimum_amount_out);
-
-        let pool_state = if protocol_params.pool_state.is_some() {
-            protocol_params.pool_state.unwrap()
-        } else {
-            let mint_token_in_pool_state_index =
-                protocol_params.mint_token_in_pool_state_index.unwrap_or(1);
-            let mint_token_in_pool_state_index =
-                protocol_params.mint_token_in_pool_state_index.unwrap_or(1);
-            get_pool_pda(
-                &accounts::AMM_CONFIG,
-                if mint_token_in_pool_state_index == 1 {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                } else {
-                    &params.mint
-                },
-                if mint_token_in_pool_state_index == 1 {
-                    &params.mint
-                } else {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                },
-            )
-            .unwrap()
-        };
+        let (pool_state, amm_config) =
+            resolve_pool(params.rpc.as_deref(), protocol_params, &params.mint).await?;
 
         let wsol_token_account = spl_associated_token_account::get_associated_token_address(
             &params.payer.pubkey(),
@@ -444,8 +356,8 @@ impl RaydiumCpmmInstructionBuilder {
         let accounts = vec![
             solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::AMM_CONFIG, false), // Amm Config (readonly)
-            solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
+            solana_sdk::instruction::AccountMeta::new_readonly(amm_config, false), // Amm Config (readonly)
+            solana_sdk::instruction::AccountMeta::new(pool_state, false),          // Pool State
             solana_sdk::instruction::AccountMeta::new(mint_token_account, false), // Input Token Account
             solana_sdk::instruction::AccountMeta::new(wsol_token_account, false), // Output Token Account
             solana_sdk::instruction::AccountMeta::new(mint_vault_account, false), // Input Vault Account
@@ -460,10 +372,16 @@ impl RaydiumCpmmInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(observation_state_account, false), // Observation State Account
         ];
         // 创建指令数据
-        let mut data = vec![];
-        data.extend_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
-        data.extend_from_slice(&amount.to_le_bytes());
-        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        let data = quote_swap_data(
+            params.rpc.as_deref(),
+            &pool_state,
+            protocol_params,
+            params.slippage_basis_points,
+            params.swap_mode,
+            amount,
+            false,
+        )
+        .await?;
 
         instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
 
@@ -513,43 +431,7 @@ impl RaydiumCpmmInstructionBuilder {
             return Err(anyhow!("Amount cannot be zero"));
         }
 
-        let mut minimum_amount_out: u64 = if protocol_params.minimum_amount_out.is_some() {
-            protocol_params.minimum_amount_out.unwrap()
-        } else {
-            println!("未提供minimum_amount_out，使用默认值0");
-            0
-        };
-        if minimum_amount_out != 0 {
-            let slippage_basis_points: u64 = if params.slippage_basis_points.is_some() {
-                params.slippage_basis_points.unwrap()
-            } else {
-                DEFAULT_SLIPPAGE
-            } as u64;
-            minimum_amount_out = minimum_amount_out * (10000 - slippage_basis_points) / 10000;
-            println!("slippage_basis_points: {}", slippage_basis_points);
-        }
-        println!("minimum_amount_out: {}", minimum_amount_out);
-
-        let pool_state = if protocol_params.pool_state.is_some() {
-            protocol_params.pool_state.unwrap()
-        } else {
-            let mint_token_in_pool_state_index =
-                protocol_params.mint_token_in_pool_state_index.unwrap_or(1);
-            get_pool_pda(
-                &accounts::AMM_CONFIG,
-                if mint_token_in_pool_state_index == 1 {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                } else {
-                    &params.mint
-                },
-                if mint_token_in_pool_state_index == 1 {
-                    &params.mint
-                } else {
-                    &accounts::WSOL_TOKEN_ACCOUNT
-                },
-            )
-            .unwrap()
-        };
+        let pool_state = resolve_pool_state(protocol_params, &params.mint, amm_config);
 
         let wsol_token_account = spl_associated_token_account::get_associated_token_address(
             &params.payer.pubkey(),
@@ -583,7 +465,7 @@ impl RaydiumCpmmInstructionBuilder {
         let accounts = vec![
             solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(amm_config.clone(), false), // Amm Config (readonly)
+            solana_sdk::instruction::AccountMeta::new_readonly(*amm_config, false), // Amm Config (readonly)
             solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
             solana_sdk::instruction::AccountMeta::new(mint_token_account, false), // Input Token Account
             solana_sdk::instruction::AccountMeta::new(wsol_token_account, false), // Output Token Account
@@ -599,10 +481,16 @@ impl RaydiumCpmmInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(observation_state_account, false), // Observation State Account
         ];
         // 创建指令数据
-        let mut data = vec![];
-        data.extend_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
-        data.extend_from_slice(&amount.to_le_bytes());
-        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        let data = quote_swap_data(
+            params.rpc.as_deref(),
+            &pool_state,
+            protocol_params,
+            params.slippage_basis_points,
+            params.swap_mode,
+            amount,
+            false,
+        )
+        .await?;
 
         instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
 
@@ -767,3 +655,366 @@ impl RaydiumCpmmInstructionBuilder {
     //     Ok(instructions)
     // }
 }
+
+/// 确定池子地址和所属的 AmmConfig
+///
+/// 参数中未提供的部分从链上读取：有 pool_state 时读取池子的 amm_config，两者都没有时查找 mint
+/// 储备最深的 WSOL 池子。没有 rpc 时使用默认的 `AMM_CONFIG` 推导池子地址。
+async fn resolve_pool(
+    rpc: Option<&SolanaRpcClient>,
+    protocol_params: &RaydiumCpmmParams,
+    mint: &Pubkey,
+) -> Result<(Pubkey, Pubkey)> {
+    match (protocol_params.pool_state, protocol_params.amm_config, rpc) {
+        (Some(pool_state), Some(amm_config), _) => Ok((pool_state, amm_config)),
+        (Some(pool_state), None, Some(rpc)) => {
+            Ok((pool_state, Pool::fetch(rpc, &pool_state).await?.amm_config))
+        }
+        (None, None, Some(rpc)) => {
+            let (pool_state, pool) = Pool::find_by_mint(rpc, mint).await?;
+            Ok((pool_state, pool.amm_config))
+        }
+        (_, amm_config, _) => {
+            let amm_config = amm_config.unwrap_or(AMM_CONFIG);
+            Ok((resolve_pool_state(protocol_params, mint, &amm_config), amm_config))
+        }
+    }
+}
+
+/// 未提供 pool_state 时按 AmmConfig 和 mint 在池子中的位置推导池子地址
+fn resolve_pool_state(
+    protocol_params: &RaydiumCpmmParams,
+    mint: &Pubkey,
+    amm_config: &Pubkey,
+) -> Pubkey {
+    if let Some(pool_state) = protocol_params.pool_state {
+        return pool_state;
+    }
+    let (token0_mint, token1_mint) =
+        if protocol_params.mint_token_in_pool_state_index.unwrap_or(1) == 1 {
+            (&accounts::WSOL_TOKEN_ACCOUNT, mint)
+        } else {
+            (mint, &accounts::WSOL_TOKEN_ACCOUNT)
+        };
+    get_pool_pda(amm_config, token0_mint, token1_mint).unwrap()
+}
+
+/// 根据交换模式计算交换指令数据
+///
+/// `ExactIn` 时 `minimum_amount_out` 按滑点折算；`ExactOut` 时 `amount_in` 是最多花费或卖出的数量，
+/// 当前报价已超过上限时直接返回错误。
+async fn quote_swap_data(
+    rpc: Option<&SolanaRpcClient>,
+    pool_state: &Pubkey,
+    protocol_params: &RaydiumCpmmParams,
+    slippage_basis_points: Option<u64>,
+    swap_mode: SwapMode,
+    amount_in: u64,
+    is_buy: bool,
+) -> Result<Vec<u8>> {
+    let SwapMode::ExactOut(amount_out) = swap_mode else {
+        let mut minimum_amount_out = protocol_params.minimum_amount_out.unwrap_or_else(|| {
+            println!("未提供minimum_amount_out，使用默认值0");
+            0
+        });
+        if minimum_amount_out != 0 {
+            let slippage_basis_points = slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+            minimum_amount_out = minimum_amount_out * (10000 - slippage_basis_points) / 10000;
+            println!("slippage_basis_points: {}", slippage_basis_points);
+        }
+        println!("minimum_amount_out: {}", minimum_amount_out);
+        return Ok(swap_data(swap_mode, amount_in, minimum_amount_out));
+    };
+
+    let rpc = rpc.ok_or_else(|| anyhow!("RPC is not set"))?;
+    if is_buy {
+        let required = get_buy_sol_amount(rpc, pool_state, amount_out).await?;
+        if required > amount_in {
+            return Err(anyhow!(
+                "Buying {} tokens costs {} lamports, more than the limit {}",
+                amount_out,
+                required,
+                amount_in
+            ));
+        }
+    } else {
+        let required = get_sell_token_amount(rpc, pool_state, amount_out).await?;
+        if required > amount_in {
+            return Err(anyhow!(
+                "Receiving {} lamports needs {} tokens, more than the limit {}",
+                amount_out,
+                required,
+                amount_in
+            ));
+        }
+    }
+    Ok(swap_data(swap_mode, amount_in, 0))
+}
+
+/// 交换指令数据，`ExactOut` 时使用 `swap_base_output`，`amount_in` 作为最大输入
+fn swap_data(swap_mode: SwapMode, amount_in: u64, minimum_amount_out: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(24);
+    match swap_mode {
+        SwapMode::ExactIn => {
+            data.extend_from_slice(SWAP_BASE_IN_DISCRIMINATOR);
+            data.extend_from_slice(&amount_in.to_le_bytes());
+            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        }
+        SwapMode::ExactOut(amount_out) => {
+            // swap_base_output(max_amount_in, amount_out)
+            data.extend_from_slice(SWAP_BASE_OUT_DISCRIMINATOR);
+            data.extend_from_slice(&amount_in.to_le_bytes());
+            data.extend_from_slice(&amount_out.to_le_bytes());
+        }
+    }
+    data
+}
+
+/// 创建池子时一侧代币的信息
+#[derive(Debug, Clone, Copy)]
+pub struct PoolTokenInput {
+    pub mint: Pubkey,
+    /// 代币所属的 token program，Token-2022 代币需要传 `TOKEN_2022_PROGRAM`
+    pub token_program: Pubkey,
+    /// 初始存入数量
+    pub amount: u64,
+}
+
+/// 构建 `initialize` 指令，使用指定的 AmmConfig 创建池子
+///
+/// 两侧代币按 mint 地址排序为 token0/token1，创建者得到初始 LP。`open_time` 之前池子不能交易，
+/// 传 0 表示立即开放。返回指令和池子地址。
+pub fn initialize(
+    creator: &Pubkey,
+    amm_config: &Pubkey,
+    token_a: PoolTokenInput,
+    token_b: PoolTokenInput,
+    open_time: u64,
+    auto_handle_wsol: bool,
+) -> Result<(Vec<Instruction>, Pubkey)> {
+    if token_a.mint == token_b.mint {
+        return Err(anyhow!("Pool tokens must be different"));
+    }
+    let (token0, token1) =
+        if token_a.mint < token_b.mint { (token_a, token_b) } else { (token_b, token_a) };
+    let pool_state = get_pool_pda(amm_config, &token0.mint, &token1.mint)
+        .ok_or_else(|| anyhow!("Failed to derive the pool address"))?;
+    let lp_mint =
+        get_lp_mint_pda(&pool_state).ok_or_else(|| anyhow!("Failed to derive the LP mint"))?;
+    let token0_vault = get_vault_pda(&pool_state, &token0.mint).unwrap();
+    let token1_vault = get_vault_pda(&pool_state, &token1.mint).unwrap();
+    let observation_state = get_observation_state_pda(&pool_state).unwrap();
+
+    let mut instructions = vec![];
+    let wsol_amount = wsol_side_amount(&token0.mint, &token1.mint, token0.amount, token1.amount);
+    if auto_handle_wsol {
+        if let Some(amount) = wsol_amount {
+            instructions.extend(wrap_wsol(creator, amount));
+        }
+    }
+
+    let accounts = vec![
+        AccountMeta::new(*creator, true),
+        AccountMeta::new_readonly(*amm_config, false),
+        AccountMeta::new_readonly(accounts::AUTHORITY, false),
+        AccountMeta::new(pool_state, false),
+        AccountMeta::new_readonly(token0.mint, false),
+        AccountMeta::new_readonly(token1.mint, false),
+        AccountMeta::new(lp_mint, false),
+        AccountMeta::new(user_token_account(creator, &token0.mint, &token0.token_program), false),
+        AccountMeta::new(user_token_account(creator, &token1.mint, &token1.token_program), false),
+        AccountMeta::new(user_token_account(creator, &lp_mint, &accounts::TOKEN_PROGRAM), false),
+        AccountMeta::new(token0_vault, false),
+        AccountMeta::new(token1_vault, false),
+        AccountMeta::new(accounts::CREATE_POOL_FEE_RECEIVER, false),
+        AccountMeta::new(observation_state, false),
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),
+        AccountMeta::new_readonly(token0.token_program, false),
+        AccountMeta::new_readonly(token1.token_program, false),
+        AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::RENT, false),
+    ];
+    let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&token0.amount.to_le_bytes());
+    data.extend_from_slice(&token1.amount.to_le_bytes());
+    data.extend_from_slice(&open_time.to_le_bytes());
+    instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
+
+    if auto_handle_wsol && wsol_amount.is_some() {
+        instructions.push(close_wsol(creator));
+    }
+    Ok((instructions, pool_state))
+}
+
+/// 构建 `deposit` 指令，精确铸造 `lp_token_amount` 个 LP，两侧存入数量不超过给定上限
+///
+/// 会按需创建 LP 代币账户。`auto_handle_wsol` 时按 wSOL 一侧的上限包装，交易结束后关闭账户退回剩余部分。
+pub fn deposit(
+    owner: &Pubkey,
+    pool_state: &Pubkey,
+    pool: &Pool,
+    lp_token_amount: u64,
+    maximum_token0_amount: u64,
+    maximum_token1_amount: u64,
+    auto_handle_wsol: bool,
+) -> Vec<Instruction> {
+    let mut instructions = vec![create_associated_token_account_idempotent(
+        owner,
+        owner,
+        &pool.lp_mint,
+        &accounts::TOKEN_PROGRAM,
+    )];
+    let wsol_amount = wsol_side_amount(
+        &pool.token0_mint,
+        &pool.token1_mint,
+        maximum_token0_amount,
+        maximum_token1_amount,
+    );
+    if auto_handle_wsol {
+        if let Some(amount) = wsol_amount {
+            instructions.extend(wrap_wsol(owner, amount));
+        }
+    }
+
+    let mut data = DEPOSIT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&lp_token_amount.to_le_bytes());
+    data.extend_from_slice(&maximum_token0_amount.to_le_bytes());
+    data.extend_from_slice(&maximum_token1_amount.to_le_bytes());
+    instructions.push(Instruction {
+        program_id: accounts::RAYDIUM_CPMM,
+        accounts: liquidity_accounts(owner, pool_state, pool),
+        data,
+    });
+
+    if auto_handle_wsol && wsol_amount.is_some() {
+        instructions.push(close_wsol(owner));
+    }
+    instructions
+}
+
+/// 构建 `withdraw` 指令，销毁 `lp_token_amount` 个 LP，两侧取回数量不低于给定下限
+///
+/// 会按需创建两侧的代币账户。`auto_handle_wsol` 时交易结束后把取回的 wSOL 解包为 SOL。
+pub fn withdraw(
+    owner: &Pubkey,
+    pool_state: &Pubkey,
+    pool: &Pool,
+    lp_token_amount: u64,
+    minimum_token0_amount: u64,
+    minimum_token1_amount: u64,
+    auto_handle_wsol: bool,
+) -> Vec<Instruction> {
+    let mut instructions = vec![
+        create_associated_token_account_idempotent(
+            owner,
+            owner,
+            &pool.token0_mint,
+            &pool.token0_program,
+        ),
+        create_associated_token_account_idempotent(
+            owner,
+            owner,
+            &pool.token1_mint,
+            &pool.token1_program,
+        ),
+    ];
+
+    let mut data = WITHDRAW_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&lp_token_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_token0_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_token1_amount.to_le_bytes());
+    let mut accounts = liquidity_accounts(owner, pool_state, pool);
+    accounts.push(AccountMeta::new_readonly(accounts::MEMO_PROGRAM, false));
+    instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
+
+    let has_wsol = pool.token0_mint == accounts::WSOL_TOKEN_ACCOUNT
+        || pool.token1_mint == accounts::WSOL_TOKEN_ACCOUNT;
+    if auto_handle_wsol && has_wsol {
+        instructions.push(close_wsol(owner));
+    }
+    instructions
+}
+
+/// `deposit` 和 `withdraw` 共用的账户，`withdraw` 还需要在末尾加上 memo program
+fn liquidity_accounts(owner: &Pubkey, pool_state: &Pubkey, pool: &Pool) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(accounts::AUTHORITY, false),
+        AccountMeta::new(*pool_state, false),
+        AccountMeta::new(user_token_account(owner, &pool.lp_mint, &accounts::TOKEN_PROGRAM), false),
+        AccountMeta::new(user_token_account(owner, &pool.token0_mint, &pool.token0_program), false),
+        AccountMeta::new(user_token_account(owner, &pool.token1_mint, &pool.token1_program), false),
+        AccountMeta::new(pool.token0_vault, false),
+        AccountMeta::new(pool.token1_vault, false),
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(pool.token0_mint, false),
+        AccountMeta::new_readonly(pool.token1_mint, false),
+        AccountMeta::new(pool.lp_mint, false),
+    ]
+}
+
+/// 一侧为 wSOL 时需要包装的数量
+fn wsol_side_amount(
+    token0_mint: &Pubkey,
+    token1_mint: &Pubkey,
+    token0_amount: u64,
+    token1_amount: u64,
+) -> Option<u64> {
+    if *token0_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        Some(token0_amount)
+    } else if *token1_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        Some(token1_amount)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_data() {
+        let exact_in = swap_data(SwapMode::ExactIn, 100, 90);
+        assert_eq!(&exact_in[..8], SWAP_BASE_IN_DISCRIMINATOR);
+        assert_eq!(&exact_in[16..], &90u64.to_le_bytes());
+
+        let exact_out = swap_data(SwapMode::ExactOut(5_000), 100, 0);
+        assert_eq!(&exact_out[..8], SWAP_BASE_OUT_DISCRIMINATOR);
+        assert_eq!(&exact_out[8..16], &100u64.to_le_bytes());
+        assert_eq!(&exact_out[16..], &5_000u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_initialize_sorts_tokens() {
+        let creator = Pubkey::new_unique();
+        let token = PoolTokenInput {
+            mint: Pubkey::new_unique(),
+            token_program: accounts::TOKEN_PROGRAM,
+            amount: 1_000,
+        };
+        let sol = PoolTokenInput {
+            mint: accounts::WSOL_TOKEN_ACCOUNT,
+            token_program: accounts::TOKEN_PROGRAM,
+            amount: 2_000,
+        };
+        let (instructions, pool_state) =
+            initialize(&creator, &AMM_CONFIG, token, sol, 0, true).unwrap();
+        let (reversed, reversed_pool_state) =
+            initialize(&creator, &AMM_CONFIG, sol, token, 0, true).unwrap();
+        assert_eq!(pool_state, reversed_pool_state);
+
+        // wSOL 包装三条、initialize、关闭 wSOL
+        assert_eq!(instructions.len(), 5);
+        let ix = &instructions[3];
+        assert_eq!(ix.data, reversed[3].data);
+        assert_eq!(&ix.data[..8], INITIALIZE_DISCRIMINATOR);
+        let (token0, token1) = if token.mint < sol.mint { (token, sol) } else { (sol, token) };
+        assert_eq!(ix.accounts[4].pubkey, token0.mint);
+        assert_eq!(&ix.data[8..16], &token0.amount.to_le_bytes());
+        assert_eq!(&ix.data[16..24], &token1.amount.to_le_bytes());
+        assert_eq!(ix.accounts.len(), 20);
+    }
+}
@@ -7,30 +7,64 @@ pub mod trading;
 pub mod utils;
 pub use solana_streamer_sdk;
 
-use crate::swqos::SwqosConfig;
+use crate::constants::swqos::SWQOS_WARM_UP_TIMEOUT;
+use crate::constants::trade::trade::DEFAULT_SLIPPAGE;
+use crate::swqos::{
+    sender_pool::start_sender_pool, RateLimitedClient, SwqosConfig, SwqosHealthStats,
+};
+use crate::trading::common::{
+    is_using_nonce,
+    utils::{calculate_with_slippage_buy, calculate_with_slippage_sell},
+};
+use crate::trading::core::executor::MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
+use crate::trading::core::parallel::parallel_execute_with_tips;
+use crate::trading::core::params::BonkCreateParams;
 use crate::trading::core::params::BonkParams;
 use crate::trading::core::params::PumpFunParams;
 use crate::trading::core::params::PumpSwapParams;
+use crate::trading::core::params::RaydiumAmmV4Params;
+use crate::trading::core::params::RaydiumClmmParams;
 use crate::trading::core::params::RaydiumCpmmParams;
 use crate::trading::core::traits::ProtocolParams;
 use crate::trading::factory::DexType;
+use crate::trading::pumpswap::common::{get_deposit_amounts, get_withdraw_amounts};
+use crate::trading::pumpswap::pool::Pool as PumpSwapPool;
+use crate::trading::raydium_cpmm::pool::Pool as RaydiumCpmmPool;
+use crate::trading::BundleBuyResult;
 use crate::trading::BuyParams;
+use crate::trading::CreateParams;
 use crate::trading::SellParams;
+use crate::trading::SwapMode;
+use crate::trading::TokenMetadata;
 use crate::trading::TradeFactory;
-use common::{PriorityFee, SolanaRpcClient, TradeConfig};
+use crate::trading::TradeTemplate;
+use common::wallet_pool::split_amount;
+use common::{
+    AsyncTradeSigner, Lamports, PriorityFee, SolanaRpcClient, TradeConfig, TradeContext,
+    VanityKeypairPool, WalletPool,
+};
 use rustls::crypto::{ring::default_provider, CryptoProvider};
 use solana_sdk::hash::Hash;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
 use std::sync::Arc;
 use std::sync::Mutex;
-use swqos::SwqosClient;
+use swqos::{SwqosClient, SwqosType, TradeType};
 
 pub struct SolanaTrade {
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn AsyncTradeSigner>,
+    /// Optional wallet that pays fees and tips instead of `payer`
+    pub fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     pub rpc: Arc<SolanaRpcClient>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
     pub priority_fee: PriorityFee,
     pub trade_config: TradeConfig,
+    /// Nonce, tip and address lookup table state owned by this instance
+    pub context: TradeContext,
+    /// Additional trading wallets, see `with_wallet_pool`
+    pub wallet_pool: Arc<WalletPool>,
+    /// Pre-ground mint keypairs used by `create` when no mint is given
+    pub vanity_pool: Option<Arc<VanityKeypairPool>>,
 }
 
 static INSTANCE: Mutex<Option<Arc<SolanaTrade>>> = Mutex::new(None);
@@ -39,17 +73,40 @@ impl Clone for SolanaTrade {
     fn clone(&self) -> Self {
         Self {
             payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
             rpc: self.rpc.clone(),
             swqos_clients: self.swqos_clients.clone(),
             priority_fee: self.priority_fee.clone(),
             trade_config: self.trade_config.clone(),
+            context: self.context.clone(),
+            wallet_pool: self.wallet_pool.clone(),
+            vanity_pool: self.vanity_pool.clone(),
         }
     }
 }
 
 impl SolanaTrade {
+    /// Create a client with its own nonce, tip and address lookup table state
+    ///
+    /// Several instances can live in one process; they do not share any state. Returns an
+    /// error when a SWQOS provider cannot be created, e.g. an endpoint header is invalid.
     #[inline]
-    pub async fn new(payer: Arc<Keypair>, mut trade_config: TradeConfig) -> Self {
+    pub async fn new(
+        payer: Arc<dyn AsyncTradeSigner>,
+        trade_config: TradeConfig,
+    ) -> Result<Self, anyhow::Error> {
+        Self::new_with_context(payer, trade_config, TradeContext::new()).await
+    }
+
+    /// Create a client on top of an existing `TradeContext`
+    ///
+    /// Use this when several wallets should deliberately share one nonce account
+    /// or one set of cached lookup tables.
+    pub async fn new_with_context(
+        payer: Arc<dyn AsyncTradeSigner>,
+        trade_config: TradeConfig,
+        context: TradeContext,
+    ) -> Result<Self, anyhow::Error> {
         if CryptoProvider::get_default().is_none() {
             let _ = default_provider()
                 .install_default()
@@ -58,48 +115,52 @@ impl SolanaTrade {
 
         let rpc_url = trade_config.rpc_url.clone();
         let swqos_configs = trade_config.swqos_configs.clone();
-        let mut priority_fee = trade_config.priority_fee.clone();
+        let priority_fee = trade_config.priority_fee.clone();
         let commitment = trade_config.commitment.clone();
-        if priority_fee.buy_tip_fees.len() < swqos_configs.len() {
-            // 补齐数组,只补齐缺少的
-            let mut buy_tip_fees = priority_fee.buy_tip_fees.clone();
-            let default_fee = priority_fee.buy_tip_fee;
-            // 计算需要补充的元素数量
-            let missing_count = swqos_configs.len() - buy_tip_fees.len();
-            // 添加缺少的元素，使用默认值
-            for _ in 0..missing_count {
-                buy_tip_fees.push(default_fee);
-            }
-            // 更新 priority_fee 中的 buy_tip_fees
-            priority_fee.buy_tip_fees = buy_tip_fees;
-            trade_config.priority_fee = priority_fee.clone();
-        }
 
         let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
 
-        for swqos in swqos_configs {
+        for swqos in swqos_configs.iter() {
             let swqos_client =
-                SwqosConfig::get_swqos_client(rpc_url.clone(), commitment.clone(), swqos.clone());
+                SwqosConfig::get_swqos_client(rpc_url.clone(), commitment.clone(), swqos.clone())?;
             swqos_clients.push(swqos_client);
         }
 
+        // 每个服务商一个绑核发送线程，限流在调用方申请许可后再交给发送线程
+        // 返回前等待连接预热，第一笔交易不再承担 TLS/HTTP2 握手耗时
+        let swqos_clients =
+            start_sender_pool(swqos_clients, trade_config.keepalive_interval, SWQOS_WARM_UP_TIMEOUT)
+                .await;
+        let swqos_clients: Vec<Arc<SwqosClient>> = swqos_clients
+            .into_iter()
+            .zip(swqos_configs.iter())
+            .map(|(swqos_client, swqos)| {
+                match trade_config.rate_limits.get(&swqos.swqos_type()) {
+                    Some(rate_limit) => Ok(Arc::new(RateLimitedClient::new(
+                        swqos_client,
+                        *rate_limit,
+                    )?) as Arc<SwqosClient>),
+                    None => Ok(swqos_client),
+                }
+            })
+            .collect::<Result<_, anyhow::Error>>()?;
+
         let rpc = Arc::new(SolanaRpcClient::new_with_commitment(
             rpc_url.clone(),
             commitment,
         ));
 
-        let instance = Self {
+        Ok(Self {
             payer,
+            fee_payer: None,
             rpc,
             swqos_clients,
             priority_fee,
             trade_config: trade_config.clone(),
-        };
-
-        let mut current = INSTANCE.lock().unwrap();
-        *current = Some(Arc::new(instance.clone()));
-
-        instance
+            context,
+            wallet_pool: Arc::new(WalletPool::default()),
+            vanity_pool: None,
+        })
     }
 
     /// Get the RPC client instance
@@ -107,13 +168,113 @@ impl SolanaTrade {
         &self.rpc
     }
 
-    /// Get the current instance
-    pub fn get_instance() -> Arc<Self> {
-        let instance = INSTANCE.lock().unwrap();
-        instance
-            .as_ref()
-            .expect("PumpFun instance not initialized. Please call new() first.")
-            .clone()
+    /// Connection state of each provider, from the warm-up in `new` and the keepalive pings
+    ///
+    /// Check `connected` before a launch to make sure the send path is hot.
+    pub fn get_swqos_health(&self) -> Vec<SwqosHealthStats> {
+        self.swqos_clients.iter().filter_map(|client| client.get_health()).collect()
+    }
+
+    /// Return a copy of this client that only sends through the given provider types
+    ///
+    /// The copy shares the underlying clients, so this is cheap enough to call per trade.
+    /// Returns an error when none of the configured clients matches, since such a copy
+    /// could not send anything.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Dust sell through RPC only
+    /// solana_trade.with_swqos(&[SwqosType::Default])?.sell(...).await?;
+    /// // Snipe through Jito and ZeroSlot
+    /// solana_trade.with_swqos(&[SwqosType::Jito, SwqosType::ZeroSlot])?.buy(...).await?;
+    /// ```
+    pub fn with_swqos(&self, swqos_types: &[SwqosType]) -> Result<Self, anyhow::Error> {
+        let mut trade = self.clone();
+        trade.swqos_clients = self
+            .swqos_clients
+            .iter()
+            .filter(|client| swqos_types.contains(&client.get_swqos_type()))
+            .cloned()
+            .collect();
+        if trade.swqos_clients.is_empty() {
+            return Err(anyhow::anyhow!("No configured SWQOS client matches {:?}", swqos_types));
+        }
+        Ok(trade)
+    }
+
+    /// Return a copy of this client where `fee_payer` pays the transaction fees and tips
+    ///
+    /// `payer` stays the token owner and the SOL source of the trade; both wallets sign.
+    /// A single hot fee-payer wallet can be shared by many trading wallets this way.
+    /// When a nonce account is used, its authority must be the fee payer.
+    pub fn with_fee_payer(&self, fee_payer: Arc<dyn AsyncTradeSigner>) -> Self {
+        let mut trade = self.clone();
+        trade.fee_payer = Some(fee_payer);
+        trade
+    }
+
+    /// Return a copy of this client that trades from `payer`
+    pub fn with_payer(&self, payer: Arc<dyn AsyncTradeSigner>) -> Self {
+        let mut trade = self.clone();
+        trade.payer = payer;
+        trade
+    }
+
+    /// Return a copy of this client that manages the given trading wallets
+    ///
+    /// `payer` stays the default wallet; use `with_pool_wallet`, `with_next_wallet` or
+    /// `buy_split` to trade from the pool.
+    pub fn with_wallet_pool(&self, wallet_pool: WalletPool) -> Self {
+        let mut trade = self.clone();
+        trade.wallet_pool = Arc::new(wallet_pool);
+        trade
+    }
+
+    /// Return a copy of this client that takes new token mints from a vanity keypair pool
+    pub fn with_vanity_pool(&self, vanity_pool: Arc<VanityKeypairPool>) -> Self {
+        let mut trade = self.clone();
+        trade.vanity_pool = Some(vanity_pool);
+        trade
+    }
+
+    /// Return a copy of this client that trades from the pool wallet at `index`
+    pub fn with_pool_wallet(&self, index: usize) -> Result<Self, anyhow::Error> {
+        let payer = self
+            .wallet_pool
+            .get(index)
+            .ok_or_else(|| anyhow::anyhow!("Wallet index {} out of range", index))?;
+        Ok(self.with_payer(payer))
+    }
+
+    /// Return a copy of this client that trades from the next pool wallet, round-robin
+    pub fn with_next_wallet(&self) -> Result<Self, anyhow::Error> {
+        let payer =
+            self.wallet_pool.next_wallet().ok_or_else(|| anyhow::anyhow!("Wallet pool is empty"))?;
+        Ok(self.with_payer(payer))
+    }
+
+    /// Return a copy of this client restricted to a group from `TradeConfig::swqos_groups`
+    pub fn with_swqos_group(&self, name: &str) -> Result<Self, anyhow::Error> {
+        let swqos_types = self
+            .trade_config
+            .swqos_groups
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("SWQOS group not found: {}", name))?;
+        self.with_swqos(swqos_types)
+            .map_err(|_| anyhow::anyhow!("SWQOS group {} has no configured clients", name))
+    }
+
+    /// Register a process-wide default instance for `get_instance`
+    ///
+    /// `new` never does this implicitly, so creating more clients does not replace it.
+    pub fn set_instance(instance: Arc<Self>) {
+        *INSTANCE.lock().unwrap() = Some(instance);
+    }
+
+    /// Get the instance registered with `set_instance`, if any
+    pub fn get_instance() -> Option<Arc<Self>> {
+        INSTANCE.lock().unwrap().clone()
     }
 
     /// Execute a buy order for a specified token
@@ -123,10 +284,10 @@ impl SolanaTrade {
     /// * `dex_type` - The trading protocol to use (PumpFun, PumpSwap, or Bonk)
     /// * `mint` - The public key of the token mint to buy
     /// * `creator` - Optional creator public key for the token (defaults to Pubkey::default() if None)
-    /// * `sol_amount` - Amount of SOL to spend on the purchase (in lamports)
+    /// * `sol_amount` - Amount of SOL to spend on the purchase
     /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
     /// * `recent_blockhash` - Recent blockhash for transaction validity
-    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `custom_buy_tip_fee` - Optional tip fee applied to every provider for this buy
     /// * `extension_params` - Optional protocol-specific parameters (uses defaults if None)
     ///
     /// # Returns
@@ -149,7 +310,7 @@ impl SolanaTrade {
     /// use crate::trading::factory::DexType;
     ///
     /// let mint = Pubkey::new_unique();
-    /// let sol_amount = 1_000_000_000; // 1 SOL in lamports
+    /// let sol_amount = Lamports::new(1_000_000_000); // 1 SOL
     /// let slippage = Some(500); // 5% slippage
     /// let recent_blockhash = Hash::default();
     ///
@@ -169,10 +330,76 @@ impl SolanaTrade {
         dex_type: DexType,
         mint: Pubkey,
         creator: Option<Pubkey>,
-        sol_amount: u64,
+        sol_amount: Lamports,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        custom_buy_tip_fee: Option<Lamports>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<(), anyhow::Error> {
+        self.execute_buy(
+            dex_type,
+            mint,
+            creator,
+            sol_amount,
+            SwapMode::ExactIn,
+            slippage_basis_points,
+            recent_blockhash,
+            custom_buy_tip_fee,
+            extension_params,
+        )
+        .await
+    }
+
+    /// Buy an exact amount of tokens, spending at most `max_sol_amount`
+    ///
+    /// Supported on Bonk, PumpSwap (WSOL-quoted pools), Raydium CPMM, Raydium AMM v4 and Raydium
+    /// CLMM. The transaction fails on-chain if the pool would charge more than `max_sol_amount`,
+    /// and the order is rejected up front when the current quote already exceeds it.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_amount` - Amount of tokens to receive (in smallest token units)
+    /// * `max_sol_amount` - Maximum amount of SOL to spend, including protocol fees
+    ///
+    /// The remaining arguments are the same as for `buy`.
+    pub async fn buy_exact_out(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        token_amount: u64,
+        max_sol_amount: Lamports,
+        recent_blockhash: Hash,
+        custom_buy_tip_fee: Option<Lamports>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<(), anyhow::Error> {
+        if token_amount == 0 {
+            return Err(anyhow::anyhow!("Amount cannot be zero"));
+        }
+        self.execute_buy(
+            dex_type,
+            mint,
+            creator,
+            max_sol_amount,
+            SwapMode::ExactOut(token_amount),
+            None,
+            recent_blockhash,
+            custom_buy_tip_fee,
+            extension_params,
+        )
+        .await
+    }
+
+    async fn execute_buy(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        sol_amount: Lamports,
+        swap_mode: SwapMode,
         slippage_basis_points: Option<u64>,
         recent_blockhash: Hash,
-        custom_buy_tip_fee: Option<f64>,
+        custom_buy_tip_fee: Option<Lamports>,
         extension_params: Option<Box<dyn ProtocolParams>>,
     ) -> Result<(), anyhow::Error> {
         let executor = TradeFactory::create_executor(dex_type.clone());
@@ -186,29 +413,32 @@ impl SolanaTrade {
                 DexType::RaydiumCpmm => {
                     Box::new(RaydiumCpmmParams::default()) as Box<dyn ProtocolParams>
                 }
+                DexType::RaydiumAmmV4 => {
+                    Box::new(RaydiumAmmV4Params::default()) as Box<dyn ProtocolParams>
+                }
+                DexType::RaydiumClmm => {
+                    Box::new(RaydiumClmmParams::default()) as Box<dyn ProtocolParams>
+                }
             }
         };
-        let buy_params = BuyParams {
+        let mut buy_params = BuyParams {
             rpc: Some(self.rpc.clone()),
             payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
             mint: mint,
             creator: creator.unwrap_or(Pubkey::default()),
             sol_amount: sol_amount,
+            swap_mode,
             slippage_basis_points: slippage_basis_points,
             priority_fee: self.trade_config.priority_fee.clone(),
             lookup_table_key: self.trade_config.lookup_table_key,
             recent_blockhash,
             data_size_limit: 0,
             protocol_params: protocol_params.clone(),
+            context: self.context.clone(),
         };
-        let mut priority_fee = buy_params.priority_fee.clone();
-        if custom_buy_tip_fee.is_some() {
-            priority_fee.buy_tip_fee = custom_buy_tip_fee.unwrap();
-            priority_fee.buy_tip_fees = priority_fee
-                .buy_tip_fees
-                .iter()
-                .map(|_| custom_buy_tip_fee.unwrap())
-                .collect();
+        if let Some(custom_buy_tip_fee) = custom_buy_tip_fee {
+            buy_params.priority_fee.override_buy_tip_fee(custom_buy_tip_fee);
         }
         let buy_with_tip_params = buy_params.clone().with_tip(self.swqos_clients.clone());
 
@@ -230,6 +460,14 @@ impl SolanaTrade {
                 .as_any()
                 .downcast_ref::<RaydiumCpmmParams>()
                 .is_some(),
+            DexType::RaydiumAmmV4 => protocol_params
+                .as_any()
+                .downcast_ref::<RaydiumAmmV4Params>()
+                .is_some(),
+            DexType::RaydiumClmm => protocol_params
+                .as_any()
+                .downcast_ref::<RaydiumClmmParams>()
+                .is_some(),
         };
 
         if !is_valid_params {
@@ -239,6 +477,309 @@ impl SolanaTrade {
         executor.buy_with_tip(buy_with_tip_params).await
     }
 
+    /// Split one buy across several pool wallets
+    ///
+    /// `total_sol_amount` is divided evenly over `wallet_count` wallets picked round-robin from
+    /// the wallet pool, and the buys run concurrently. Each wallet's balance cache is cleared
+    /// afterwards. The wallets share this client's durable nonce, so splitting is rejected when
+    /// a nonce account is configured.
+    ///
+    /// # Returns
+    ///
+    /// The result of each wallet's buy, in the order the wallets were picked.
+    pub async fn buy_split(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        total_sol_amount: Lamports,
+        wallet_count: usize,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        custom_buy_tip_fee: Option<Lamports>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<Vec<(Pubkey, Result<(), anyhow::Error>)>, anyhow::Error> {
+        if is_using_nonce(&self.context.nonce_cache) {
+            return Err(anyhow::anyhow!("Split buys do not support durable nonce"));
+        }
+        if wallet_count > self.wallet_pool.len() {
+            return Err(anyhow::anyhow!(
+                "Requested {} wallets but the pool only has {}",
+                wallet_count,
+                self.wallet_pool.len()
+            ));
+        }
+        let amounts = split_amount(total_sol_amount, wallet_count)?;
+
+        let mut wallets = Vec::with_capacity(wallet_count);
+        for _ in 0..wallet_count {
+            wallets.push(self.with_next_wallet()?);
+        }
+
+        let buys = wallets.iter().zip(amounts).map(|(trade, sol_amount)| {
+            trade.buy(
+                dex_type.clone(),
+                mint,
+                creator,
+                sol_amount,
+                slippage_basis_points,
+                recent_blockhash,
+                custom_buy_tip_fee,
+                extension_params.clone(),
+            )
+        });
+        let results = futures::future::join_all(buys).await;
+
+        Ok(wallets
+            .iter()
+            .zip(results)
+            .map(|(trade, result)| {
+                let wallet = trade.payer.pubkey();
+                self.wallet_pool.invalidate(&wallet);
+                (wallet, result)
+            })
+            .collect())
+    }
+
+    /// Buy the same mint from up to 5 wallets atomically in one Jito bundle
+    ///
+    /// Each `(wallet, sol_amount)` pair becomes one transaction, in order; only the last one
+    /// carries the tip. Requires a configured Jito client and no durable nonce. Later wallets
+    /// trade after earlier ones, so the slippage must cover the price impact of the bundle.
+    ///
+    /// # Returns
+    ///
+    /// The signature and confirmation result of each wallet's transaction.
+    pub async fn bundle_buy(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        buys: Vec<(Arc<dyn AsyncTradeSigner>, Lamports)>,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        custom_buy_tip_fee: Option<Lamports>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<Vec<BundleBuyResult>, anyhow::Error> {
+        let jito_client = self
+            .swqos_clients
+            .iter()
+            .find(|client| client.get_swqos_type() == SwqosType::Jito)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Bundle buy requires a Jito client"))?;
+        let protocol_params = match extension_params {
+            Some(params) => params,
+            None => match dex_type {
+                DexType::PumpFun => Box::new(PumpFunParams::default()) as Box<dyn ProtocolParams>,
+                DexType::PumpSwap => Box::new(PumpSwapParams::default()) as Box<dyn ProtocolParams>,
+                DexType::Bonk => Box::new(BonkParams::default()) as Box<dyn ProtocolParams>,
+                DexType::RaydiumCpmm => {
+                    Box::new(RaydiumCpmmParams::default()) as Box<dyn ProtocolParams>
+                }
+                DexType::RaydiumAmmV4 => {
+                    Box::new(RaydiumAmmV4Params::default()) as Box<dyn ProtocolParams>
+                }
+                DexType::RaydiumClmm => {
+                    Box::new(RaydiumClmmParams::default()) as Box<dyn ProtocolParams>
+                }
+            },
+        };
+        let mut priority_fee = self.trade_config.priority_fee.for_swqos(SwqosType::Jito);
+        if let Some(custom_buy_tip_fee) = custom_buy_tip_fee {
+            priority_fee.override_buy_tip_fee(custom_buy_tip_fee);
+        }
+
+        let params = buys
+            .into_iter()
+            .map(|(payer, sol_amount)| BuyParams {
+                rpc: Some(self.rpc.clone()),
+                payer,
+                fee_payer: self.fee_payer.clone(),
+                mint,
+                creator: creator.unwrap_or_default(),
+                sol_amount,
+                swap_mode: SwapMode::ExactIn,
+                slippage_basis_points,
+                priority_fee: priority_fee.clone(),
+                lookup_table_key: self.trade_config.lookup_table_key,
+                recent_blockhash,
+                data_size_limit: 0,
+                protocol_params: protocol_params.clone(),
+                context: self.context.clone(),
+            })
+            .collect();
+        let results = trading::core::bundle::bundle_buy(dex_type, params, jito_client).await?;
+        for result in &results {
+            self.wallet_pool.invalidate(&result.wallet);
+        }
+        Ok(results)
+    }
+
+    /// Create a new token
+    ///
+    /// The payer becomes the token creator. `mint` is the new mint account and signs the
+    /// transaction together with the payer. When it is `None`, a keypair is taken from the
+    /// vanity pool if one is configured, otherwise a random keypair is used. The transaction is
+    /// sent through every configured provider with tips, like a buy.
+    ///
+    /// `DexType::PumpFun` and `DexType::Bonk` support token creation. Bonk launches take a
+    /// `BonkCreateParams` in `extension_params`; the letsbonk defaults are used if None.
+    ///
+    /// # Returns
+    ///
+    /// The address of the new mint.
+    pub async fn create(
+        &self,
+        dex_type: DexType,
+        mint: Option<Arc<dyn AsyncTradeSigner>>,
+        metadata: TokenMetadata,
+        recent_blockhash: Hash,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<Pubkey, anyhow::Error> {
+        self.create_and_buy(
+            dex_type,
+            mint,
+            metadata,
+            Lamports::ZERO,
+            None,
+            recent_blockhash,
+            None,
+            extension_params,
+        )
+        .await
+    }
+
+    /// Create a new token and buy it from the creator in the same transaction
+    ///
+    /// The dev buy is priced against the initial bonding curve, so nobody can trade before it.
+    /// A zero `dev_buy_sol_amount` only creates the token. `mint` is resolved as in `create`.
+    pub async fn create_and_buy(
+        &self,
+        dex_type: DexType,
+        mint: Option<Arc<dyn AsyncTradeSigner>>,
+        metadata: TokenMetadata,
+        dev_buy_sol_amount: Lamports,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        custom_buy_tip_fee: Option<Lamports>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<Pubkey, anyhow::Error> {
+        let protocol_params = match extension_params {
+            Some(params) => params,
+            None => match dex_type {
+                DexType::PumpFun => Box::new(PumpFunParams::default()) as Box<dyn ProtocolParams>,
+                DexType::Bonk => Box::new(BonkCreateParams::default()) as Box<dyn ProtocolParams>,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Token creation is not supported for {:?}",
+                        dex_type
+                    ))
+                }
+            },
+        };
+        let executor = TradeFactory::create_executor(dex_type);
+        let mint = match mint {
+            Some(mint) => mint,
+            None => self.next_mint()?,
+        };
+        let mint_pubkey = mint.pubkey();
+        let mut create_params = CreateParams {
+            swqos_clients: self.swqos_clients.clone(),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint,
+            metadata,
+            creator: self.payer.pubkey(),
+            dev_buy_sol_amount,
+            slippage_basis_points,
+            priority_fee: self.trade_config.priority_fee.clone(),
+            lookup_table_key: self.trade_config.lookup_table_key,
+            recent_blockhash,
+            data_size_limit: 0,
+            protocol_params,
+            context: self.context.clone(),
+        };
+        if let Some(custom_buy_tip_fee) = custom_buy_tip_fee {
+            create_params.priority_fee.override_buy_tip_fee(custom_buy_tip_fee);
+        }
+        let result = executor.create(create_params).await;
+        self.wallet_pool.invalidate(&self.payer.pubkey());
+        result.map(|_| mint_pubkey)
+    }
+
+    /// Take the mint for a new token from the vanity pool, or generate a random one
+    ///
+    /// A keypair taken from the pool is removed from it even if the create later fails.
+    fn next_mint(&self) -> Result<Arc<dyn AsyncTradeSigner>, anyhow::Error> {
+        match &self.vanity_pool {
+            Some(vanity_pool) => vanity_pool
+                .take()?
+                .map(|keypair| Arc::new(keypair) as Arc<dyn AsyncTradeSigner>)
+                .ok_or_else(|| anyhow::anyhow!("Vanity keypair pool is empty")),
+            None => Ok(Arc::new(Keypair::new())),
+        }
+    }
+
+    /// Pre-compile a buy transaction for every configured provider
+    ///
+    /// Call this ahead of the trigger, for example when a `PumpFunCreateTokenEvent` arrives.
+    /// PDAs, ATAs, compute budget instructions, the lookup table and account ordering are
+    /// resolved here; `TradeTemplate::execute` then only patches amounts, tip and
+    /// blockhash/nonce before signing. Only PumpFun and Bonk are supported.
+    ///
+    /// `sol_amount` is a placeholder used to build the instructions, the actual amounts are
+    /// passed to `TradeTemplate::execute`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let params = Box::new(PumpFunParams { bonding_curve: Some(bonding_curve.clone()) });
+    /// let template = solana_trade
+    ///     .create_buy_template(DexType::PumpFun, mint, Some(creator), sol_amount, None, blockhash, Some(params))
+    ///     .await?;
+    /// // later, on trigger
+    /// let amounts = TemplateAmounts::pumpfun(&bonding_curve, sol_amount, Some(100));
+    /// template.execute(&amounts, blockhash, None).await?;
+    /// ```
+    pub async fn create_buy_template(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        sol_amount: Lamports,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<TradeTemplate, anyhow::Error> {
+        let protocol_params = match (extension_params, &dex_type) {
+            (Some(params), _) => params,
+            (None, DexType::PumpFun) => {
+                Box::new(PumpFunParams::default()) as Box<dyn ProtocolParams>
+            }
+            (None, DexType::Bonk) => Box::new(BonkParams::default()) as Box<dyn ProtocolParams>,
+            (None, _) => {
+                return Err(anyhow::anyhow!("Trade template is not supported for {:?}", dex_type))
+            }
+        };
+        let buy_params = BuyParams {
+            rpc: Some(self.rpc.clone()),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint,
+            creator: creator.unwrap_or(Pubkey::default()),
+            sol_amount,
+            swap_mode: SwapMode::ExactIn,
+            slippage_basis_points,
+            priority_fee: self.trade_config.priority_fee.clone(),
+            lookup_table_key: self.trade_config.lookup_table_key,
+            recent_blockhash,
+            data_size_limit: 0,
+            protocol_params,
+            context: self.context.clone(),
+        };
+        TradeTemplate::build(dex_type, buy_params, self.swqos_clients.clone()).await
+    }
+
     /// Execute a sell order for a specified token
     ///
     /// # Arguments
@@ -249,7 +790,7 @@ impl SolanaTrade {
     /// * `token_amount` - Amount of tokens to sell (in smallest token units)
     /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
     /// * `recent_blockhash` - Recent blockhash for transaction validity
-    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `custom_sell_tip_fee` - Optional tip fee applied to every provider for this sell
     /// * `with_tip` - Optional boolean to indicate if the transaction should be sent with tip
     /// * `extension_params` - Optional protocol-specific parameters (uses defaults if None)
     ///
@@ -298,7 +839,77 @@ impl SolanaTrade {
         token_amount: u64,
         slippage_basis_points: Option<u64>,
         recent_blockhash: Hash,
-        custom_buy_tip_fee: Option<f64>,
+        custom_sell_tip_fee: Option<Lamports>,
+        with_tip: bool,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<(), anyhow::Error> {
+        self.execute_sell(
+            dex_type,
+            mint,
+            creator,
+            token_amount,
+            SwapMode::ExactIn,
+            slippage_basis_points,
+            recent_blockhash,
+            custom_sell_tip_fee,
+            with_tip,
+            extension_params,
+        )
+        .await
+    }
+
+    /// Sell until exactly `sol_amount` is received, selling at most `max_token_amount`
+    ///
+    /// Supported on Bonk, Raydium CPMM, Raydium AMM v4 and Raydium CLMM. The transaction fails
+    /// on-chain if more than `max_token_amount` tokens would be needed, and the order is rejected
+    /// up front when the current quote already exceeds it.
+    ///
+    /// # Arguments
+    ///
+    /// * `sol_amount` - Amount of SOL to receive, after protocol fees
+    /// * `max_token_amount` - Maximum amount of tokens to sell (in smallest token units)
+    ///
+    /// The remaining arguments are the same as for `sell`.
+    pub async fn sell_exact_out(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        sol_amount: Lamports,
+        max_token_amount: u64,
+        recent_blockhash: Hash,
+        custom_sell_tip_fee: Option<Lamports>,
+        with_tip: bool,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<(), anyhow::Error> {
+        if sol_amount.is_zero() {
+            return Err(anyhow::anyhow!("Amount cannot be zero"));
+        }
+        self.execute_sell(
+            dex_type,
+            mint,
+            creator,
+            max_token_amount,
+            SwapMode::ExactOut(sol_amount.as_u64()),
+            None,
+            recent_blockhash,
+            custom_sell_tip_fee,
+            with_tip,
+            extension_params,
+        )
+        .await
+    }
+
+    async fn execute_sell(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        token_amount: u64,
+        swap_mode: SwapMode,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        custom_sell_tip_fee: Option<Lamports>,
         with_tip: bool,
         extension_params: Option<Box<dyn ProtocolParams>>,
     ) -> Result<(), anyhow::Error> {
@@ -313,28 +924,31 @@ impl SolanaTrade {
                 DexType::RaydiumCpmm => {
                     Box::new(RaydiumCpmmParams::default()) as Box<dyn ProtocolParams>
                 }
+                DexType::RaydiumAmmV4 => {
+                    Box::new(RaydiumAmmV4Params::default()) as Box<dyn ProtocolParams>
+                }
+                DexType::RaydiumClmm => {
+                    Box::new(RaydiumClmmParams::default()) as Box<dyn ProtocolParams>
+                }
             }
         };
-        let sell_params = SellParams {
+        let mut sell_params = SellParams {
             rpc: Some(self.rpc.clone()),
             payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
             mint: mint,
             creator: creator.unwrap_or(Pubkey::default()),
             token_amount: Some(token_amount),
+            swap_mode,
             slippage_basis_points: slippage_basis_points,
             priority_fee: self.trade_config.priority_fee.clone(),
             lookup_table_key: self.trade_config.lookup_table_key,
             recent_blockhash,
             protocol_params: protocol_params.clone(),
+            context: self.context.clone(),
         };
-        let mut priority_fee = sell_params.priority_fee.clone();
-        if custom_buy_tip_fee.is_some() {
-            priority_fee.buy_tip_fee = custom_buy_tip_fee.unwrap();
-            priority_fee.buy_tip_fees = priority_fee
-                .buy_tip_fees
-                .iter()
-                .map(|_| custom_buy_tip_fee.unwrap())
-                .collect();
+        if let Some(custom_sell_tip_fee) = custom_sell_tip_fee {
+            sell_params.priority_fee.override_sell_tip_fee(custom_sell_tip_fee);
         }
         let sell_with_tip_params = sell_params.clone().with_tip(self.swqos_clients.clone());
 
@@ -356,6 +970,14 @@ impl SolanaTrade {
                 .as_any()
                 .downcast_ref::<RaydiumCpmmParams>()
                 .is_some(),
+            DexType::RaydiumAmmV4 => protocol_params
+                .as_any()
+                .downcast_ref::<RaydiumAmmV4Params>()
+                .is_some(),
+            DexType::RaydiumClmm => protocol_params
+                .as_any()
+                .downcast_ref::<RaydiumClmmParams>()
+                .is_some(),
         };
 
         if !is_valid_params {
@@ -384,7 +1006,7 @@ impl SolanaTrade {
     /// * `percent` - Percentage of tokens to sell (1-100, where 100 = 100%)
     /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
     /// * `recent_blockhash` - Recent blockhash for transaction validity
-    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `custom_sell_tip_fee` - Optional tip fee applied to every provider for this sell
     /// * `extension_params` - Optional protocol-specific parameters (uses defaults if None)
     ///
     /// # Returns
@@ -436,7 +1058,7 @@ impl SolanaTrade {
         percent: u64,
         slippage_basis_points: Option<u64>,
         recent_blockhash: Hash,
-        custom_buy_tip_fee: Option<f64>,
+        custom_sell_tip_fee: Option<Lamports>,
         with_tip: bool,
         extension_params: Option<Box<dyn ProtocolParams>>,
     ) -> Result<(), anyhow::Error> {
@@ -451,10 +1073,343 @@ impl SolanaTrade {
             amount,
             slippage_basis_points,
             recent_blockhash,
-            custom_buy_tip_fee,
+            custom_sell_tip_fee,
             with_tip,
             extension_params,
         )
         .await
     }
+
+    /// Create a PumpSwap pool funded with `base_amount_in` and `quote_amount_in`
+    ///
+    /// The payer becomes the pool creator and coin creator and receives the initial LP tokens.
+    /// `index` distinguishes several pools of the same creator for the same pair, use 0 for
+    /// the first one. Returns the address of the new pool.
+    pub async fn pumpswap_create_pool(
+        &self,
+        base_mint: Pubkey,
+        quote_mint: Pubkey,
+        base_amount_in: u64,
+        quote_amount_in: u64,
+        index: u16,
+        recent_blockhash: Hash,
+    ) -> Result<Pubkey, anyhow::Error> {
+        let creator = self.payer.pubkey();
+        let args = instruction::pumpswap::CreatePool {
+            index,
+            base_amount_in,
+            quote_amount_in,
+            coin_creator: creator,
+        };
+        let (instructions, pool) =
+            instruction::pumpswap::create_pool(&creator, &base_mint, &quote_mint, args, true)?;
+        let result =
+            self.execute_instructions(instructions, TradeType::CreatePool, recent_blockhash).await;
+        self.wallet_pool.invalidate(&creator);
+        result.map(|_| pool)
+    }
+
+    /// Add liquidity to a PumpSwap pool, minting exactly `lp_token_amount` LP tokens
+    ///
+    /// The base and quote amounts are derived from the current reserves and capped by
+    /// `slippage_basis_points`. The wSOL side is wrapped and unwrapped automatically.
+    pub async fn pumpswap_deposit(
+        &self,
+        pool_address: Pubkey,
+        lp_token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+    ) -> Result<(), anyhow::Error> {
+        let pool = PumpSwapPool::fetch(&self.rpc, &pool_address).await?;
+        let (base_reserves, quote_reserves) = pool.get_token_balances(&self.rpc).await?;
+        let (base_amount_in, quote_amount_in) =
+            get_deposit_amounts(lp_token_amount, base_reserves, quote_reserves, pool.lp_supply)?;
+        let slippage = slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+        let instructions = instruction::pumpswap::deposit(
+            &self.payer.pubkey(),
+            &pool_address,
+            &pool,
+            lp_token_amount,
+            calculate_with_slippage_buy(base_amount_in, slippage),
+            calculate_with_slippage_buy(quote_amount_in, slippage),
+            true,
+        );
+        let result =
+            self.execute_instructions(instructions, TradeType::Deposit, recent_blockhash).await;
+        self.wallet_pool.invalidate(&self.payer.pubkey());
+        result
+    }
+
+    /// Remove liquidity from a PumpSwap pool by burning `lp_token_amount` LP tokens
+    ///
+    /// The minimum base and quote amounts are derived from the current reserves and
+    /// `slippage_basis_points`. Received wSOL is unwrapped automatically.
+    pub async fn pumpswap_withdraw(
+        &self,
+        pool_address: Pubkey,
+        lp_token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+    ) -> Result<(), anyhow::Error> {
+        let pool = PumpSwapPool::fetch(&self.rpc, &pool_address).await?;
+        let (base_reserves, quote_reserves) = pool.get_token_balances(&self.rpc).await?;
+        let (base_amount_out, quote_amount_out) =
+            get_withdraw_amounts(lp_token_amount, base_reserves, quote_reserves, pool.lp_supply)?;
+        let slippage = slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+        let instructions = instruction::pumpswap::withdraw(
+            &self.payer.pubkey(),
+            &pool_address,
+            &pool,
+            lp_token_amount,
+            calculate_with_slippage_sell(base_amount_out, slippage),
+            calculate_with_slippage_sell(quote_amount_out, slippage),
+            true,
+        );
+        let result =
+            self.execute_instructions(instructions, TradeType::Withdraw, recent_blockhash).await;
+        self.wallet_pool.invalidate(&self.payer.pubkey());
+        result
+    }
+
+    /// Create a Raydium CPMM pool under `amm_config`, funded with both token amounts
+    ///
+    /// `amm_config` selects the fee tier, see `raydium_cpmm::common::get_amm_config_pda`.
+    /// Token programs are read from the mint accounts, so Token-2022 mints are supported.
+    /// The pool opens for trading at `open_time` (unix seconds), 0 opens it immediately.
+    /// Returns the address of the new pool.
+    pub async fn raydium_cpmm_create_pool(
+        &self,
+        amm_config: Pubkey,
+        mint_a: Pubkey,
+        amount_a: u64,
+        mint_b: Pubkey,
+        amount_b: u64,
+        open_time: u64,
+        recent_blockhash: Hash,
+    ) -> Result<Pubkey, anyhow::Error> {
+        let creator = self.payer.pubkey();
+        let token_a = instruction::raydium_cpmm::PoolTokenInput {
+            mint: mint_a,
+            token_program: self.rpc.get_account(&mint_a).await?.owner,
+            amount: amount_a,
+        };
+        let token_b = instruction::raydium_cpmm::PoolTokenInput {
+            mint: mint_b,
+            token_program: self.rpc.get_account(&mint_b).await?.owner,
+            amount: amount_b,
+        };
+        let (instructions, pool_state) = instruction::raydium_cpmm::initialize(
+            &creator,
+            &amm_config,
+            token_a,
+            token_b,
+            open_time,
+            true,
+        )?;
+        let result =
+            self.execute_instructions(instructions, TradeType::CreatePool, recent_blockhash).await;
+        self.wallet_pool.invalidate(&creator);
+        result.map(|_| pool_state)
+    }
+
+    /// Add liquidity to a Raydium CPMM pool, minting exactly `lp_token_amount` LP tokens
+    ///
+    /// The token amounts are derived from the vault balances minus accrued fees and capped by
+    /// `slippage_basis_points`. The wSOL side is wrapped and unwrapped automatically.
+    pub async fn raydium_cpmm_deposit(
+        &self,
+        pool_state: Pubkey,
+        lp_token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+    ) -> Result<(), anyhow::Error> {
+        let (pool, token0_reserves, token1_reserves) =
+            self.fetch_raydium_cpmm_reserves(&pool_state).await?;
+        let (token0_amount, token1_amount) = trading::raydium_cpmm::common::get_deposit_amounts(
+            lp_token_amount,
+            pool.lp_supply,
+            token0_reserves,
+            token1_reserves,
+        )?;
+        let slippage = slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+        let instructions = instruction::raydium_cpmm::deposit(
+            &self.payer.pubkey(),
+            &pool_state,
+            &pool,
+            lp_token_amount,
+            calculate_with_slippage_buy(token0_amount, slippage),
+            calculate_with_slippage_buy(token1_amount, slippage),
+            true,
+        );
+        let result =
+            self.execute_instructions(instructions, TradeType::Deposit, recent_blockhash).await;
+        self.wallet_pool.invalidate(&self.payer.pubkey());
+        result
+    }
+
+    /// Remove liquidity from a Raydium CPMM pool by burning `lp_token_amount` LP tokens
+    ///
+    /// The minimum token amounts are derived from the vault balances minus accrued fees and
+    /// `slippage_basis_points`. Received wSOL is unwrapped automatically.
+    pub async fn raydium_cpmm_withdraw(
+        &self,
+        pool_state: Pubkey,
+        lp_token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+    ) -> Result<(), anyhow::Error> {
+        let (pool, token0_reserves, token1_reserves) =
+            self.fetch_raydium_cpmm_reserves(&pool_state).await?;
+        let (token0_amount, token1_amount) = trading::raydium_cpmm::common::get_withdraw_amounts(
+            lp_token_amount,
+            pool.lp_supply,
+            token0_reserves,
+            token1_reserves,
+        )?;
+        let slippage = slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+        let instructions = instruction::raydium_cpmm::withdraw(
+            &self.payer.pubkey(),
+            &pool_state,
+            &pool,
+            lp_token_amount,
+            calculate_with_slippage_sell(token0_amount, slippage),
+            calculate_with_slippage_sell(token1_amount, slippage),
+            true,
+        );
+        let result =
+            self.execute_instructions(instructions, TradeType::Withdraw, recent_blockhash).await;
+        self.wallet_pool.invalidate(&self.payer.pubkey());
+        result
+    }
+
+    /// Fetch a Raydium CPMM pool together with its reserves excluding accrued fees
+    async fn fetch_raydium_cpmm_reserves(
+        &self,
+        pool_state: &Pubkey,
+    ) -> Result<(RaydiumCpmmPool, u64, u64), anyhow::Error> {
+        let pool = RaydiumCpmmPool::fetch(&self.rpc, pool_state).await?;
+        let (token0_vault_amount, token1_vault_amount) =
+            trading::raydium_cpmm::common::get_pool_token_balances(
+                &self.rpc,
+                pool_state,
+                &pool.token0_mint,
+                &pool.token1_mint,
+            )
+            .await?;
+        let (token0_reserves, token1_reserves) =
+            pool.vault_amounts_without_fees(token0_vault_amount, token1_vault_amount)?;
+        Ok((pool, token0_reserves, token1_reserves))
+    }
+
+    /// Send prebuilt instructions through every configured provider, like a buy
+    async fn execute_instructions(
+        &self,
+        instructions: Vec<solana_sdk::instruction::Instruction>,
+        trade_type: TradeType,
+        recent_blockhash: Hash,
+    ) -> Result<(), anyhow::Error> {
+        parallel_execute_with_tips(
+            self.context.clone(),
+            self.swqos_clients.clone(),
+            self.payer.clone(),
+            self.fee_payer.clone(),
+            instructions,
+            self.trade_config.priority_fee.clone(),
+            self.trade_config.lookup_table_key,
+            recent_blockhash,
+            MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            trade_type,
+            vec![],
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swqos::{SwqosConfig, SwqosRegion};
+    use solana_sdk::commitment_config::CommitmentConfig;
+
+    fn trade_with(swqos_configs: Vec<SwqosConfig>) -> SolanaTrade {
+        let rpc_url = "http://127.0.0.1:8899".to_string();
+        let swqos_clients = swqos_configs
+            .iter()
+            .map(|config| {
+                SwqosConfig::get_swqos_client(
+                    rpc_url.clone(),
+                    CommitmentConfig::confirmed(),
+                    config.clone(),
+                )
+                .unwrap()
+            })
+            .collect();
+        let trade_config = TradeConfig::new(
+            rpc_url.clone(),
+            swqos_configs,
+            PriorityFee::default(),
+            CommitmentConfig::confirmed(),
+            None,
+        )
+        .with_swqos_group("snipe", vec![SwqosType::Jito, SwqosType::ZeroSlot])
+        .with_swqos_group("tpu", vec![SwqosType::Tpu]);
+        SolanaTrade {
+            payer: Arc::new(Keypair::new()),
+            fee_payer: None,
+            rpc: Arc::new(SolanaRpcClient::new(rpc_url)),
+            swqos_clients,
+            priority_fee: PriorityFee::default(),
+            trade_config,
+            context: TradeContext::new(),
+            wallet_pool: Arc::new(WalletPool::default()),
+            vanity_pool: None,
+        }
+    }
+
+    #[test]
+    fn test_with_swqos_filters_clients() {
+        let trade = trade_with(vec![
+            SwqosConfig::Jito("".to_string(), SwqosRegion::Frankfurt),
+            SwqosConfig::ZeroSlot("".to_string(), SwqosRegion::Frankfurt),
+            SwqosConfig::Temporal("".to_string(), SwqosRegion::Frankfurt),
+        ]);
+
+        let filtered = trade.with_swqos(&[SwqosType::Jito, SwqosType::Temporal]).unwrap();
+        let types: Vec<SwqosType> =
+            filtered.swqos_clients.iter().map(|client| client.get_swqos_type()).collect();
+        assert_eq!(types, vec![SwqosType::Jito, SwqosType::Temporal]);
+        assert_eq!(trade.swqos_clients.len(), 3);
+
+        assert!(trade.with_swqos(&[SwqosType::Helius]).is_err());
+        assert!(trade.with_swqos(&[]).is_err());
+
+        assert_eq!(trade.with_swqos_group("snipe").unwrap().swqos_clients.len(), 2);
+        assert!(trade.with_swqos_group("tpu").is_err());
+        assert!(trade.with_swqos_group("missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_buy_split_rejects_durable_nonce() {
+        let mut trade = trade_with(vec![]);
+        let wallets: Vec<Arc<dyn AsyncTradeSigner>> =
+            vec![Arc::new(Keypair::new()), Arc::new(Keypair::new())];
+        trade.wallet_pool = Arc::new(WalletPool::new(wallets));
+        trade.context.nonce_cache.init(Some(Pubkey::new_unique().to_string()));
+
+        let err = trade
+            .buy_split(
+                DexType::PumpFun,
+                Pubkey::new_unique(),
+                None,
+                Lamports::from_sol(0.1).unwrap(),
+                2,
+                None,
+                Hash::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("durable nonce"), "{}", err);
+    }
 }
@@ -1,8 +1,8 @@
 use std::{str::FromStr, sync::Arc};
 
 use sol_trade_sdk::{
-    common::{bonding_curve::BondingCurveAccount, AnyResult, PriorityFee, TradeConfig},
-    swqos::{SwqosConfig, SwqosRegion},
+    common::{bonding_curve::BondingCurveAccount, AnyResult, Lamports, PriorityFee, TradeConfig},
+    swqos::{SwqosConfig, SwqosRegion, SwqosType},
     trading::{core::params::{BonkParams, PumpFunParams, PumpSwapParams, RaydiumCpmmParams}, factory::DexType, raydium_cpmm::common::{get_buy_token_amount, get_sell_sol_amount}},
     SolanaTrade,
 };
@@ -48,7 +48,7 @@ async fn test_create_solana_trade_client() -> AnyResult<SolanaTrade> {
     let swqos_configs = create_swqos_configs(&rpc_url);
     let trade_config = create_trade_config(rpc_url, swqos_configs);
 
-    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await?;
     println!("SolanaTrade client created successfully!");
 
     Ok(solana_trade_client)
@@ -66,13 +66,16 @@ fn create_swqos_configs(rpc_url: &str) -> Vec<SwqosConfig> {
 }
 
 fn create_trade_config(rpc_url: String, swqos_configs: Vec<SwqosConfig>) -> TradeConfig {
-    TradeConfig {
+    TradeConfig::new(
         rpc_url,
-        commitment: CommitmentConfig::confirmed(),
-        priority_fee: PriorityFee::default(),
         swqos_configs,
-        lookup_table_key: None,
-    }
+        PriorityFee::default(),
+        CommitmentConfig::confirmed(),
+        None,
+    )
+    // 小额卖出只走 RPC，狙击只走 Jito + ZeroSlot
+    .with_swqos_group("rpc_only", vec![SwqosType::Default])
+    .with_swqos_group("snipe", vec![SwqosType::Jito, SwqosType::ZeroSlot])
 }
 
 async fn test_pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> AnyResult<()> {
@@ -81,7 +84,7 @@ async fn test_pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> Any
     let client = test_create_solana_trade_client().await?;
     let creator = Pubkey::from_str("xxxxxx")?;
     let mint_pubkey = Pubkey::from_str("xxxxxx")?;
-    let buy_sol_cost = 100_000;
+    let buy_sol_cost = Lamports::new(100_000);
     let slippage_basis_points = Some(100);
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
     let bonding_curve = BondingCurveAccount::from_trade(&trade_info);
@@ -143,7 +146,7 @@ async fn test_pumpfun_sniper_trade_with_shreds(trade_info: PumpFunTradeEvent) ->
 
     // Buy tokens
     println!("Buying tokens from PumpFun...");
-    let buy_sol_amount = 100_000;
+    let buy_sol_amount = Lamports::new(100_000);
     client
         .buy(
             DexType::PumpFun,
@@ -183,7 +186,7 @@ async fn test_pumpswap() -> AnyResult<()> {
     let client = test_create_solana_trade_client().await?;
     let creator = Pubkey::from_str("11111111111111111111111111111111")?;
     let mint_pubkey = Pubkey::from_str("2zMMhcVQEXDtdE6vsFS7S7D5oUodfJHE8vd1gnBouauv")?;
-    let buy_sol_cost = 100_000;
+    let buy_sol_cost = Lamports::new(100_000);
     let slippage_basis_points = Some(100);
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
     let pool_address = Pubkey::from_str("xxxxxxx")?;
@@ -242,7 +245,7 @@ async fn test_bonk_copy_trade_with_grpc(trade_info: BonkTradeEvent) -> AnyResult
 
     let client = test_create_solana_trade_client().await?;
     let mint_pubkey = Pubkey::from_str("xxxxxxx")?;
-    let buy_sol_cost = 100_000;
+    let buy_sol_cost = Lamports::new(100_000);
     let slippage_basis_points = Some(100);
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
@@ -288,7 +291,7 @@ async fn test_bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyRe
 
     let client = test_create_solana_trade_client().await?;
     let mint_pubkey = Pubkey::from_str("xxxxxxx")?;
-    let buy_sol_cost = 100_000;
+    let buy_sol_cost = Lamports::new(100_000);
     let slippage_basis_points = Some(100);
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
@@ -330,7 +333,7 @@ async fn test_bonk() -> Result<(), Box<dyn std::error::Error>> {
 
     let client = test_create_solana_trade_client().await?;
     let mint_pubkey = Pubkey::from_str("xxxxxxx")?;
-    let buy_sol_cost = 100_000;
+    let buy_sol_cost = Lamports::new(100_000);
     let slippage_basis_points = Some(100);
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
@@ -372,11 +375,11 @@ async fn test_raydium_cpmm() -> Result<(), Box<dyn std::error::Error>> {
 
     let client = test_create_solana_trade_client().await?;
     let mint_pubkey = Pubkey::from_str("xxxxxxxx")?;
-    let buy_sol_cost = 100_000;
+    let buy_sol_cost = Lamports::new(100_000);
     let slippage_basis_points = Some(100);
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
     let pool_state = Pubkey::from_str("xxxxxxx")?;
-    let buy_amount_out = get_buy_token_amount(&client.rpc, &pool_state, buy_sol_cost).await?;
+    let buy_amount_out = get_buy_token_amount(&client.rpc, &pool_state, buy_sol_cost.as_u64()).await?;
     // Buy tokens
     println!("Buying tokens from Raydium Cpmm...");
     client
@@ -389,11 +392,12 @@ async fn test_raydium_cpmm() -> Result<(), Box<dyn std::error::Error>> {
             recent_blockhash,
             None,
             Some(Box::new(RaydiumCpmmParams {
-                pool_state: Some(pool_state),             // 如果不传，会自动计算
+                pool_state: Some(pool_state),             // 如果不传，使用该 mint 储备最深的 WSOL 池子
                 mint_token_program: Some(spl_token::ID),  // spl_token_2022::ID
                 mint_token_in_pool_state_index: Some(1), // mint_token 在 pool_state 中的索引,默认在索引1
                 minimum_amount_out: Some(buy_amount_out), // 如果不传、默认为0
                 auto_handle_wsol: true,
+                amm_config: None, // 如果不传，从链上池子读取
             })),
         )
         .await?;
@@ -412,11 +416,12 @@ async fn test_raydium_cpmm() -> Result<(), Box<dyn std::error::Error>> {
         None,
         false,
         Some(Box::new(RaydiumCpmmParams {
-            pool_state: Some(pool_state), // 如果不传，会自动计算
+            pool_state: Some(pool_state), // 如果不传，使用该 mint 储备最深的 WSOL 池子
             mint_token_program: Some(spl_token::ID), // spl_token_2022::ID
             mint_token_in_pool_state_index: Some(1), // mint_token 在 pool_state 中的索引,默认在索引1
             minimum_amount_out: Some(sell_sol_amount), // 如果不传、默认为0
             auto_handle_wsol: true,
+            amm_config: None, // 如果不传，从链上池子读取
         })),
     ).await?;
 
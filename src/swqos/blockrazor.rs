@@ -0,0 +1,184 @@
+use crate::swqos::common::{
+    build_http_client, endpoint_with_path, poll_transaction_confirmation,
+    serialize_transaction_and_encode,
+};
+use rand::seq::IndexedRandom;
+use reqwest::Client;
+use serde_json::json;
+use std::{sync::Arc, time::Instant};
+
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::swqos::SwqosClientTrait;
+use crate::swqos::{SwqosEndpoint, SwqosType, TradeType};
+use anyhow::Result;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::{
+    common::{Lamports, SolanaRpcClient},
+    constants::swqos::{BLOCKRAZOR_MIN_TIP, BLOCKRAZOR_TIP_ACCOUNTS},
+};
+
+/// BlockRazor 加速发送客户端
+///
+/// 请求体只包含 base64 交易和 `fast` 模式，`apikey` 请求头携带授权。交易需要向
+/// BlockRazor 小费账户支付不低于 `BLOCKRAZOR_MIN_TIP` 的小费。
+#[derive(Clone)]
+pub struct BlockRazorClient {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub rpc_client: Arc<SolanaRpcClient>,
+    pub http_client: Client,
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for BlockRazorClient {
+    async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        self.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        self.send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        let tip_account = *BLOCKRAZOR_TIP_ACCOUNTS
+            .choose(&mut rand::rng())
+            .or_else(|| BLOCKRAZOR_TIP_ACCOUNTS.first())
+            .unwrap();
+        Ok(tip_account.to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::BlockRazor
+    }
+
+    async fn warm_up(&self) -> Result<()> {
+        self.ping().await
+    }
+
+    fn get_min_tip(&self) -> Lamports {
+        BLOCKRAZOR_MIN_TIP
+    }
+}
+
+impl BlockRazorClient {
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Result<Self> {
+        Self::new_with_endpoint(rpc_url, SwqosEndpoint::new(endpoint), auth_token)
+    }
+
+    /// 使用自定义端点配置（请求头、超时）创建客户端
+    pub fn new_with_endpoint(
+        rpc_url: String,
+        endpoint: SwqosEndpoint,
+        auth_token: String,
+    ) -> Result<Self> {
+        let rpc_client = SolanaRpcClient::new(rpc_url);
+        let http_client = build_http_client(&endpoint)?;
+        Ok(Self {
+            rpc_client: Arc::new(rpc_client),
+            endpoint: endpoint.url,
+            auth_token,
+            http_client,
+        })
+    }
+
+    /// 请求 `/health` 保持连接活跃，避免首笔交易承担建连耗时
+    pub async fn ping(&self) -> Result<()> {
+        let url = endpoint_with_path(&self.endpoint, "/health")?;
+        self.http_client.get(url).send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        let start_time = Instant::now();
+        let (content, signature) =
+            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        println!(" 交易编码base64: {:?}", start_time.elapsed());
+
+        let request_body = serde_json::to_string(&json!({
+            "transaction": content,
+            "mode": "fast"
+        }))?;
+
+        let response_text = self
+            .http_client
+            .post(&self.endpoint)
+            .body(request_body)
+            .header("apikey", &self.auth_token)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if response_json.get("signature").is_some() {
+                println!(" blockrazor{}提交: {:?}", trade_type, start_time.elapsed());
+            } else if let Some(_error) = response_json.get("error") {
+                eprintln!(" blockrazor{}提交失败: {:?}", trade_type, _error);
+            }
+        }
+
+        let start_time: Instant = Instant::now();
+        match poll_transaction_confirmation(&self.rpc_client, signature).await {
+            Ok(_) => (),
+            Err(_) => (),
+        }
+
+        println!(" blockrazor{}确认: {:?}", trade_type, start_time.elapsed());
+
+        Ok(())
+    }
+
+    pub async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        for transaction in transactions {
+            self.send_transaction(trade_type, transaction).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swqos::{SwqosConfig, SwqosRegion};
+    use solana_sdk::commitment_config::CommitmentConfig;
+
+    #[test]
+    fn test_resolve_endpoint_and_tip_account() {
+        let config = SwqosConfig::BlockRazor("token".to_string(), SwqosRegion::Frankfurt);
+        assert_eq!(config.swqos_type(), SwqosType::BlockRazor);
+        assert_eq!(
+            config.resolve_endpoint().url,
+            "http://frankfurt.solana.blockrazor.xyz:443/sendTransaction"
+        );
+
+        let client = SwqosConfig::get_swqos_client(
+            "http://127.0.0.1:8899".to_string(),
+            CommitmentConfig::confirmed(),
+            config,
+        )
+        .unwrap();
+        assert_eq!(client.get_swqos_type(), SwqosType::BlockRazor);
+        assert_eq!(client.get_min_tip(), BLOCKRAZOR_MIN_TIP);
+        let tip_account = client.get_tip_account().unwrap();
+        assert!(BLOCKRAZOR_TIP_ACCOUNTS.iter().any(|account| account.to_string() == tip_account));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::common::types::SolanaRpcClient;
+use crate::swqos::SwqosEndpoint;
 use anyhow::Result;
 use base64::engine::general_purpose::{self, STANDARD};
 use base64::Engine;
 use bincode::serialize;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client;
 use serde_json::json;
 use solana_client::rpc_client::SerializableTransaction;
@@ -25,6 +27,42 @@ impl FormatBase64VersionedTransaction for VersionedTransaction {
     }
 }
 
+/// 按端点配置构建 SWQOS 使用的 HTTP 客户端
+pub fn build_http_client(endpoint: &SwqosEndpoint) -> Result<Client> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &endpoint.headers {
+        headers.insert(HeaderName::from_bytes(name.as_bytes())?, HeaderValue::from_str(value)?);
+    }
+    let http_client = Client::builder()
+        .default_headers(headers)
+        .pool_idle_timeout(Duration::from_secs(60))
+        .pool_max_idle_per_host(64)
+        .tcp_keepalive(Some(Duration::from_secs(1200)))
+        .http2_keep_alive_interval(Duration::from_secs(15))
+        .timeout(endpoint.timeout())
+        .connect_timeout(endpoint.connect_timeout())
+        .build()?;
+    Ok(http_client)
+}
+
+/// 将端点地址的路径替换为给定路径，用于推导保活/健康检查地址
+pub fn endpoint_with_path(endpoint: &str, path: &str) -> Result<String> {
+    let mut url = reqwest::Url::parse(endpoint)?;
+    url.set_path(path);
+    url.set_query(None);
+    Ok(url.to_string())
+}
+
+/// 向端点发送一次 GET 请求以建立或保持连接
+///
+/// 收到任何 HTTP 响应都说明 TLS/HTTP2 连接已经可用，因此不检查状态码，
+/// 服务商没有专门的保活地址时也可以直接请求根路径。
+pub async fn ping_endpoint(http_client: &Client, endpoint: &str, path: &str) -> Result<()> {
+    let url = endpoint_with_path(endpoint, path)?;
+    http_client.get(url).send().await?;
+    Ok(())
+}
+
 pub async fn poll_transaction_confirmation(
     rpc: &SolanaRpcClient,
     txt_sig: Signature,
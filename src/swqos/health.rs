@@ -0,0 +1,106 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::swqos::SwqosType;
+
+/// 服务商连接健康状态快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwqosHealthStats {
+    pub swqos_type: SwqosType,
+    /// 最近一次预热/保活是否成功，成功表示发送路径的连接已建立
+    pub connected: bool,
+    /// 最近一次预热/保活的耗时
+    pub last_ping_latency: Option<Duration>,
+    /// 距离最近一次预热/保活的时间，从未执行过时为空
+    pub since_last_ping: Option<Duration>,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+struct HealthState {
+    connected: bool,
+    last_ping_at: Option<Instant>,
+    last_ping_latency: Option<Duration>,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+}
+
+/// 记录服务商预热和保活结果
+pub struct SwqosHealth {
+    swqos_type: SwqosType,
+    state: Mutex<HealthState>,
+}
+
+impl SwqosHealth {
+    pub fn new(swqos_type: SwqosType) -> Self {
+        Self {
+            swqos_type,
+            state: Mutex::new(HealthState {
+                connected: false,
+                last_ping_at: None,
+                last_ping_latency: None,
+                consecutive_failures: 0,
+                last_error: None,
+            }),
+        }
+    }
+
+    pub fn record_success(&self, latency: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.connected = true;
+        state.last_ping_at = Some(Instant::now());
+        state.last_ping_latency = Some(latency);
+        state.consecutive_failures = 0;
+        state.last_error = None;
+    }
+
+    pub fn record_failure(&self, latency: Duration, error: String) {
+        let mut state = self.state.lock().unwrap();
+        state.connected = false;
+        state.last_ping_at = Some(Instant::now());
+        state.last_ping_latency = Some(latency);
+        state.consecutive_failures += 1;
+        state.last_error = Some(error);
+    }
+
+    pub fn stats(&self) -> SwqosHealthStats {
+        let state = self.state.lock().unwrap();
+        SwqosHealthStats {
+            swqos_type: self.swqos_type,
+            connected: state.connected,
+            last_ping_latency: state.last_ping_latency,
+            since_last_ping: state.last_ping_at.map(|at| at.elapsed()),
+            consecutive_failures: state.consecutive_failures,
+            last_error: state.last_error.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_records_failures_until_success() {
+        let health = SwqosHealth::new(SwqosType::Jito);
+        assert!(!health.stats().connected);
+        assert!(health.stats().since_last_ping.is_none());
+
+        health.record_failure(Duration::from_millis(5), "timeout".to_string());
+        health.record_failure(Duration::from_millis(5), "timeout".to_string());
+        let stats = health.stats();
+        assert!(!stats.connected);
+        assert_eq!(stats.consecutive_failures, 2);
+        assert_eq!(stats.last_error.as_deref(), Some("timeout"));
+
+        health.record_success(Duration::from_millis(3));
+        let stats = health.stats();
+        assert!(stats.connected);
+        assert_eq!(stats.consecutive_failures, 0);
+        assert_eq!(stats.last_ping_latency, Some(Duration::from_millis(3)));
+        assert!(stats.last_error.is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::swqos::common::{
+    build_http_client, endpoint_with_path, poll_transaction_confirmation,
+    serialize_transaction_and_encode,
+};
+use rand::seq::IndexedRandom;
+use reqwest::Client;
+use serde_json::json;
+use std::{sync::Arc, time::Instant};
+
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::swqos::SwqosClientTrait;
+use crate::swqos::{SwqosEndpoint, SwqosType, TradeType};
+use anyhow::Result;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::{
+    common::{Lamports, SolanaRpcClient},
+    constants::swqos::{HELIUS_MIN_TIP, HELIUS_TIP_ACCOUNTS},
+};
+
+/// Helius Sender 客户端
+///
+/// Sender 会同时通过 Jito 和质押连接转发交易，要求交易携带不低于
+/// `HELIUS_MIN_TIP` 的小费，并且必须跳过预检。
+#[derive(Clone)]
+pub struct HeliusClient {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub rpc_client: Arc<SolanaRpcClient>,
+    pub http_client: Client,
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for HeliusClient {
+    async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        self.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        self.send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        let tip_account = *HELIUS_TIP_ACCOUNTS
+            .choose(&mut rand::rng())
+            .or_else(|| HELIUS_TIP_ACCOUNTS.first())
+            .unwrap();
+        Ok(tip_account.to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::Helius
+    }
+
+    async fn warm_up(&self) -> Result<()> {
+        self.ping().await
+    }
+
+    fn get_min_tip(&self) -> Lamports {
+        HELIUS_MIN_TIP
+    }
+}
+
+impl HeliusClient {
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Result<Self> {
+        Self::new_with_endpoint(rpc_url, SwqosEndpoint::new(endpoint), auth_token)
+    }
+
+    /// 使用自定义端点配置（请求头、超时）创建客户端
+    pub fn new_with_endpoint(
+        rpc_url: String,
+        endpoint: SwqosEndpoint,
+        auth_token: String,
+    ) -> Result<Self> {
+        let rpc_client = SolanaRpcClient::new(rpc_url);
+        let http_client = build_http_client(&endpoint)?;
+        Ok(Self {
+            rpc_client: Arc::new(rpc_client),
+            endpoint: endpoint.url,
+            auth_token,
+            http_client,
+        })
+    }
+
+    /// 请求 `/ping` 保持连接活跃，避免首笔交易承担建连耗时
+    pub async fn ping(&self) -> Result<()> {
+        let url = endpoint_with_path(&self.endpoint, "/ping")?;
+        self.http_client.get(url).send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        let start_time = Instant::now();
+        let (content, signature) =
+            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        println!(" 交易编码base64: {:?}", start_time.elapsed());
+
+        let request_body = serde_json::to_string(&json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "sendTransaction",
+            "params": [
+                content,
+                {
+                    "encoding": "base64",
+                    "skipPreflight": true,
+                    "maxRetries": 0
+                }
+            ]
+        }))?;
+
+        let request = if self.auth_token.is_empty() {
+            self.http_client.post(&self.endpoint)
+        } else {
+            self.http_client.post(&self.endpoint).query(&[("api-key", &self.auth_token)])
+        };
+        let response_text = request
+            .body(request_body)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if response_json.get("result").is_some() {
+                println!(" helius{}提交: {:?}", trade_type, start_time.elapsed());
+            } else if let Some(_error) = response_json.get("error") {
+                eprintln!(" helius{}提交失败: {:?}", trade_type, _error);
+            }
+        }
+
+        let start_time: Instant = Instant::now();
+        match poll_transaction_confirmation(&self.rpc_client, signature).await {
+            Ok(_) => (),
+            Err(_) => (),
+        }
+
+        println!(" helius{}确认: {:?}", trade_type, start_time.elapsed());
+
+        Ok(())
+    }
+
+    pub async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        for transaction in transactions {
+            self.send_transaction(trade_type, transaction).await?;
+        }
+        Ok(())
+    }
+}
@@ -8,10 +8,10 @@ use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
 use solana_transaction_status::UiTransactionEncoding;
-use std::time::Duration;
 
+use crate::swqos::common::{build_http_client, ping_endpoint};
 use crate::swqos::SwqosClientTrait;
-use crate::swqos::{SwqosType, TradeType};
+use crate::swqos::{SwqosEndpoint, SwqosType, TradeType};
 use anyhow::Result;
 use solana_sdk::transaction::VersionedTransaction;
 
@@ -53,26 +53,36 @@ impl SwqosClientTrait for JitoClient {
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Jito
     }
+
+    async fn warm_up(&self) -> Result<()> {
+        self.ping().await
+    }
 }
 
 impl JitoClient {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Result<Self> {
+        Self::new_with_endpoint(rpc_url, SwqosEndpoint::new(endpoint), auth_token)
+    }
+
+    /// 使用自定义端点配置（请求头、超时）创建客户端
+    pub fn new_with_endpoint(
+        rpc_url: String,
+        endpoint: SwqosEndpoint,
+        auth_token: String,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = Client::builder()
-            .pool_idle_timeout(Duration::from_secs(60))
-            .pool_max_idle_per_host(64)
-            .tcp_keepalive(Some(Duration::from_secs(1200)))
-            .http2_keep_alive_interval(Duration::from_secs(15))
-            .timeout(Duration::from_secs(10))
-            .connect_timeout(Duration::from_secs(5))
-            .build()
-            .unwrap();
-        Self {
+        let http_client = build_http_client(&endpoint)?;
+        Ok(Self {
             rpc_client: Arc::new(rpc_client),
-            endpoint,
+            endpoint: endpoint.url,
             auth_token,
             http_client,
-        }
+        })
+    }
+
+    /// Jito 没有专门的保活地址，请求根路径建立连接
+    pub async fn ping(&self) -> Result<()> {
+        ping_endpoint(&self.http_client, &self.endpoint, "/").await
     }
 
     pub async fn send_transaction(
@@ -174,13 +184,17 @@ impl JitoClient {
             .text()
             .await?;
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
-                println!(" jito{}提交: {:?}", trade_type, start_time.elapsed());
-            } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" jito{}提交失败: {:?}", trade_type, _error);
-            }
+        // bundle 要么整体上链要么整体丢弃，提交失败必须返回给调用方
+        let response_json = serde_json::from_str::<serde_json::Value>(&response_text)
+            .map_err(|_| anyhow::anyhow!("jito bundle响应无法解析: {}", response_text))?;
+        if let Some(error) = response_json.get("error") {
+            eprintln!(" jito{}提交失败: {:?}", trade_type, error);
+            return Err(anyhow::anyhow!("jito bundle提交失败: {}", error));
+        }
+        if response_json.get("result").is_none() {
+            return Err(anyhow::anyhow!("jito bundle响应缺少result: {}", response_text));
         }
+        println!(" jito{}提交: {:?}", trade_type, start_time.elapsed());
 
         Ok(())
     }
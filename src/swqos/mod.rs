@@ -1,27 +1,43 @@
+pub mod astralane;
+pub mod blockrazor;
 pub mod bloxroute;
 pub mod common;
+pub mod health;
+pub mod helius;
 pub mod jito;
 pub mod nextblock;
+pub mod rate_limiter;
+pub mod sender_pool;
 pub mod solana_rpc;
 pub mod temporal;
+pub mod tpu;
 pub mod zeroslot;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use solana_rpc_client::{http_sender::HttpSender, rpc_client::RpcClientConfig};
 use solana_sdk::{commitment_config::CommitmentConfig, transaction::VersionedTransaction};
 use tokio::sync::RwLock;
 
 use anyhow::Result;
+use serde::Deserialize;
+
+pub use health::SwqosHealthStats;
+pub use rate_limiter::{RateLimitConfig, RateLimitedClient, SaturatedPolicy, SwqosTier};
+pub use tpu::TpuConfig;
 
 use crate::{
-    common::SolanaRpcClient,
+    common::{Lamports, SolanaRpcClient},
     constants::swqos::{
-        SWQOS_ENDPOINTS_BLOX, SWQOS_ENDPOINTS_JITO, SWQOS_ENDPOINTS_NEXTBLOCK,
+        SWQOS_ENDPOINTS_ASTRALANE, SWQOS_ENDPOINTS_BLOCKRAZOR, SWQOS_ENDPOINTS_BLOX,
+        SWQOS_ENDPOINTS_HELIUS, SWQOS_ENDPOINTS_JITO, SWQOS_ENDPOINTS_NEXTBLOCK,
         SWQOS_ENDPOINTS_TEMPORAL, SWQOS_ENDPOINTS_ZERO_SLOT,
     },
     swqos::{
-        bloxroute::BloxrouteClient, jito::JitoClient, nextblock::NextBlockClient,
-        solana_rpc::SolRpcClient, temporal::TemporalClient, zeroslot::ZeroSlotClient,
+        astralane::AstralaneClient, blockrazor::BlockRazorClient, bloxroute::BloxrouteClient,
+        helius::HeliusClient, jito::JitoClient, nextblock::NextBlockClient,
+        solana_rpc::SolRpcClient, temporal::TemporalClient, tpu::TpuClient,
+        zeroslot::ZeroSlotClient,
     },
 };
 
@@ -35,6 +51,9 @@ pub enum TradeType {
     CreateAndBuy,
     Buy,
     Sell,
+    CreatePool,
+    Deposit,
+    Withdraw,
 }
 
 impl std::fmt::Display for TradeType {
@@ -44,21 +63,36 @@ impl std::fmt::Display for TradeType {
             TradeType::CreateAndBuy => "创建并买入",
             TradeType::Buy => "买入",
             TradeType::Sell => "卖出",
+            TradeType::CreatePool => "创建池子",
+            TradeType::Deposit => "添加流动性",
+            TradeType::Withdraw => "移除流动性",
         };
         write!(f, "{}", s)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum SwqosType {
     Jito,
     NextBlock,
     ZeroSlot,
     Temporal,
     Bloxroute,
+    Helius,
+    BlockRazor,
+    Astralane,
+    /// 通过 QUIC 直连 leader TPU
+    Tpu,
     Default,
 }
 
+impl SwqosType {
+    /// 是否需要在交易中附带小费转账
+    pub fn need_tip(&self) -> bool {
+        !matches!(self, SwqosType::Default | SwqosType::Tpu)
+    }
+}
+
 pub type SwqosClient = dyn SwqosClientTrait + Send + Sync + 'static;
 
 #[async_trait::async_trait]
@@ -75,6 +109,22 @@ pub trait SwqosClientTrait {
     ) -> Result<()>;
     fn get_tip_account(&self) -> Result<String>;
     fn get_swqos_type(&self) -> SwqosType;
+    /// 服务商要求的最低小费，构建交易时小费会被抬高到该值
+    fn get_min_tip(&self) -> Lamports {
+        Lamports::ZERO
+    }
+    /// 发送前申请限流许可，返回 false 表示该服务商已饱和，本次应跳过
+    async fn acquire_send_permit(&self) -> bool {
+        true
+    }
+    /// 预热到服务商的连接，发送线程启动时调用
+    async fn warm_up(&self) -> Result<()> {
+        Ok(())
+    }
+    /// 连接健康状态，只有运行在发送线程中的客户端会记录
+    fn get_health(&self) -> Option<SwqosHealthStats> {
+        None
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -89,6 +139,51 @@ pub enum SwqosRegion {
     Default,
 }
 
+/// SWQOS 端点配置
+///
+/// 用于指向私有中继、尚未收录的新区域或本地模拟服务，
+/// 并可为该端点附加自定义请求头和超时时间。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SwqosEndpoint {
+    /// 端点地址，例如 `http://127.0.0.1:8899`
+    pub url: String,
+    /// 每个请求附带的自定义请求头
+    pub headers: Vec<(String, String)>,
+    /// 请求超时时间，默认 10 秒
+    pub timeout: Option<Duration>,
+    /// 连接超时时间，默认 5 秒
+    pub connect_timeout: Option<Duration>,
+}
+
+impl SwqosEndpoint {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), ..Default::default() }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout.unwrap_or(Duration::from_secs(10))
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout.unwrap_or(Duration::from_secs(5))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SwqosConfig {
     Default(String),
@@ -97,9 +192,54 @@ pub enum SwqosConfig {
     Bloxroute(String, SwqosRegion),
     Temporal(String, SwqosRegion),
     ZeroSlot(String, SwqosRegion),
+    /// Helius Sender，auth_token 可传空字符串
+    Helius(String, SwqosRegion),
+    BlockRazor(String, SwqosRegion),
+    Astralane(String, SwqosRegion),
+    /// 直连 leader TPU，无需 auth_token 和区域
+    Tpu(TpuConfig),
+    /// 使用自定义端点的服务商: (服务类型, auth_token, 端点配置)
+    /// `SwqosType::Default` 时 auth_token 会被忽略，端点即 RPC 地址；
+    /// `SwqosType::Tpu` 没有端点，构建客户端时返回错误，请使用 `SwqosConfig::Tpu`
+    Custom(SwqosType, String, SwqosEndpoint),
 }
 
 impl SwqosConfig {
+    pub fn swqos_type(&self) -> SwqosType {
+        match self {
+            SwqosConfig::Default(_) => SwqosType::Default,
+            SwqosConfig::Jito(..) => SwqosType::Jito,
+            SwqosConfig::NextBlock(..) => SwqosType::NextBlock,
+            SwqosConfig::Bloxroute(..) => SwqosType::Bloxroute,
+            SwqosConfig::Temporal(..) => SwqosType::Temporal,
+            SwqosConfig::ZeroSlot(..) => SwqosType::ZeroSlot,
+            SwqosConfig::Helius(..) => SwqosType::Helius,
+            SwqosConfig::BlockRazor(..) => SwqosType::BlockRazor,
+            SwqosConfig::Astralane(..) => SwqosType::Astralane,
+            SwqosConfig::Tpu(_) => SwqosType::Tpu,
+            SwqosConfig::Custom(swqos_type, ..) => *swqos_type,
+        }
+    }
+
+    /// 解析配置对应的端点，区域配置查表，自定义配置直接使用传入的端点
+    pub fn resolve_endpoint(&self) -> SwqosEndpoint {
+        match self {
+            SwqosConfig::Default(endpoint) => SwqosEndpoint::new(endpoint.clone()),
+            SwqosConfig::Jito(_, region)
+            | SwqosConfig::NextBlock(_, region)
+            | SwqosConfig::Bloxroute(_, region)
+            | SwqosConfig::Temporal(_, region)
+            | SwqosConfig::ZeroSlot(_, region)
+            | SwqosConfig::Helius(_, region)
+            | SwqosConfig::BlockRazor(_, region)
+            | SwqosConfig::Astralane(_, region) => {
+                SwqosEndpoint::new(SwqosConfig::get_endpoint(self.swqos_type(), region.clone()))
+            }
+            SwqosConfig::Tpu(_) => SwqosEndpoint::default(),
+            SwqosConfig::Custom(_, _, endpoint) => endpoint.clone(),
+        }
+    }
+
     pub fn get_endpoint(swqos_type: SwqosType, region: SwqosRegion) -> String {
         match swqos_type {
             SwqosType::Jito => SWQOS_ENDPOINTS_JITO[region as usize].to_string(),
@@ -107,7 +247,10 @@ impl SwqosConfig {
             SwqosType::ZeroSlot => SWQOS_ENDPOINTS_ZERO_SLOT[region as usize].to_string(),
             SwqosType::Temporal => SWQOS_ENDPOINTS_TEMPORAL[region as usize].to_string(),
             SwqosType::Bloxroute => SWQOS_ENDPOINTS_BLOX[region as usize].to_string(),
-            SwqosType::Default => "".to_string(),
+            SwqosType::Helius => SWQOS_ENDPOINTS_HELIUS[region as usize].to_string(),
+            SwqosType::BlockRazor => SWQOS_ENDPOINTS_BLOCKRAZOR[region as usize].to_string(),
+            SwqosType::Astralane => SWQOS_ENDPOINTS_ASTRALANE[region as usize].to_string(),
+            SwqosType::Tpu | SwqosType::Default => "".to_string(),
         }
     }
 
@@ -115,46 +258,128 @@ impl SwqosConfig {
         rpc_url: String,
         commitment: CommitmentConfig,
         swqos_config: SwqosConfig,
-    ) -> Arc<SwqosClient> {
-        match swqos_config {
-            SwqosConfig::Jito(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::Jito, region);
-                let jito_client = JitoClient::new(
-                    rpc_url.clone(),
-                    endpoint,
-                    auth_token
-                );
-                Arc::new(jito_client)
+    ) -> Result<Arc<SwqosClient>> {
+        if let SwqosConfig::Custom(SwqosType::Tpu, ..) = swqos_config {
+            return Err(anyhow::anyhow!("TPU 直连不支持自定义端点，请使用 SwqosConfig::Tpu"));
+        }
+        let endpoint = swqos_config.resolve_endpoint();
+        let auth_token = match &swqos_config {
+            SwqosConfig::Default(_) | SwqosConfig::Tpu(_) => String::new(),
+            SwqosConfig::Jito(auth_token, _)
+            | SwqosConfig::NextBlock(auth_token, _)
+            | SwqosConfig::Bloxroute(auth_token, _)
+            | SwqosConfig::Temporal(auth_token, _)
+            | SwqosConfig::ZeroSlot(auth_token, _)
+            | SwqosConfig::Helius(auth_token, _)
+            | SwqosConfig::BlockRazor(auth_token, _)
+            | SwqosConfig::Astralane(auth_token, _)
+            | SwqosConfig::Custom(_, auth_token, _) => auth_token.clone(),
+        };
+        match swqos_config.swqos_type() {
+            SwqosType::Jito => {
+                let jito_client = JitoClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(jito_client))
             }
-            SwqosConfig::NextBlock(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::NextBlock, region);
+            SwqosType::NextBlock => {
                 let nextblock_client =
-                    NextBlockClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
-                Arc::new(nextblock_client)
+                    NextBlockClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(nextblock_client))
             }
-            SwqosConfig::ZeroSlot(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::ZeroSlot, region);
+            SwqosType::ZeroSlot => {
                 let zeroslot_client =
-                    ZeroSlotClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
-                Arc::new(zeroslot_client)
+                    ZeroSlotClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(zeroslot_client))
             }
-            SwqosConfig::Temporal(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::Temporal, region);
+            SwqosType::Temporal => {
                 let temporal_client =
-                    TemporalClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
-                Arc::new(temporal_client)
+                    TemporalClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(temporal_client))
             }
-            SwqosConfig::Bloxroute(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::Bloxroute, region);
+            SwqosType::Bloxroute => {
                 let bloxroute_client =
-                    BloxrouteClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
-                Arc::new(bloxroute_client)
+                    BloxrouteClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(bloxroute_client))
+            }
+            SwqosType::Helius => {
+                let helius_client = HeliusClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(helius_client))
+            }
+            SwqosType::BlockRazor => {
+                let blockrazor_client =
+                    BlockRazorClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(blockrazor_client))
+            }
+            SwqosType::Astralane => {
+                let astralane_client =
+                    AstralaneClient::new_with_endpoint(rpc_url, endpoint, auth_token)?;
+                Ok(Arc::new(astralane_client))
             }
-            SwqosConfig::Default(endpoint) => {
-                let rpc = SolanaRpcClient::new_with_commitment(endpoint, commitment);
+            SwqosType::Tpu => {
+                let SwqosConfig::Tpu(tpu_config) = swqos_config else {
+                    unreachable!("自定义 TPU 配置已在上方拒绝");
+                };
+                let tpu_client = TpuClient::new(rpc_url, commitment, tpu_config)?;
+                Ok(Arc::new(tpu_client))
+            }
+            SwqosType::Default => {
+                // 自定义端点的请求头和超时同样作用于 RPC 请求
+                let http_client = common::build_http_client(&endpoint)?;
+                let rpc = SolanaRpcClient::new_sender(
+                    HttpSender::new_with_client(endpoint.url.clone(), http_client),
+                    RpcClientConfig::with_commitment(commitment),
+                );
                 let rpc_client = SolRpcClient::new(Arc::new(rpc));
-                Arc::new(rpc_client)
+                Ok(Arc::new(rpc_client))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_endpoint() {
+        let config = SwqosConfig::Jito("".to_string(), SwqosRegion::Frankfurt);
+        assert_eq!(
+            config.resolve_endpoint().url,
+            "https://frankfurt.mainnet.block-engine.jito.wtf"
+        );
+
+        let endpoint = SwqosEndpoint::new("http://127.0.0.1:8080")
+            .with_header("x-api-key", "local")
+            .with_timeout(Duration::from_millis(500));
+        let config = SwqosConfig::Custom(SwqosType::ZeroSlot, "token".to_string(), endpoint);
+        let resolved = config.resolve_endpoint();
+        assert_eq!(config.swqos_type(), SwqosType::ZeroSlot);
+        assert_eq!(resolved.url, "http://127.0.0.1:8080");
+        assert_eq!(resolved.headers, vec![("x-api-key".to_string(), "local".to_string())]);
+        assert_eq!(resolved.timeout(), Duration::from_millis(500));
+        assert_eq!(resolved.connect_timeout(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_custom_endpoint_client() {
+        let rpc_url = "http://127.0.0.1:8899".to_string();
+        let commitment = CommitmentConfig::confirmed();
+
+        let endpoint =
+            SwqosEndpoint::new("http://127.0.0.1:8080").with_header("x-api-key", "local");
+        let config = SwqosConfig::Custom(SwqosType::Default, String::new(), endpoint);
+        let client = SwqosConfig::get_swqos_client(rpc_url.clone(), commitment, config);
+        assert_eq!(client.ok().map(|c| c.get_swqos_type()), Some(SwqosType::Default));
+
+        // 请求头会应用到 RPC 客户端，非法请求头在构建时报错
+        let endpoint = SwqosEndpoint::new("http://127.0.0.1:8080").with_header("bad header", "x");
+        let config = SwqosConfig::Custom(SwqosType::Default, String::new(), endpoint);
+        assert!(SwqosConfig::get_swqos_client(rpc_url.clone(), commitment, config).is_err());
+
+        let config = SwqosConfig::Custom(
+            SwqosType::Tpu,
+            String::new(),
+            SwqosEndpoint::new("http://127.0.0.1:8080"),
+        );
+        assert!(SwqosConfig::get_swqos_client(rpc_url, commitment, config).is_err());
+    }
+}
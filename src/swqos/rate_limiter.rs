@@ -0,0 +1,265 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::{
+    common::Lamports,
+    swqos::{SwqosClient, SwqosClientTrait, SwqosHealthStats, SwqosType, TradeType},
+};
+
+/// 服务商限流档位，数值为通用默认值，具体以所购套餐为准
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum SwqosTier {
+    Free,
+    Standard,
+    Pro,
+}
+
+/// 令牌桶满时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum SaturatedPolicy {
+    /// 直接跳过该服务商
+    Skip,
+    /// 最多排队等待指定毫秒数，超时后跳过
+    Queue { max_wait_ms: u64 },
+}
+
+/// 单个服务商的限流配置
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RateLimitConfig {
+    /// 每秒补充的请求数
+    pub requests_per_second: f64,
+    /// 令牌桶容量，即允许的突发请求数
+    pub burst: u32,
+    pub on_saturated: SaturatedPolicy,
+}
+
+impl RateLimitConfig {
+    pub fn per_second(requests_per_second: f64) -> Self {
+        Self {
+            requests_per_second,
+            burst: requests_per_second.ceil().max(1.0) as u32,
+            on_saturated: SaturatedPolicy::Skip,
+        }
+    }
+
+    pub fn for_tier(tier: SwqosTier) -> Self {
+        match tier {
+            SwqosTier::Free => Self::per_second(1.0),
+            SwqosTier::Standard => Self::per_second(5.0),
+            SwqosTier::Pro => Self::per_second(50.0),
+        }
+    }
+
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    pub fn with_queue(mut self, max_wait: Duration) -> Self {
+        self.on_saturated = SaturatedPolicy::Queue { max_wait_ms: max_wait.as_millis() as u64 };
+        self
+    }
+
+    /// 检查配置是否可用，反序列化得到的配置在创建客户端前也会经过这里
+    pub fn validate(&self) -> Result<()> {
+        if !self.requests_per_second.is_finite() || self.requests_per_second <= 0.0 {
+            return Err(anyhow!("限流速率必须是大于0的有限数: {}", self.requests_per_second));
+        }
+        Ok(())
+    }
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// 令牌桶限流器
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self> {
+        if !requests_per_second.is_finite() || requests_per_second <= 0.0 {
+            return Err(anyhow!("限流速率必须是大于0的有限数: {}", requests_per_second));
+        }
+        let capacity = burst.max(1) as f64;
+        Ok(Self {
+            capacity,
+            refill_per_sec: requests_per_second,
+            state: Mutex::new(BucketState { tokens: capacity, last_refill: Instant::now() }),
+        })
+    }
+
+    fn refill(&self, state: &mut BucketState, now: Instant) {
+        let elapsed = now.saturating_duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        state.last_refill = now;
+    }
+
+    /// 尝试取出一个令牌，成功返回 `Ok`，否则返回距离下一个令牌可用的时间
+    pub fn try_acquire_at(&self, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state, now);
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            return Ok(());
+        }
+        // 速率极低时等待时间可能超出 Duration 范围
+        let wait = (1.0 - state.tokens) / self.refill_per_sec;
+        Err(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+    }
+
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        self.try_acquire_at(Instant::now())
+    }
+}
+
+/// 为服务商客户端附加令牌桶限流
+pub struct RateLimitedClient {
+    inner: Arc<SwqosClient>,
+    bucket: TokenBucket,
+    config: RateLimitConfig,
+}
+
+impl RateLimitedClient {
+    pub fn new(inner: Arc<SwqosClient>, config: RateLimitConfig) -> Result<Self> {
+        config.validate()?;
+        let bucket = TokenBucket::new(config.requests_per_second, config.burst)?;
+        Ok(Self { inner, bucket, config })
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for RateLimitedClient {
+    async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        self.inner.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        self.inner.send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        self.inner.get_tip_account()
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.inner.get_swqos_type()
+    }
+
+    fn get_min_tip(&self) -> Lamports {
+        self.inner.get_min_tip()
+    }
+
+    async fn warm_up(&self) -> Result<()> {
+        self.inner.warm_up().await
+    }
+
+    fn get_health(&self) -> Option<SwqosHealthStats> {
+        self.inner.get_health()
+    }
+
+    async fn acquire_send_permit(&self) -> bool {
+        let wait = match self.bucket.try_acquire() {
+            Ok(()) => return true,
+            Err(wait) => wait,
+        };
+        match self.config.on_saturated {
+            SaturatedPolicy::Skip => false,
+            SaturatedPolicy::Queue { max_wait_ms } => {
+                // 超出 Instant 范围的期限视为不限时，超出范围的等待视为等不到令牌
+                let deadline = Instant::now().checked_add(Duration::from_millis(max_wait_ms));
+                let mut wait = wait;
+                loop {
+                    match (Instant::now().checked_add(wait), deadline) {
+                        (Some(ready_at), Some(deadline)) if ready_at <= deadline => {}
+                        (Some(_), None) => {}
+                        _ => return false,
+                    }
+                    tokio::time::sleep(wait).await;
+                    match self.bucket.try_acquire() {
+                        Ok(()) => return true,
+                        Err(next_wait) => wait = next_wait,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_burst_and_refill() {
+        let bucket = TokenBucket::new(2.0, 2).unwrap();
+        let start = Instant::now();
+
+        assert!(bucket.try_acquire_at(start).is_ok());
+        assert!(bucket.try_acquire_at(start).is_ok());
+        let wait = bucket.try_acquire_at(start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        // 500ms 后补充一个令牌
+        let later = start + Duration::from_millis(500);
+        assert!(bucket.try_acquire_at(later).is_ok());
+        assert!(bucket.try_acquire_at(later).is_err());
+
+        // 长时间空闲后不会超过桶容量
+        let idle = later + Duration::from_secs(10);
+        assert!(bucket.try_acquire_at(idle).is_ok());
+        assert!(bucket.try_acquire_at(idle).is_ok());
+        assert!(bucket.try_acquire_at(idle).is_err());
+    }
+
+    #[test]
+    fn test_invalid_rate_is_rejected() {
+        assert!(TokenBucket::new(0.0, 1).is_err());
+        assert!(TokenBucket::new(-1.0, 1).is_err());
+        assert!(TokenBucket::new(f64::NAN, 1).is_err());
+        assert!(TokenBucket::new(f64::INFINITY, 1).is_err());
+        assert!(RateLimitConfig::per_second(f64::NAN).validate().is_err());
+        assert!(RateLimitConfig::per_second(0.5).validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queue_with_extreme_wait_does_not_panic() {
+        use crate::swqos::{SwqosConfig, SwqosRegion};
+        use solana_sdk::commitment_config::CommitmentConfig;
+
+        let inner = SwqosConfig::get_swqos_client(
+            "http://127.0.0.1:8899".to_string(),
+            CommitmentConfig::confirmed(),
+            SwqosConfig::Jito("".to_string(), SwqosRegion::Frankfurt),
+        )
+        .unwrap();
+        // 下一个令牌要等待超出 Duration 范围的时间，期限也超出 Instant 范围
+        let config = RateLimitConfig {
+            requests_per_second: f64::MIN_POSITIVE,
+            burst: 1,
+            on_saturated: SaturatedPolicy::Queue { max_wait_ms: u64::MAX },
+        };
+        let client = RateLimitedClient::new(inner, config).unwrap();
+        assert!(client.acquire_send_permit().await);
+        assert!(!client.acquire_send_permit().await);
+    }
+}
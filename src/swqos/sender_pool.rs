@@ -0,0 +1,217 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::transaction::VersionedTransaction;
+use tokio::sync::{mpsc, oneshot, watch};
+
+use crate::{
+    common::Lamports,
+    swqos::{
+        health::{SwqosHealth, SwqosHealthStats},
+        SwqosClient, SwqosClientTrait, SwqosType, TradeType,
+    },
+};
+
+enum SendJob {
+    Transaction(TradeType, VersionedTransaction, oneshot::Sender<Result<()>>),
+    Transactions(TradeType, Vec<VersionedTransaction>, oneshot::Sender<Result<()>>),
+}
+
+/// 由专用发送线程执行发送的服务商客户端
+///
+/// 每个服务商独占一个绑核线程，线程内运行单线程 tokio 运行时，
+/// HTTP 连接在该运行时内预热并复用。交易通过 channel 交给发送线程，
+/// 发送耗时不再受调用方运行时调度影响，也不会绑定调用方的 tokio 工作线程。
+///
+/// 设置了 `keepalive_interval` 时，发送线程会定期重复预热，避免空闲后连接被断开，
+/// 结果记录在健康状态中。
+pub struct PooledClient {
+    inner: Arc<SwqosClient>,
+    sender: mpsc::UnboundedSender<SendJob>,
+    health: Arc<SwqosHealth>,
+    warmed_up: watch::Receiver<bool>,
+}
+
+/// 执行一次预热并记录结果
+async fn ping_and_record(client: &SwqosClient, health: &SwqosHealth) {
+    let start_time = Instant::now();
+    match client.warm_up().await {
+        Ok(()) => health.record_success(start_time.elapsed()),
+        Err(e) => {
+            eprintln!(" {:?}预热失败: {:?}", client.get_swqos_type(), e);
+            health.record_failure(start_time.elapsed(), e.to_string());
+        }
+    }
+}
+
+impl PooledClient {
+    /// 启动发送线程，`core_id` 为空时不绑核，`keepalive_interval` 为空时不保活
+    pub fn spawn(
+        inner: Arc<SwqosClient>,
+        core_id: Option<core_affinity::CoreId>,
+        keepalive_interval: Option<Duration>,
+    ) -> Result<Self> {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<SendJob>();
+        let (warmed_up_sender, warmed_up) = watch::channel(false);
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let client = inner.clone();
+        let swqos_type = inner.get_swqos_type();
+        let health = Arc::new(SwqosHealth::new(swqos_type));
+        let worker_health = health.clone();
+
+        std::thread::Builder::new().name(format!("swqos-sender-{:?}", swqos_type)).spawn(
+            move || {
+                if let Some(core_id) = core_id {
+                    core_affinity::set_for_current(core_id);
+                }
+                runtime.block_on(async move {
+                    ping_and_record(client.as_ref(), &worker_health).await;
+                    let _ = warmed_up_sender.send(true);
+
+                    if let Some(interval) = keepalive_interval {
+                        let client = client.clone();
+                        let health = worker_health.clone();
+                        tokio::spawn(async move {
+                            let mut ticker = tokio::time::interval(interval);
+                            // 第一次 tick 立即返回，启动预热已经完成，跳过
+                            ticker.tick().await;
+                            loop {
+                                ticker.tick().await;
+                                ping_and_record(client.as_ref(), &health).await;
+                            }
+                        });
+                    }
+
+                    // 每个任务单独 spawn，等待确认时不阻塞后续发送
+                    while let Some(job) = receiver.recv().await {
+                        let client = client.clone();
+                        tokio::spawn(async move {
+                            match job {
+                                SendJob::Transaction(trade_type, transaction, reply) => {
+                                    let result =
+                                        client.send_transaction(trade_type, &transaction).await;
+                                    let _ = reply.send(result);
+                                }
+                                SendJob::Transactions(trade_type, transactions, reply) => {
+                                    let result =
+                                        client.send_transactions(trade_type, &transactions).await;
+                                    let _ = reply.send(result);
+                                }
+                            }
+                        });
+                    }
+                });
+            },
+        )?;
+
+        Ok(Self { inner, sender, health, warmed_up })
+    }
+
+    /// 等待发送线程完成首次预热，超时返回 false
+    pub async fn wait_warmed_up(&self, timeout: Duration) -> bool {
+        let mut warmed_up = self.warmed_up.clone();
+        let ready = matches!(
+            tokio::time::timeout(timeout, warmed_up.wait_for(|ready| *ready)).await,
+            Ok(Ok(_))
+        );
+        ready
+    }
+
+    async fn dispatch(
+        &self,
+        job: impl FnOnce(oneshot::Sender<Result<()>>) -> SendJob,
+    ) -> Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.sender
+            .send(job(reply))
+            .map_err(|_| anyhow!("{:?}发送线程已停止", self.inner.get_swqos_type()))?;
+        result.await.map_err(|_| anyhow!("{:?}发送线程已停止", self.inner.get_swqos_type()))?
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for PooledClient {
+    async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        let transaction = transaction.clone();
+        self.dispatch(|reply| SendJob::Transaction(trade_type, transaction, reply)).await
+    }
+
+    async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        let transactions = transactions.clone();
+        self.dispatch(|reply| SendJob::Transactions(trade_type, transactions, reply)).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        self.inner.get_tip_account()
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.inner.get_swqos_type()
+    }
+
+    fn get_min_tip(&self) -> Lamports {
+        self.inner.get_min_tip()
+    }
+
+    async fn acquire_send_permit(&self) -> bool {
+        self.inner.acquire_send_permit().await
+    }
+
+    async fn warm_up(&self) -> Result<()> {
+        self.inner.warm_up().await
+    }
+
+    fn get_health(&self) -> Option<SwqosHealthStats> {
+        Some(self.health.stats())
+    }
+}
+
+/// 为每个服务商启动一个绑核发送线程，核心按顺序轮流分配
+///
+/// 所有发送线程并行预热，最多等待 `warm_up_timeout`，超时的服务商继续在后台预热。
+/// 启动失败的服务商退回为直接在调用方运行时中发送。
+pub async fn start_sender_pool(
+    swqos_clients: Vec<Arc<SwqosClient>>,
+    keepalive_interval: Option<Duration>,
+    warm_up_timeout: Duration,
+) -> Vec<Arc<SwqosClient>> {
+    let cores = core_affinity::get_core_ids().unwrap_or_default();
+    let mut pooled_clients = Vec::with_capacity(swqos_clients.len());
+    for (i, client) in swqos_clients.into_iter().enumerate() {
+        let core_id = if cores.is_empty() { None } else { Some(cores[i % cores.len()]) };
+        match PooledClient::spawn(client.clone(), core_id, keepalive_interval) {
+            Ok(pooled) => pooled_clients.push(Ok(Arc::new(pooled))),
+            Err(e) => {
+                eprintln!(" {:?}发送线程启动失败: {:?}", client.get_swqos_type(), e);
+                pooled_clients.push(Err(client));
+            }
+        }
+    }
+
+    let deadline = Instant::now() + warm_up_timeout;
+    for pooled in pooled_clients.iter().flatten() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if !pooled.wait_warmed_up(remaining).await {
+            eprintln!(" {:?}预热超时", pooled.get_swqos_type());
+        }
+    }
+
+    pooled_clients
+        .into_iter()
+        .map(|pooled| match pooled {
+            Ok(pooled) => pooled as Arc<SwqosClient>,
+            Err(client) => client,
+        })
+        .collect()
+}
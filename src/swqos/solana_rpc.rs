@@ -66,6 +66,11 @@ impl SwqosClientTrait for SolRpcClient {
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Default
     }
+
+    async fn warm_up(&self) -> Result<()> {
+        self.rpc_client.get_health().await?;
+        Ok(())
+    }
 }
 
 impl SolRpcClient {
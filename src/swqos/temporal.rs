@@ -3,11 +3,11 @@ use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use solana_transaction_status::UiTransactionEncoding;
-use std::time::Duration;
 use std::{sync::Arc, time::Instant};
 
+use crate::swqos::common::{build_http_client, ping_endpoint};
 use crate::swqos::SwqosClientTrait;
-use crate::swqos::{SwqosType, TradeType};
+use crate::swqos::{SwqosEndpoint, SwqosType, TradeType};
 use anyhow::Result;
 use solana_sdk::transaction::VersionedTransaction;
 
@@ -50,26 +50,36 @@ impl SwqosClientTrait for TemporalClient {
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Temporal
     }
+
+    async fn warm_up(&self) -> Result<()> {
+        self.ping().await
+    }
 }
 
 impl TemporalClient {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Result<Self> {
+        Self::new_with_endpoint(rpc_url, SwqosEndpoint::new(endpoint), auth_token)
+    }
+
+    /// 使用自定义端点配置（请求头、超时）创建客户端
+    pub fn new_with_endpoint(
+        rpc_url: String,
+        endpoint: SwqosEndpoint,
+        auth_token: String,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = Client::builder()
-            .pool_idle_timeout(Duration::from_secs(60))
-            .pool_max_idle_per_host(64)
-            .tcp_keepalive(Some(Duration::from_secs(1200)))
-            .http2_keep_alive_interval(Duration::from_secs(15))
-            .timeout(Duration::from_secs(10))
-            .connect_timeout(Duration::from_secs(5))
-            .build()
-            .unwrap();
-        Self {
+        let http_client = build_http_client(&endpoint)?;
+        Ok(Self {
             rpc_client: Arc::new(rpc_client),
-            endpoint,
+            endpoint: endpoint.url,
             auth_token,
             http_client,
-        }
+        })
+    }
+
+    /// 请求 `/ping` 保持连接活跃，避免首笔交易承担建连耗时
+    pub async fn ping(&self) -> Result<()> {
+        ping_endpoint(&self.http_client, &self.endpoint, "/ping").await
     }
 
     pub async fn send_transaction(
@@ -0,0 +1,357 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use solana_client::connection_cache::ConnectionCache;
+use solana_connection_cache::nonblocking::client_connection::ClientConnection;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Signature},
+    transaction::VersionedTransaction,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    common::SolanaRpcClient,
+    swqos::{common::poll_transaction_confirmation, SwqosClientTrait, SwqosType, TradeType},
+};
+
+/// 默认向之后多少个槽位的 leader 发送
+pub const TPU_DEFAULT_FANOUT_SLOTS: u64 = 8;
+/// 每次刷新拉取的 leader 计划长度
+const LEADER_WINDOW_SLOTS: u64 = 64;
+/// leader 计划的最长使用时间
+const LEADER_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+/// 集群节点信息的最长使用时间
+const CLUSTER_NODES_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+const SLOT_DURATION_MS: u128 = 400;
+
+/// TPU 直连配置
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TpuConfig {
+    /// 向之后多少个槽位的 leader 发送，为 0 时使用 `TPU_DEFAULT_FANOUT_SLOTS`
+    pub fanout_slots: u64,
+    /// 质押身份 keypair 文件路径，为空时以非质押身份建立 QUIC 连接
+    pub identity_keypair_path: Option<String>,
+    /// 直接指定 TPU QUIC 地址并跳过 leader 查询，用于本地监听器测试
+    pub leader_override: Vec<SocketAddr>,
+}
+
+impl TpuConfig {
+    pub fn fanout_slots(&self) -> u64 {
+        if self.fanout_slots == 0 {
+            TPU_DEFAULT_FANOUT_SLOTS
+        } else {
+            self.fanout_slots
+        }
+    }
+}
+
+#[derive(Default)]
+struct LeaderTracker {
+    /// 从最近一次刷新时的槽位开始的 leader 计划
+    leaders: Vec<Pubkey>,
+    refreshed_at: Option<Instant>,
+    tpu_quic: HashMap<Pubkey, SocketAddr>,
+    nodes_refreshed_at: Option<Instant>,
+}
+
+impl LeaderTracker {
+    /// 按刷新后经过的时间估算当前槽位在 leader 计划中的下标，避免每次发送都查询 RPC
+    fn current_index(&self) -> Option<usize> {
+        let refreshed_at = self.refreshed_at?;
+        if refreshed_at.elapsed() > LEADER_REFRESH_INTERVAL {
+            return None;
+        }
+        Some((refreshed_at.elapsed().as_millis() / SLOT_DURATION_MS) as usize)
+    }
+
+    fn is_stale(&self, fanout_slots: u64) -> bool {
+        match self.current_index() {
+            Some(index) => index + fanout_slots as usize > self.leaders.len(),
+            None => true,
+        }
+    }
+
+    fn nodes_stale(&self) -> bool {
+        self.nodes_refreshed_at.is_none_or(|t| t.elapsed() > CLUSTER_NODES_REFRESH_INTERVAL)
+    }
+
+    /// 从 `index` 开始的 `fanout_slots` 个槽位的 leader TPU 地址（去重）
+    fn leader_addresses(&self, index: usize, fanout_slots: u64) -> Vec<SocketAddr> {
+        let mut addresses = Vec::new();
+        for leader in self.leaders.iter().skip(index).take(fanout_slots as usize) {
+            if let Some(addr) = self.tpu_quic.get(leader) {
+                if !addresses.contains(addr) {
+                    addresses.push(*addr);
+                }
+            }
+        }
+        addresses
+    }
+}
+
+/// 通过 QUIC 直接发送交易到即将出块的 leader TPU，无需中继和小费
+#[derive(Clone)]
+pub struct TpuClient {
+    pub rpc_client: Arc<SolanaRpcClient>,
+    pub config: TpuConfig,
+    connection_cache: Arc<ConnectionCache>,
+    leader_tracker: Arc<RwLock<LeaderTracker>>,
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for TpuClient {
+    async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        self.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        for transaction in transactions {
+            self.send_transaction(trade_type, transaction).await?;
+        }
+        Ok(())
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        Ok("".to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::Tpu
+    }
+}
+
+impl TpuClient {
+    pub fn new(rpc_url: String, commitment: CommitmentConfig, config: TpuConfig) -> Result<Self> {
+        let rpc_client = SolanaRpcClient::new_with_commitment(rpc_url, commitment);
+        let identity = config
+            .identity_keypair_path
+            .as_ref()
+            .map(|path| {
+                read_keypair_file(path)
+                    .map_err(|e| anyhow!("读取TPU身份keypair失败 {}: {}", path, e))
+            })
+            .transpose()?;
+        let connection_cache = match &identity {
+            Some(identity) => ConnectionCache::new_with_client_options(
+                "sol-trade-sdk-tpu",
+                1,
+                None,
+                Some((identity, IpAddr::V4(Ipv4Addr::UNSPECIFIED))),
+                None,
+            ),
+            None => ConnectionCache::new_quic("sol-trade-sdk-tpu", 1),
+        };
+        Ok(Self {
+            rpc_client: Arc::new(rpc_client),
+            config,
+            connection_cache: Arc::new(connection_cache),
+            leader_tracker: Arc::new(RwLock::new(LeaderTracker::default())),
+        })
+    }
+
+    /// 重新拉取 leader 计划，必要时同时刷新集群节点的 TPU QUIC 地址
+    async fn refresh_leaders(&self) -> Result<()> {
+        let fanout_slots = self.config.fanout_slots();
+        let mut tracker = self.leader_tracker.write().await;
+        // 其他任务可能已经完成刷新
+        if !tracker.is_stale(fanout_slots) && !tracker.nodes_stale() {
+            return Ok(());
+        }
+
+        let slot = self.rpc_client.get_slot().await?;
+        let leaders = self
+            .rpc_client
+            .get_slot_leaders(slot, LEADER_WINDOW_SLOTS.max(fanout_slots * 2))
+            .await?;
+        tracker.leaders = leaders;
+        tracker.refreshed_at = Some(Instant::now());
+
+        if tracker.nodes_stale() {
+            let nodes = self.rpc_client.get_cluster_nodes().await?;
+            tracker.tpu_quic = nodes
+                .into_iter()
+                .filter_map(|node| {
+                    let pubkey = Pubkey::from_str(&node.pubkey).ok()?;
+                    Some((pubkey, node.tpu_quic?))
+                })
+                .collect();
+            tracker.nodes_refreshed_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// 获取即将出块的 leader TPU QUIC 地址
+    pub async fn upcoming_leader_addresses(&self) -> Result<Vec<SocketAddr>> {
+        if !self.config.leader_override.is_empty() {
+            return Ok(self.config.leader_override.clone());
+        }
+
+        let fanout_slots = self.config.fanout_slots();
+        {
+            let tracker = self.leader_tracker.read().await;
+            if !tracker.is_stale(fanout_slots) && !tracker.nodes_stale() {
+                if let Some(index) = tracker.current_index() {
+                    return Ok(tracker.leader_addresses(index, fanout_slots));
+                }
+            }
+        }
+
+        self.refresh_leaders().await?;
+        let tracker = self.leader_tracker.read().await;
+        Ok(tracker.leader_addresses(tracker.current_index().unwrap_or(0), fanout_slots))
+    }
+
+    /// 并发发送到所有目标 leader，任意一个成功即视为提交成功
+    async fn send_wire_transaction(&self, wire_transaction: &[u8]) -> Result<usize> {
+        let addresses = self.upcoming_leader_addresses().await?;
+        if addresses.is_empty() {
+            return Err(anyhow!("没有可用的leader TPU地址"));
+        }
+
+        let sends = addresses.iter().map(|addr| {
+            let connection = self.connection_cache.get_nonblocking_connection(addr);
+            async move { connection.send_data(wire_transaction).await }
+        });
+        let results = futures::future::join_all(sends).await;
+
+        let success_count = results.iter().filter(|result| result.is_ok()).count();
+        if success_count == 0 {
+            let errors: Vec<String> = results
+                .into_iter()
+                .filter_map(|result| result.err())
+                .map(|e| e.to_string())
+                .collect();
+            return Err(anyhow!("发送到所有leader失败: {:?}", errors));
+        }
+        Ok(success_count)
+    }
+
+    pub async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        let start_time = Instant::now();
+        let wire_transaction = bincode::serialize(transaction)?;
+        let signature: Signature = transaction.signatures[0];
+
+        match self.send_wire_transaction(&wire_transaction).await {
+            Ok(count) => {
+                println!(" tpu{}提交({}个leader): {:?}", trade_type, count, start_time.elapsed())
+            }
+            Err(e) => {
+                eprintln!(" tpu{}提交失败: {:?}", trade_type, e);
+                return Err(e);
+            }
+        }
+
+        let start_time: Instant = Instant::now();
+        match poll_transaction_confirmation(&self.rpc_client, signature).await {
+            Ok(_) => (),
+            Err(_) => (),
+        }
+
+        println!(" tpu{}确认: {:?}", trade_type, start_time.elapsed());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leader_addresses_dedup() {
+        let leader_a = Pubkey::new_unique();
+        let leader_b = Pubkey::new_unique();
+        let addr_a: SocketAddr = "127.0.0.1:8009".parse().unwrap();
+        let addr_b: SocketAddr = "127.0.0.2:8009".parse().unwrap();
+
+        let tracker = LeaderTracker {
+            leaders: vec![leader_a, leader_a, leader_a, leader_a, leader_b, leader_b],
+            refreshed_at: Some(Instant::now()),
+            tpu_quic: HashMap::from([(leader_a, addr_a), (leader_b, addr_b)]),
+            nodes_refreshed_at: Some(Instant::now()),
+        };
+
+        assert_eq!(tracker.leader_addresses(0, 4), vec![addr_a]);
+        assert_eq!(tracker.leader_addresses(2, 4), vec![addr_a, addr_b]);
+        assert!(!tracker.is_stale(4));
+        assert!(tracker.is_stale(8));
+    }
+
+    #[test]
+    fn test_missing_identity_keypair() {
+        let config = TpuConfig {
+            identity_keypair_path: Some("/nonexistent/tpu-identity.json".to_string()),
+            ..TpuConfig::default()
+        };
+        let result = TpuClient::new(
+            "http://127.0.0.1:8899".to_string(),
+            CommitmentConfig::confirmed(),
+            config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_send_to_local_quic_listener() {
+        use solana_sdk::signature::Keypair;
+        use solana_streamer::{
+            quic::{spawn_server, QuicServerParams},
+            streamer::StakedNodes,
+        };
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // 本地 TPU QUIC 监听器，收到的数据包写入 channel
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let listener_addr = socket.local_addr().unwrap();
+        let (packet_sender, packet_receiver) = crossbeam_channel::unbounded();
+        let exit = Arc::new(AtomicBool::new(false));
+        let server = spawn_server(
+            "solTpuTest",
+            "tpu_test",
+            socket,
+            &Keypair::new(),
+            packet_sender,
+            exit.clone(),
+            Arc::new(std::sync::RwLock::new(StakedNodes::default())),
+            QuicServerParams::default(),
+        )
+        .unwrap();
+
+        let config = TpuConfig { leader_override: vec![listener_addr], ..TpuConfig::default() };
+        let client = TpuClient::new(
+            "http://127.0.0.1:8899".to_string(),
+            CommitmentConfig::confirmed(),
+            config,
+        )
+        .unwrap();
+        let wire_transaction = vec![7u8; 200];
+        assert_eq!(client.send_wire_transaction(&wire_transaction).await.unwrap(), 1);
+
+        let batch = packet_receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        let packet = batch.iter().next().unwrap();
+        assert_eq!(packet.data(..).unwrap(), wire_transaction.as_slice());
+
+        exit.store(true, Ordering::Relaxed);
+        server.thread.join().unwrap();
+    }
+}
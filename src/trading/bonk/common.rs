@@ -1,4 +1,5 @@
 use crate::constants;
+use anyhow::anyhow;
 use solana_sdk::pubkey::Pubkey;
 
 pub fn get_amount_in_net(
@@ -86,6 +87,79 @@ pub fn get_amount_out(
     amount_out as u64
 }
 
+/// 卖出 `amount_in` 个代币得到的SOL数量，手续费从输出中扣除
+pub fn get_sell_amount_out(
+    amount_in: u64,
+    protocol_fee_rate: u128,
+    platform_fee_rate: u128,
+    share_fee_rate: u128,
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+) -> Result<u64, anyhow::Error> {
+    let amount_in_u128 = amount_in as u128;
+    let input_reserve = virtual_base
+        .checked_sub(real_base)
+        .ok_or_else(|| anyhow!("Pool real base reserve exceeds the virtual reserve"))?;
+    let output_reserve = virtual_quote
+        .checked_add(real_quote)
+        .ok_or_else(|| anyhow!("Pool quote reserve overflows"))?;
+    let amount_out = amount_in_u128
+        .checked_mul(output_reserve)
+        .ok_or_else(|| anyhow!("Sell amount overflows"))?
+        / (input_reserve + amount_in_u128);
+    let protocol_fee = amount_out * protocol_fee_rate / 10000;
+    let platform_fee = amount_out * platform_fee_rate / 10000;
+    let share_fee = amount_out * share_fee_rate / 10000;
+    amount_out
+        .checked_sub(protocol_fee + platform_fee + share_fee)
+        .and_then(|amount_out| u64::try_from(amount_out).ok())
+        .ok_or_else(|| anyhow!("Invalid sell amount out"))
+}
+
+/// 卖出得到 `amount_out` 个SOL（扣除手续费后）需要卖出的代币数量，向上取整
+///
+/// 输出超过池中可用的SOL时返回 `None`。
+pub fn get_sell_amount_in(
+    amount_out: u64,
+    protocol_fee_rate: u128,
+    platform_fee_rate: u128,
+    share_fee_rate: u128,
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+) -> Option<u64> {
+    let total_fee_rate = protocol_fee_rate + platform_fee_rate + share_fee_rate;
+    let amount_out_gross = (amount_out as u128 * 10000).div_ceil(10000 - total_fee_rate);
+    let input_reserve = virtual_base.checked_sub(real_base)?;
+    let output_reserve = virtual_quote.checked_add(real_quote)?;
+    let denominator = output_reserve.checked_sub(amount_out_gross).filter(|d| *d > 0)?;
+    let amount_in = (amount_out_gross * input_reserve).div_ceil(denominator);
+    u64::try_from(amount_in).ok()
+}
+
+/// 常数乘积曲线初始化后的虚拟储备 `(virtual_base, virtual_quote)`
+///
+/// 卖出 `total_base_sell` 后筹集到 `total_quote_fund_raising`，且此时价格等于迁移时
+/// 剩余代币与筹集资金组成的池子价格，由这两个条件解出初始虚拟储备。
+pub fn get_initial_reserves(
+    supply: u64,
+    total_base_sell: u64,
+    total_quote_fund_raising: u64,
+    total_locked_amount: u64,
+) -> Option<(u128, u128)> {
+    let sell = total_base_sell as u128;
+    let fund = total_quote_fund_raising as u128;
+    let migrate_base =
+        (supply as u128).checked_sub(sell)?.checked_sub(total_locked_amount as u128)?;
+    let denominator = sell.checked_sub(migrate_base).filter(|d| *d > 0)?;
+    let virtual_base = sell.checked_mul(sell)? / denominator;
+    let virtual_quote = fund.checked_mul(migrate_base)? / denominator;
+    Some((virtual_base, virtual_quote))
+}
+
 pub fn get_pool_pda(base_mint: &Pubkey, quote_mint: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 3] = &[
         constants::bonk::seeds::POOL_SEED,
@@ -186,4 +260,75 @@ mod tests {
         assert!(geet_amount_out_result == 66275810509273);
         assert!(get_amount_in_result == 753217040);
     }
+
+    #[test]
+    fn test_initial_reserves_match_letsbonk_defaults() {
+        let (virtual_base, virtual_quote) = get_initial_reserves(
+            constants::bonk::launch::DEFAULT_SUPPLY,
+            constants::bonk::launch::DEFAULT_TOTAL_BASE_SELL,
+            constants::bonk::launch::DEFAULT_TOTAL_QUOTE_FUND_RAISING,
+            0,
+        )
+        .unwrap();
+        // 链上池子的初始值为 1073025605596382 / 30000852951，取整方式不同只有极小误差
+        assert_eq!(virtual_quote, 30000852951);
+        assert!(virtual_base.abs_diff(1073025605596382) < 10_000);
+        assert!(get_initial_reserves(100, 40, 1, 0).is_none());
+    }
+
+    #[test]
+    fn test_sell_exact_out_round_trip() {
+        let (virtual_base, virtual_quote) = (1073025605596382, 30000852951);
+        let (real_base, real_quote) = (200_000_000_000_000, 10_000_000_000);
+        let amount_out = 1_000_000_000;
+        let amount_in = get_sell_amount_in(
+            amount_out,
+            PROTOCOL_FEE_RATE,
+            PLATFORM_FEE_RATE,
+            SHARE_FEE_RATE,
+            virtual_base,
+            virtual_quote,
+            real_base,
+            real_quote,
+        )
+        .unwrap();
+        let sell = |amount_in| {
+            get_sell_amount_out(
+                amount_in,
+                PROTOCOL_FEE_RATE,
+                PLATFORM_FEE_RATE,
+                SHARE_FEE_RATE,
+                virtual_base,
+                virtual_quote,
+                real_base,
+                real_quote,
+            )
+            .unwrap()
+        };
+        // 向上取整后足够，再少卖约 1 lamport 价值的代币就不够
+        assert!(sell(amount_in) >= amount_out);
+        assert!(sell(amount_in - 100_000) < amount_out);
+        assert!(get_sell_amount_in(
+            u64::MAX,
+            PROTOCOL_FEE_RATE,
+            PLATFORM_FEE_RATE,
+            SHARE_FEE_RATE,
+            virtual_base,
+            virtual_quote,
+            real_base,
+            real_quote,
+        )
+        .is_none());
+        assert!(get_sell_amount_out(
+            1_000,
+            PROTOCOL_FEE_RATE,
+            PLATFORM_FEE_RATE,
+            SHARE_FEE_RATE,
+            real_base - 1,
+            virtual_quote,
+            real_base,
+            real_quote,
+        )
+        .is_err());
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{common::SolanaRpcClient, constants::bonk::accounts};
 use anyhow::anyhow;
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_sdk::pubkey::Pubkey;
 
 #[derive(Debug, Clone, BorshDeserialize)]
@@ -12,6 +12,41 @@ pub struct VestingSchedule {
     pub allocated_share_amount: u64,
 }
 
+/// `initialize` 指令的代币参数
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct MintParams {
+    pub decimals: u8,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// `initialize` 指令的联合曲线参数
+#[derive(Debug, Clone, BorshSerialize)]
+pub enum CurveParams {
+    Constant { supply: u64, total_base_sell: u64, total_quote_fund_raising: u64, migrate_type: u8 },
+    Fixed { supply: u64, total_quote_fund_raising: u64, migrate_type: u8 },
+    Linear { supply: u64, total_quote_fund_raising: u64, migrate_type: u8 },
+}
+
+/// `initialize` 指令的锁仓参数，不锁仓时全部为 0
+#[derive(Debug, Clone, Default, BorshSerialize)]
+pub struct VestingParams {
+    pub total_locked_amount: u64,
+    pub cliff_period: u64,
+    pub unlock_period: u64,
+}
+
+impl From<&VestingSchedule> for VestingParams {
+    fn from(schedule: &VestingSchedule) -> Self {
+        Self {
+            total_locked_amount: schedule.total_locked_amount,
+            cliff_period: schedule.cliff_period,
+            unlock_period: schedule.unlock_period,
+        }
+    }
+}
+
 #[derive(Debug, Clone, BorshDeserialize)]
 pub struct Pool {
     pub epoch: u64,
@@ -1,6 +1,7 @@
 pub mod address_lookup_manager;
 pub mod compute_budget_manager;
 pub mod nonce_manager;
+pub mod token_accounts;
 pub mod transaction_builder;
 pub mod utils;
 
@@ -8,5 +9,6 @@ pub mod utils;
 pub use address_lookup_manager::*;
 pub use compute_budget_manager::*;
 pub use nonce_manager::*;
+pub use token_accounts::*;
 pub use transaction_builder::*;
 pub use utils::*;
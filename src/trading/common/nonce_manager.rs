@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use solana_hash::Hash;
-use solana_sdk::{instruction::Instruction, signature::Keypair, signer::Signer};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 use solana_system_interface::instruction::advance_nonce_account;
 
 use crate::common::nonce_cache::NonceCache;
@@ -11,10 +11,10 @@ use crate::common::nonce_cache::NonceCache;
 /// 如果nonce被锁定、已使用或未准备好，将返回错误
 /// 成功时会锁定并标记nonce为已使用
 pub fn add_nonce_instruction(
+    nonce_cache: &NonceCache,
     instructions: &mut Vec<Instruction>,
-    payer: &Keypair,
+    payer: &Pubkey,
 ) -> Result<(), anyhow::Error> {
-    let nonce_cache = NonceCache::get_instance();
     let nonce_info = nonce_cache.get_nonce_info();
 
     // 只检查nonce_account是否存在
@@ -23,12 +23,7 @@ pub fn add_nonce_instruction(
         // if nonce_info.lock {
         //     return Err(anyhow!("Nonce is locked"));
         // }
-        if nonce_info.used {
-            return Err(anyhow!("Nonce is used"));
-        }
-        if nonce_info.current_nonce == Hash::default() {
-            return Err(anyhow!("Nonce is not ready"));
-        }
+        check_nonce_ready(nonce_cache)?;
         // if nonce_info.next_buy_time == 0 || chrono::Utc::now().timestamp() < nonce_info.next_buy_time {
         //     return Err(anyhow!("Nonce is not ready"));
         // }
@@ -36,7 +31,7 @@ pub fn add_nonce_instruction(
         // nonce_cache.lock();
 
         // 创建Solana系统nonce推进指令 - 使用系统程序ID
-        let nonce_advance_ix = advance_nonce_account(&nonce_pubkey, &payer.pubkey());
+        let nonce_advance_ix = advance_nonce_account(&nonce_pubkey, payer);
 
         instructions.push(nonce_advance_ix);
     }
@@ -44,10 +39,24 @@ pub fn add_nonce_instruction(
     Ok(())
 }
 
+/// 检查nonce是否可用，未配置nonce账户时直接返回成功
+pub fn check_nonce_ready(nonce_cache: &NonceCache) -> Result<(), anyhow::Error> {
+    let nonce_info = nonce_cache.get_nonce_info();
+    if nonce_info.nonce_account.is_none() {
+        return Ok(());
+    }
+    if nonce_info.used {
+        return Err(anyhow!("Nonce is used"));
+    }
+    if nonce_info.current_nonce == Hash::default() {
+        return Err(anyhow!("Nonce is not ready"));
+    }
+    Ok(())
+}
+
 /// 获取用于交易的blockhash
 /// 如果使用了nonce账户，返回nonce中的blockhash，否则返回传入的recent_blockhash
-pub fn get_transaction_blockhash(recent_blockhash: Hash) -> Hash {
-    let nonce_cache = NonceCache::get_instance();
+pub fn get_transaction_blockhash(nonce_cache: &NonceCache, recent_blockhash: Hash) -> Hash {
     let nonce_info = nonce_cache.get_nonce_info();
 
     if nonce_info.nonce_account.is_some() {
@@ -58,8 +67,7 @@ pub fn get_transaction_blockhash(recent_blockhash: Hash) -> Hash {
 }
 
 /// 检查是否使用nonce账户
-pub fn is_using_nonce() -> bool {
-    let nonce_cache = NonceCache::get_instance();
+pub fn is_using_nonce(nonce_cache: &NonceCache) -> bool {
     let nonce_info = nonce_cache.get_nonce_info();
     nonce_info.nonce_account.is_some()
 }
@@ -0,0 +1,107 @@
+use solana_sdk::{account::Account, instruction::Instruction, pubkey::Pubkey};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token::{instruction::close_account, native_mint};
+
+use crate::common::SolanaRpcClient;
+
+/// 用户在 `token_program` 下的关联代币账户
+pub fn user_token_account(user: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(user, mint, token_program)
+}
+
+/// 创建wSOL账户，转入 `amount` lamports 并同步余额
+pub fn wrap_wsol(user: &Pubkey, amount: u64) -> Vec<Instruction> {
+    let wsol_token_account = user_token_account(user, &native_mint::ID, &spl_token::ID);
+    vec![
+        create_associated_token_account_idempotent(user, user, &native_mint::ID, &spl_token::ID),
+        transfer(user, &wsol_token_account, amount),
+        spl_token::instruction::sync_native(&spl_token::ID, &wsol_token_account).unwrap(),
+    ]
+}
+
+/// 关闭wSOL账户，回收余额和租金
+pub fn close_wsol(user: &Pubkey) -> Instruction {
+    close_account(
+        &spl_token::ID,
+        &user_token_account(user, &native_mint::ID, &spl_token::ID),
+        user,
+        user,
+        &[],
+    )
+    .unwrap()
+}
+
+/// 分批读取账户，每次请求最多 100 个
+pub async fn get_multiple_accounts(
+    rpc: &SolanaRpcClient,
+    keys: &[Pubkey],
+) -> Result<Vec<Option<Account>>, anyhow::Error> {
+    let mut accounts = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(100) {
+        accounts.extend(rpc.get_multiple_accounts(chunk).await?);
+    }
+    Ok(accounts)
+}
+
+/// 读取 SPL Token / Token-2022 代币账户的余额，两者的 amount 都位于偏移 64
+pub fn token_account_amount(account: &Account) -> Option<u64> {
+    let bytes = account.data.get(64..72)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// 读取一组金库的余额，返回余额最大的金库下标，读取失败的金库按 0 计算
+///
+/// 用于在同一 mint 的多个 WSOL 池子中选出 WSOL 储备最深的一个，`vaults` 为空时返回 `None`。
+pub async fn find_deepest_vault(
+    rpc: &SolanaRpcClient,
+    vaults: &[Pubkey],
+) -> Result<Option<usize>, anyhow::Error> {
+    let accounts = get_multiple_accounts(rpc, vaults).await?;
+    Ok(deepest_index(
+        accounts.iter().map(|account| account.as_ref().and_then(token_account_amount).unwrap_or(0)),
+    ))
+}
+
+/// 余额最大的下标，相同余额时取靠后的一个
+fn deepest_index(amounts: impl Iterator<Item = u64>) -> Option<usize> {
+    amounts.enumerate().max_by_key(|(_, amount)| *amount).map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_account_amount() {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&42u64.to_le_bytes());
+        let account = Account { data, ..Default::default() };
+        assert_eq!(token_account_amount(&account), Some(42));
+        assert_eq!(token_account_amount(&Account::default()), None);
+    }
+
+    #[test]
+    fn test_deepest_index() {
+        assert_eq!(deepest_index([3, 9, 0].into_iter()), Some(1));
+        assert_eq!(deepest_index(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_wrap_and_close_wsol() {
+        let user = Pubkey::new_unique();
+        let wsol_token_account = user_token_account(&user, &native_mint::ID, &spl_token::ID);
+
+        let wrap = wrap_wsol(&user, 1_000);
+        assert_eq!(wrap.len(), 3);
+        assert_eq!(wrap[1].accounts[1].pubkey, wsol_token_account);
+        assert_eq!(wrap[2].accounts[0].pubkey, wsol_token_account);
+
+        let close = close_wsol(&user);
+        assert_eq!(close.accounts[0].pubkey, wsol_token_account);
+        assert!(close.accounts[2].is_signer);
+    }
+}
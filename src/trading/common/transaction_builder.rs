@@ -2,10 +2,7 @@ use solana_hash::Hash;
 use solana_sdk::{
     instruction::Instruction,
     message::{v0, VersionedMessage},
-    native_token::sol_str_to_lamports,
     pubkey::Pubkey,
-    signature::Keypair,
-    signer::Signer,
     transaction::VersionedTransaction,
 };
 use solana_system_interface::instruction::transfer;
@@ -19,27 +16,33 @@ use super::{
     nonce_manager::{add_nonce_instruction, get_transaction_blockhash},
 };
 use crate::{
-    common::PriorityFee,
+    common::{
+        signer::sign_versioned_message, AsyncTradeSigner, Lamports, PriorityFee, TradeContext,
+    },
     trading::common::{
         add_sell_compute_budget_instructions, add_sell_tip_compute_budget_instructions,
     },
 };
 
 /// 构建标准的RPC交易
+///
+/// `additional_signers` 是 payer 和手续费账户之外还需要签名的账户，例如新建代币的 mint。
 pub async fn build_rpc_transaction(
-    payer: Arc<Keypair>,
+    context: &TradeContext,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
+    additional_signers: &[Arc<dyn AsyncTradeSigner>],
 ) -> Result<VersionedTransaction, anyhow::Error> {
     let mut instructions = vec![];
 
     // 添加nonce指令
-    if let Err(e) = add_nonce_instruction(&mut instructions, payer.as_ref()) {
-        return Err(e);
-    }
+    let fee_payer_pubkey = get_fee_payer_pubkey(&payer, &fee_payer);
+    add_nonce_instruction(&context.nonce_cache, &mut instructions, &fee_payer_pubkey)?;
 
     // 添加计算预算指令
     add_rpc_compute_budget_instructions(&mut instructions, priority_fee, data_size_limit);
@@ -48,32 +51,43 @@ pub async fn build_rpc_transaction(
     instructions.extend(business_instructions);
 
     // 获取交易使用的blockhash
-    let blockhash = get_transaction_blockhash(recent_blockhash);
+    let blockhash = get_transaction_blockhash(&context.nonce_cache, recent_blockhash);
 
     // 获取地址查找表账户
-    let address_lookup_table_accounts = get_address_lookup_table_accounts(lookup_table_key).await;
+    let address_lookup_table_accounts =
+        get_address_lookup_table_accounts(&context.lookup_table_cache, lookup_table_key).await;
 
     // 构建交易
-    build_versioned_transaction(payer, instructions, address_lookup_table_accounts, blockhash).await
+    build_versioned_transaction(
+        payer,
+        fee_payer,
+        instructions,
+        address_lookup_table_accounts,
+        blockhash,
+        additional_signers,
+    )
+    .await
 }
 
 /// 构建带小费的交易
 pub async fn build_tip_transaction(
-    payer: Arc<Keypair>,
+    context: &TradeContext,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
-    tip_amount: f64,
+    tip_amount: Lamports,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
+    additional_signers: &[Arc<dyn AsyncTradeSigner>],
 ) -> Result<VersionedTransaction, anyhow::Error> {
     let mut instructions = vec![];
 
     // 添加nonce指令
-    if let Err(e) = add_nonce_instruction(&mut instructions, payer.as_ref()) {
-        return Err(e);
-    }
+    let fee_payer_pubkey = get_fee_payer_pubkey(&payer, &fee_payer);
+    add_nonce_instruction(&context.nonce_cache, &mut instructions, &fee_payer_pubkey)?;
 
     // 添加计算预算指令
     add_tip_compute_budget_instructions(&mut instructions, priority_fee, data_size_limit);
@@ -81,55 +95,83 @@ pub async fn build_tip_transaction(
     // 添加业务指令
     instructions.extend(business_instructions);
 
-    // 添加小费转账指令
+    // 添加小费转账指令，小费由手续费付款账户支付
     instructions.push(transfer(
-        &payer.pubkey(),
+        &get_fee_payer_pubkey(&payer, &fee_payer),
         tip_account,
-        sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
+        tip_amount.as_u64(),
     ));
 
     // 获取交易使用的blockhash
-    let blockhash = get_transaction_blockhash(recent_blockhash);
+    let blockhash = get_transaction_blockhash(&context.nonce_cache, recent_blockhash);
 
     // 获取地址查找表账户
-    let address_lookup_table_accounts = get_address_lookup_table_accounts(lookup_table_key).await;
+    let address_lookup_table_accounts =
+        get_address_lookup_table_accounts(&context.lookup_table_cache, lookup_table_key).await;
 
     // 构建交易
-    build_versioned_transaction(payer, instructions, address_lookup_table_accounts, blockhash).await
+    build_versioned_transaction(
+        payer,
+        fee_payer,
+        instructions,
+        address_lookup_table_accounts,
+        blockhash,
+        additional_signers,
+    )
+    .await
 }
 
 /// 构建版本化交易的底层函数
 async fn build_versioned_transaction(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: Vec<solana_sdk::message::AddressLookupTableAccount>,
     blockhash: Hash,
+    additional_signers: &[Arc<dyn AsyncTradeSigner>],
 ) -> Result<VersionedTransaction, anyhow::Error> {
     let v0_message: v0::Message = v0::Message::try_compile(
-        &payer.pubkey(),
+        &get_fee_payer_pubkey(&payer, &fee_payer),
         &instructions,
         &address_lookup_table_accounts,
         blockhash,
     )?;
 
-    let versioned_message: VersionedMessage = VersionedMessage::V0(v0_message.clone());
-    let transaction = VersionedTransaction::try_new(versioned_message, &[payer.as_ref()])?;
+    let versioned_message: VersionedMessage = VersionedMessage::V0(v0_message);
+    let mut signers: Vec<&dyn AsyncTradeSigner> = Vec::with_capacity(2 + additional_signers.len());
+    if let Some(fee_payer) = &fee_payer {
+        signers.push(fee_payer.as_ref());
+    }
+    signers.push(payer.as_ref());
+    signers.extend(additional_signers.iter().map(|signer| signer.as_ref()));
+    sign_versioned_message(versioned_message, &signers).await
+}
 
-    Ok(transaction)
+/// 获取支付手续费和小费的账户，未单独设置时由 payer 支付
+pub fn get_fee_payer_pubkey(
+    payer: &Arc<dyn AsyncTradeSigner>,
+    fee_payer: &Option<Arc<dyn AsyncTradeSigner>>,
+) -> Pubkey {
+    fee_payer.as_ref().unwrap_or(payer).pubkey()
 }
 
 /// 构建带小费的交易（使用PriorityFee中的tip_fee）
 pub async fn build_tip_transaction_with_priority_fee(
-    payer: Arc<Keypair>,
+    context: &TradeContext,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
+    additional_signers: &[Arc<dyn AsyncTradeSigner>],
 ) -> Result<VersionedTransaction, anyhow::Error> {
     build_tip_transaction(
+        context,
         payer,
+        fee_payer,
         priority_fee,
         business_instructions,
         tip_account,
@@ -137,13 +179,16 @@ pub async fn build_tip_transaction_with_priority_fee(
         lookup_table_key,
         recent_blockhash,
         data_size_limit,
+        additional_signers,
     )
     .await
 }
 
 /// 构建标准的RPC交易
 pub async fn build_sell_transaction(
-    payer: Arc<Keypair>,
+    context: &TradeContext,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     lookup_table_key: Option<Pubkey>,
@@ -158,24 +203,29 @@ pub async fn build_sell_transaction(
     instructions.extend(business_instructions);
 
     // 获取地址查找表账户
-    let address_lookup_table_accounts = get_address_lookup_table_accounts(lookup_table_key).await;
+    let address_lookup_table_accounts =
+        get_address_lookup_table_accounts(&context.lookup_table_cache, lookup_table_key).await;
 
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         recent_blockhash,
+        &[],
     )
     .await
 }
 
 pub async fn build_sell_tip_transaction(
-    payer: Arc<Keypair>,
+    context: &TradeContext,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
-    tip_amount: f64,
+    tip_amount: Lamports,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
 ) -> Result<VersionedTransaction, anyhow::Error> {
@@ -187,28 +237,33 @@ pub async fn build_sell_tip_transaction(
     // 添加业务指令
     instructions.extend(business_instructions);
 
-    // 添加小费转账指令
+    // 添加小费转账指令，小费由手续费付款账户支付
     instructions.push(transfer(
-        &payer.pubkey(),
+        &get_fee_payer_pubkey(&payer, &fee_payer),
         tip_account,
-        sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
+        tip_amount.as_u64(),
     ));
 
     // 获取地址查找表账户
-    let address_lookup_table_accounts = get_address_lookup_table_accounts(lookup_table_key).await;
+    let address_lookup_table_accounts =
+        get_address_lookup_table_accounts(&context.lookup_table_cache, lookup_table_key).await;
 
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         recent_blockhash,
+        &[],
     )
     .await
 }
 
 pub async fn build_sell_tip_transaction_with_priority_fee(
-    payer: Arc<Keypair>,
+    context: &TradeContext,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
@@ -216,7 +271,9 @@ pub async fn build_sell_tip_transaction_with_priority_fee(
     recent_blockhash: Hash,
 ) -> Result<VersionedTransaction, anyhow::Error> {
     build_sell_tip_transaction(
+        context,
         payer,
+        fee_payer,
         priority_fee,
         business_instructions,
         tip_account,
@@ -226,3 +283,41 @@ pub async fn build_sell_tip_transaction_with_priority_fee(
     )
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+
+    #[tokio::test]
+    async fn test_fee_payer_pays_fees_and_tip() {
+        let owner: Arc<dyn AsyncTradeSigner> = Arc::new(Keypair::new());
+        let fee_payer: Arc<dyn AsyncTradeSigner> = Arc::new(Keypair::new());
+        let tip_account = Pubkey::new_unique();
+
+        let transaction = build_tip_transaction(
+            &TradeContext::new(),
+            owner.clone(),
+            Some(fee_payer.clone()),
+            &PriorityFee::default(),
+            vec![transfer(&owner.pubkey(), &Pubkey::new_unique(), 1)],
+            &tip_account,
+            Lamports::new(1_000),
+            None,
+            Hash::new_unique(),
+            0,
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let account_keys = transaction.message.static_account_keys();
+        assert_eq!(account_keys[0], fee_payer.pubkey());
+        assert_eq!(transaction.signatures.len(), 2);
+        assert!(transaction.verify_with_results().into_iter().all(|ok| ok));
+
+        // 小费转账的付款方是手续费账户
+        let tip_instruction = transaction.message.instructions().last().unwrap();
+        assert_eq!(account_keys[tip_instruction.accounts[0] as usize], fee_payer.pubkey());
+    }
+}
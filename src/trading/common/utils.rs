@@ -1,9 +1,15 @@
-use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+};
 use solana_system_interface::instruction::transfer;
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
 use spl_token::instruction::close_account;
 
-use crate::common::SolanaRpcClient;
+use crate::common::{signer::sign_versioned_message, AsyncTradeSigner, SolanaRpcClient};
 use anyhow::anyhow;
 
 #[inline]
@@ -12,13 +18,36 @@ pub async fn get_token_balance(
     payer: &Pubkey,
     mint: &Pubkey,
 ) -> Result<u64, anyhow::Error> {
-    let ata = get_associated_token_address(payer, mint);
+    get_token_balance_with_program(rpc, payer, mint, &spl_token::ID).await
+}
+
+/// 读取 `token_program` 下关联代币账户的余额，Token-2022 代币需要传 Token-2022 program
+#[inline]
+pub async fn get_token_balance_with_program(
+    rpc: &SolanaRpcClient,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let ata = get_associated_token_address_with_program_id(payer, mint, token_program);
     let balance = rpc.get_token_account_balance(&ata).await?;
     let balance_u64 =
         balance.amount.parse::<u64>().map_err(|_| anyhow!("Failed to parse token balance"))?;
     Ok(balance_u64)
 }
 
+/// 读取 mint 账户的 owner，即代币使用的 SPL Token 或 Token-2022 program
+pub async fn get_mint_token_program(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<Pubkey, anyhow::Error> {
+    let owner = rpc.get_account(mint).await?.owner;
+    if owner != spl_token::ID && owner != spl_token_2022::ID {
+        return Err(anyhow!("Mint {} is not owned by a token program", mint));
+    }
+    Ok(owner)
+}
+
 #[inline]
 pub async fn get_sol_balance(
     rpc: &SolanaRpcClient,
@@ -46,7 +75,7 @@ pub fn calculate_with_slippage_sell(amount: u64, basis_points: u64) -> u64 {
 
 pub async fn transfer_sol(
     rpc: &SolanaRpcClient,
-    payer: &Keypair,
+    payer: &dyn AsyncTradeSigner,
     receive_wallet: &Pubkey,
     amount: u64,
 ) -> Result<(), anyhow::Error> {
@@ -63,12 +92,12 @@ pub async fn transfer_sol(
 
     let recent_blockhash = rpc.get_latest_blockhash().await?;
 
-    let transaction = Transaction::new_signed_with_payer(
+    let message = Message::new_with_blockhash(
         &[transfer_instruction],
         Some(&payer.pubkey()),
-        &[payer],
-        recent_blockhash,
+        &recent_blockhash,
     );
+    let transaction = sign_versioned_message(VersionedMessage::Legacy(message), &[payer]).await?;
 
     rpc.send_and_confirm_transaction(&transaction).await?;
 
@@ -90,7 +119,7 @@ pub async fn transfer_sol(
 /// 返回一个Result，成功时返回()，失败时返回错误
 pub async fn close_token_account(
     rpc: &SolanaRpcClient,
-    payer: &Keypair,
+    payer: &dyn AsyncTradeSigner,
     mint: &Pubkey,
 ) -> Result<(), anyhow::Error> {
     // 获取关联代币账户地址
@@ -108,15 +137,75 @@ pub async fn close_token_account(
 
     // 构建交易
     let recent_blockhash = rpc.get_latest_blockhash().await?;
-    let transaction = Transaction::new_signed_with_payer(
-        &[close_account_ix],
-        Some(&payer.pubkey()),
-        &[payer],
-        recent_blockhash,
-    );
+    let message =
+        Message::new_with_blockhash(&[close_account_ix], Some(&payer.pubkey()), &recent_blockhash);
+    let transaction = sign_versioned_message(VersionedMessage::Legacy(message), &[payer]).await?;
 
     // 发送交易
     rpc.send_and_confirm_transaction(&transaction).await?;
 
     Ok(())
 }
+
+/// 转出全部代币并关闭代币账户
+///
+/// 代币 program 从 mint 账户的 owner 读取，支持 SPL Token 和 Token-2022。
+/// 接收方的关联代币账户不存在时由 `owner` 创建，关闭后的租金退回 `owner`。
+///
+/// # 返回值
+///
+/// 返回转出的代币数量，没有代币账户时返回 0
+pub async fn transfer_all_tokens(
+    rpc: &SolanaRpcClient,
+    owner: &dyn AsyncTradeSigner,
+    mint: &Pubkey,
+    receive_wallet: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let owner_pubkey = owner.pubkey();
+    let token_program = get_mint_token_program(rpc, mint).await?;
+    let source = get_associated_token_address_with_program_id(&owner_pubkey, mint, &token_program);
+    if rpc.get_account_with_commitment(&source, rpc.commitment()).await?.value.is_none() {
+        return Ok(0);
+    }
+    let balance = rpc.get_token_account_balance(&source).await?;
+    let amount =
+        balance.amount.parse::<u64>().map_err(|_| anyhow!("Failed to parse token balance"))?;
+
+    let mut instructions = vec![];
+    if amount > 0 {
+        let destination =
+            get_associated_token_address_with_program_id(receive_wallet, mint, &token_program);
+        instructions.push(create_associated_token_account_idempotent(
+            &owner_pubkey,
+            receive_wallet,
+            mint,
+            &token_program,
+        ));
+        instructions.push(spl_token_2022::instruction::transfer_checked(
+            &token_program,
+            &source,
+            mint,
+            &destination,
+            &owner_pubkey,
+            &[&owner_pubkey],
+            amount,
+            balance.decimals,
+        )?);
+    }
+    instructions.push(spl_token_2022::instruction::close_account(
+        &token_program,
+        &source,
+        &owner_pubkey,
+        &owner_pubkey,
+        &[&owner_pubkey],
+    )?);
+
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+    let message =
+        Message::new_with_blockhash(&instructions, Some(&owner_pubkey), &recent_blockhash);
+    let transaction = sign_versioned_message(VersionedMessage::Legacy(message), &[owner]).await?;
+
+    rpc.send_and_confirm_transaction(&transaction).await?;
+
+    Ok(amount)
+}
@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use futures::future::join_all;
+use solana_sdk::{
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+};
+use solana_system_interface::instruction::transfer;
+use std::{str::FromStr, sync::Arc};
+
+use super::{executor::MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT, params::BuyParams, timer::TradeTimer};
+use crate::{
+    common::signer::sign_versioned_message,
+    swqos::{common::poll_transaction_confirmation, SwqosClient, SwqosType, TradeType},
+    trading::{
+        common::{
+            add_tip_compute_budget_instructions, get_address_lookup_table_accounts,
+            get_fee_payer_pubkey, is_using_nonce,
+        },
+        factory::{DexType, TradeFactory},
+    },
+};
+
+/// Jito 单个 bundle 最多包含的交易数量
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// bundle 中单个钱包的买入结果
+#[derive(Debug)]
+pub struct BundleBuyResult {
+    pub wallet: Pubkey,
+    pub signature: Signature,
+    /// bundle 整体上链或整体丢弃，失败时所有钱包都会返回错误
+    pub result: Result<()>,
+}
+
+/// 多个钱包在同一个 Jito bundle 中原子买入同一代币
+///
+/// 每个钱包一笔交易，只在最后一笔交易中附带小费。bundle 内的交易按顺序执行，
+/// 后面的钱包会以更高的价格成交，滑点需要覆盖前面钱包造成的价格变化。
+/// bundle 中的多笔交易无法共用同一个 nonce，配置了 nonce 账户时返回错误。
+pub async fn bundle_buy(
+    dex_type: DexType,
+    params: Vec<BuyParams>,
+    jito_client: Arc<SwqosClient>,
+) -> Result<Vec<BundleBuyResult>> {
+    check_bundle_size(params.len())?;
+    if jito_client.get_swqos_type() != SwqosType::Jito {
+        return Err(anyhow!("Bundles can only be sent through Jito"));
+    }
+    if params.iter().any(|params| is_using_nonce(&params.context.nonce_cache)) {
+        return Err(anyhow!("Bundles do not support durable nonce"));
+    }
+    let rpc = params[0].rpc.clone().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+    let mut timer = TradeTimer::new("构建bundle交易");
+    let builder = TradeFactory::create_instruction_builder(dex_type);
+    let tip_account = Pubkey::from_str(&jito_client.get_tip_account()?)?;
+    let last_index = params.len() - 1;
+
+    let mut wallets = Vec::with_capacity(params.len());
+    let mut transactions = Vec::with_capacity(params.len());
+    for (index, mut params) in params.into_iter().enumerate() {
+        if params.data_size_limit == 0 {
+            params.data_size_limit = MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
+        }
+        let fee_payer_pubkey = get_fee_payer_pubkey(&params.payer, &params.fee_payer);
+
+        let mut instructions = vec![];
+        add_tip_compute_budget_instructions(
+            &mut instructions,
+            &params.priority_fee,
+            params.data_size_limit,
+        );
+        instructions.extend(builder.build_buy_instructions(&params).await?);
+        if index == last_index {
+            let tip_fee = params.priority_fee.buy_tip_fee.max(jito_client.get_min_tip());
+            instructions.push(transfer(&fee_payer_pubkey, &tip_account, tip_fee.as_u64()));
+        }
+
+        let address_lookup_table_accounts = get_address_lookup_table_accounts(
+            &params.context.lookup_table_cache,
+            params.lookup_table_key,
+        )
+        .await;
+        let message = VersionedMessage::V0(v0::Message::try_compile(
+            &fee_payer_pubkey,
+            &instructions,
+            &address_lookup_table_accounts,
+            params.recent_blockhash,
+        )?);
+        let transaction = match &params.fee_payer {
+            Some(fee_payer) => {
+                sign_versioned_message(message, &[fee_payer.as_ref(), params.payer.as_ref()])
+                    .await?
+            }
+            None => sign_versioned_message(message, &[params.payer.as_ref()]).await?,
+        };
+
+        wallets.push(params.payer.pubkey());
+        transactions.push(transaction);
+    }
+    timer.stage("提交bundle交易");
+
+    if !jito_client.acquire_send_permit().await {
+        return Err(anyhow!("Jito is rate limited, bundle not sent"));
+    }
+    let send_result = jito_client.send_transactions(TradeType::Buy, &transactions).await;
+    timer.stage("确认bundle交易");
+
+    let signatures: Vec<Signature> = transactions.iter().map(|tx| tx.signatures[0]).collect();
+    let results: Vec<Result<()>> = match send_result {
+        Ok(()) => {
+            let confirmations = join_all(
+                signatures.iter().map(|signature| poll_transaction_confirmation(&rpc, *signature)),
+            )
+            .await;
+            confirmations.into_iter().map(|confirmation| confirmation.map(|_| ())).collect()
+        }
+        Err(e) => {
+            let error = e.to_string();
+            signatures.iter().map(|_| Err(anyhow!("Bundle submission failed: {}", error))).collect()
+        }
+    };
+    timer.finish();
+
+    Ok(wallets
+        .into_iter()
+        .zip(signatures)
+        .zip(results)
+        .map(|((wallet, signature), result)| BundleBuyResult { wallet, signature, result })
+        .collect())
+}
+
+/// 检查 bundle 中的钱包数量
+fn check_bundle_size(len: usize) -> Result<()> {
+    if len == 0 || len > MAX_BUNDLE_TRANSACTIONS {
+        return Err(anyhow!(
+            "Bundle must contain 1 to {} wallets, got {}",
+            MAX_BUNDLE_TRANSACTIONS,
+            len
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_bundle_size() {
+        assert!(check_bundle_size(0).is_err());
+        assert!(check_bundle_size(1).is_ok());
+        assert!(check_bundle_size(MAX_BUNDLE_TRANSACTIONS).is_ok());
+        assert!(check_bundle_size(MAX_BUNDLE_TRANSACTIONS + 1).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use super::{
     parallel::parallel_execute_with_tips,
-    params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams},
+    params::{BuyParams, BuyWithTipParams, CreateParams, SellParams, SellWithTipParams},
     timer::TradeTimer,
     traits::{InstructionBuilder, TradeExecutor},
 };
@@ -12,7 +12,7 @@ use crate::{
     trading::common::{build_rpc_transaction, build_sell_transaction},
 };
 
-const MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT: u32 = 256 * 1024;
+pub(crate) const MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT: u32 = 256 * 1024;
 
 /// 通用交易执行器实现
 pub struct GenericTradeExecutor {
@@ -52,12 +52,15 @@ impl TradeExecutor for GenericTradeExecutor {
 
         // 构建交易
         let transaction = build_rpc_transaction(
+            &params.context,
             params.payer.clone(),
+            params.fee_payer.clone(),
             &params.priority_fee,
             instructions,
             params.lookup_table_key,
             params.recent_blockhash,
             params.data_size_limit,
+            &[],
         )
         .await?;
         timer.stage("rpc提交确认");
@@ -79,15 +82,18 @@ impl TradeExecutor for GenericTradeExecutor {
         let buy_params = BuyParams {
             rpc: params.rpc,
             payer: params.payer.clone(),
+            fee_payer: params.fee_payer.clone(),
             mint: params.mint,
             creator: params.creator,
             sol_amount: params.sol_amount,
+            swap_mode: params.swap_mode,
             slippage_basis_points: params.slippage_basis_points,
             priority_fee: params.priority_fee.clone(),
             lookup_table_key: params.lookup_table_key,
             recent_blockhash: params.recent_blockhash,
             data_size_limit: params.data_size_limit,
             protocol_params: params.protocol_params.clone(),
+            context: params.context.clone(),
         };
 
         // 构建指令
@@ -100,14 +106,17 @@ impl TradeExecutor for GenericTradeExecutor {
 
         // 并行执行交易
         parallel_execute_with_tips(
+            params.context,
             params.swqos_clients,
             params.payer,
+            params.fee_payer,
             instructions,
             params.priority_fee,
             params.lookup_table_key,
             params.recent_blockhash,
             params.data_size_limit,
             TradeType::Buy,
+            vec![],
         )
         .await?;
 
@@ -130,7 +139,9 @@ impl TradeExecutor for GenericTradeExecutor {
 
         // 构建交易
         let transaction = build_sell_transaction(
+            &params.context,
             params.payer.clone(),
+            params.fee_payer.clone(),
             &params.priority_fee,
             instructions,
             params.lookup_table_key,
@@ -153,14 +164,17 @@ impl TradeExecutor for GenericTradeExecutor {
         let sell_params = SellParams {
             rpc: params.rpc,
             payer: params.payer.clone(),
+            fee_payer: params.fee_payer.clone(),
             mint: params.mint,
             creator: params.creator,
             token_amount: params.token_amount,
+            swap_mode: params.swap_mode,
             slippage_basis_points: params.slippage_basis_points,
             priority_fee: params.priority_fee.clone(),
             lookup_table_key: params.lookup_table_key,
             recent_blockhash: params.recent_blockhash,
             protocol_params: params.protocol_params.clone(),
+            context: params.context.clone(),
         };
 
         // 构建指令
@@ -173,14 +187,53 @@ impl TradeExecutor for GenericTradeExecutor {
 
         // 并行执行交易
         parallel_execute_with_tips(
+            params.context,
             params.swqos_clients,
             params.payer,
+            params.fee_payer,
             instructions,
             params.priority_fee,
             params.lookup_table_key,
             params.recent_blockhash,
             0,
             TradeType::Sell,
+            vec![],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create(&self, mut params: CreateParams) -> Result<()> {
+        if params.data_size_limit == 0 {
+            params.data_size_limit = MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
+        }
+        let timer = TradeTimer::new("构建创建交易指令");
+
+        // 构建指令
+        let instructions = self.instruction_builder.build_create_instructions(&params).await?;
+
+        timer.finish();
+
+        let trade_type = if params.dev_buy_sol_amount.is_zero() {
+            TradeType::Create
+        } else {
+            TradeType::CreateAndBuy
+        };
+
+        // 并行执行交易，mint 账户需要一起签名
+        parallel_execute_with_tips(
+            params.context,
+            params.swqos_clients,
+            params.payer,
+            params.fee_payer,
+            instructions,
+            params.priority_fee,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            params.data_size_limit,
+            trade_type,
+            vec![params.mint],
         )
         .await?;
 
@@ -1,5 +1,7 @@
+pub mod bundle;
 pub mod executor;
 pub mod parallel;
 pub mod params;
+pub mod template;
 pub mod timer;
 pub mod traits;
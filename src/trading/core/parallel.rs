@@ -1,12 +1,12 @@
 use anyhow::{anyhow, Result};
 use solana_hash::Hash;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 use std::{str::FromStr, sync::Arc};
 use tokio::task::JoinHandle;
 
 use crate::{
-    common::PriorityFee,
-    swqos::{SwqosClient, SwqosType, TradeType},
+    common::{AsyncTradeSigner, PriorityFee, TradeContext},
+    swqos::{SwqosClient, TradeType},
     trading::common::{
         build_rpc_transaction, build_sell_tip_transaction_with_priority_fee,
         build_sell_transaction, build_tip_transaction_with_priority_fee,
@@ -15,50 +15,63 @@ use crate::{
 };
 
 /// 并行执行交易的通用函数
+///
+/// `additional_signers` 会与 payer 一起签名，只用于买入和创建交易。
 pub async fn parallel_execute_with_tips(
+    context: TradeContext,
     swqos_clients: Vec<Arc<SwqosClient>>,
-    payer: Arc<Keypair>,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     instructions: Vec<Instruction>,
     priority_fee: PriorityFee,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
     trade_type: TradeType,
+    additional_signers: Vec<Arc<dyn AsyncTradeSigner>>,
 ) -> Result<()> {
-    let cores = core_affinity::get_core_ids().unwrap();
     let mut handles: Vec<JoinHandle<Result<()>>> = vec![];
 
-    for i in 0..swqos_clients.len() {
-        let swqos_client = swqos_clients[i].clone();
+    for swqos_client in swqos_clients {
+        let context = context.clone();
         let payer = payer.clone();
+        let fee_payer = fee_payer.clone();
         let instructions = instructions.clone();
-        let mut priority_fee = priority_fee.clone();
-        let core_id = cores[i % cores.len()];
+        let additional_signers = additional_signers.clone();
+        let mut priority_fee = priority_fee.for_swqos(swqos_client.get_swqos_type());
 
         let handle = tokio::spawn(async move {
-            core_affinity::set_for_current(core_id);
+            // 服务商已达到限流上限时跳过，避免发出必然被拒绝的请求
+            if !swqos_client.acquire_send_permit().await {
+                println!(" {:?}{}已限流，跳过", swqos_client.get_swqos_type(), trade_type);
+                return Ok(());
+            }
 
             let mut timer =
                 TradeTimer::new(format!("构建交易指令: {:?}", swqos_client.get_swqos_type()));
 
             let transaction = if matches!(trade_type, TradeType::Sell)
-                && swqos_client.get_swqos_type() == SwqosType::Default
+                && !swqos_client.get_swqos_type().need_tip()
             {
                 build_sell_transaction(
+                    &context,
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     lookup_table_key,
                     recent_blockhash,
                 )
                 .await?
-            } else if matches!(trade_type, TradeType::Sell)
-                && swqos_client.get_swqos_type() != SwqosType::Default
-            {
+            } else if matches!(trade_type, TradeType::Sell) {
                 let tip_account = swqos_client.get_tip_account()?;
                 let tip_account = Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
+                priority_fee.sell_tip_fee =
+                    priority_fee.sell_tip_fee.max(swqos_client.get_min_tip());
                 build_sell_tip_transaction_with_priority_fee(
+                    &context,
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     &tip_account,
@@ -66,29 +79,37 @@ pub async fn parallel_execute_with_tips(
                     recent_blockhash,
                 )
                 .await?
-            } else if swqos_client.get_swqos_type() == SwqosType::Default {
+            } else if !swqos_client.get_swqos_type().need_tip() {
                 build_rpc_transaction(
+                    &context,
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     lookup_table_key,
                     recent_blockhash,
                     data_size_limit,
+                    &additional_signers,
                 )
                 .await?
             } else {
                 let tip_account = swqos_client.get_tip_account()?;
                 let tip_account = Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
-                priority_fee.buy_tip_fee = priority_fee.buy_tip_fees[i];
+                // 小费低于服务商要求的最低值时交易会被拒绝，这里统一抬高
+                priority_fee.buy_tip_fee =
+                    priority_fee.buy_tip_fee.max(swqos_client.get_min_tip());
 
                 build_tip_transaction_with_priority_fee(
+                    &context,
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     &tip_account,
                     lookup_table_key,
                     recent_blockhash,
                     data_size_limit,
+                    &additional_signers,
                 )
                 .await?
             };
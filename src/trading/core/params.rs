@@ -1,30 +1,51 @@
 use solana_hash::Hash;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 
 use super::traits::ProtocolParams;
 use crate::common::bonding_curve::BondingCurveAccount;
-use crate::common::{PriorityFee, SolanaRpcClient};
-use crate::constants::bonk::accounts::{PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE};
+use crate::common::{AsyncTradeSigner, Lamports, PriorityFee, SolanaRpcClient, TradeContext};
+use crate::constants::bonk::accounts::{
+    PLATFORM_CONFIG, PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE,
+};
+use crate::constants::bonk::launch;
 use crate::solana_streamer_sdk::streaming::event_parser::common::EventType;
 use crate::solana_streamer_sdk::streaming::event_parser::protocols::bonk::BonkTradeEvent;
 use crate::swqos::SwqosClient;
 use crate::trading::bonk::common::{get_amount_in, get_amount_in_net, get_amount_out};
+use crate::trading::bonk::pool::{CurveParams, VestingParams};
+use crate::trading::raydium_amm_v4::pool::PoolKeys;
+
+/// 交易数量的计算方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SwapMode {
+    /// 固定输入：买入花费 `sol_amount`，卖出卖掉 `token_amount`，输出数量按滑点保护
+    #[default]
+    ExactIn,
+    /// 固定输出：买入时得到指定数量的代币，卖出时得到指定数量的SOL（lamports）。
+    /// 此时 `sol_amount`/`token_amount` 是愿意付出的上限
+    ExactOut(u64),
+}
 
 /// 通用买入参数
 #[derive(Clone)]
 pub struct BuyParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn AsyncTradeSigner>,
+    /// 支付手续费和小费的账户，为空时由 payer 支付
+    pub fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
-    pub sol_amount: u64,
+    pub sol_amount: Lamports,
+    pub swap_mode: SwapMode,
     pub slippage_basis_points: Option<u64>,
     pub priority_fee: PriorityFee,
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
     pub data_size_limit: u32,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// 所属交易实例的 nonce、地址查找表等运行时状态
+    pub context: TradeContext,
 }
 
 /// 带MEV服务的买入参数
@@ -32,31 +53,41 @@ pub struct BuyParams {
 pub struct BuyWithTipParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn AsyncTradeSigner>,
+    /// 支付手续费和小费的账户，为空时由 payer 支付
+    pub fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
-    pub sol_amount: u64,
+    pub sol_amount: Lamports,
+    pub swap_mode: SwapMode,
     pub slippage_basis_points: Option<u64>,
     pub priority_fee: PriorityFee,
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
     pub data_size_limit: u32,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// 所属交易实例的 nonce、地址查找表等运行时状态
+    pub context: TradeContext,
 }
 
 /// 通用卖出参数
 #[derive(Clone)]
 pub struct SellParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn AsyncTradeSigner>,
+    /// 支付手续费和小费的账户，为空时由 payer 支付
+    pub fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub token_amount: Option<u64>,
+    pub swap_mode: SwapMode,
     pub slippage_basis_points: Option<u64>,
     pub priority_fee: PriorityFee,
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// 所属交易实例的 nonce、地址查找表等运行时状态
+    pub context: TradeContext,
 }
 
 /// 带MEV服务的卖出参数
@@ -64,15 +95,53 @@ pub struct SellParams {
 pub struct SellWithTipParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn AsyncTradeSigner>,
+    /// 支付手续费和小费的账户，为空时由 payer 支付
+    pub fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub token_amount: Option<u64>,
+    pub swap_mode: SwapMode,
     pub slippage_basis_points: Option<u64>,
     pub priority_fee: PriorityFee,
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// 所属交易实例的 nonce、地址查找表等运行时状态
+    pub context: TradeContext,
+}
+
+/// 新代币的名称、符号和元数据地址
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    /// 指向 Metaplex 格式 JSON 的地址
+    pub uri: String,
+}
+
+/// 创建代币参数
+#[derive(Clone)]
+pub struct CreateParams {
+    pub swqos_clients: Vec<Arc<SwqosClient>>,
+    pub payer: Arc<dyn AsyncTradeSigner>,
+    /// 支付手续费和小费的账户，为空时由 payer 支付
+    pub fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
+    /// 新代币的 mint 账户，需要参与签名
+    pub mint: Arc<dyn AsyncTradeSigner>,
+    pub metadata: TokenMetadata,
+    pub creator: Pubkey,
+    /// 同一笔交易中开发者买入的SOL数量，为零时只创建不买入
+    pub dev_buy_sol_amount: Lamports,
+    pub slippage_basis_points: Option<u64>,
+    pub priority_fee: PriorityFee,
+    pub lookup_table_key: Option<Pubkey>,
+    pub recent_blockhash: Hash,
+    pub data_size_limit: u32,
+    /// 协议特定的创建参数，例如 `BonkCreateParams`
+    pub protocol_params: Box<dyn ProtocolParams>,
+    /// 所属交易实例的 nonce、地址查找表等运行时状态
+    pub context: TradeContext,
 }
 
 /// PumpFun协议特定参数
@@ -247,10 +316,48 @@ impl ProtocolParams for BonkParams {
     }
 }
 
+/// Bonk 创建代币参数，默认值与 letsbonk 发行的代币一致
+#[derive(Clone)]
+pub struct BonkCreateParams {
+    pub decimals: u8,
+    pub curve: CurveParams,
+    pub vesting: VestingParams,
+    pub platform_config: Pubkey,
+    pub auto_handle_wsol: bool,
+}
+
+impl BonkCreateParams {
+    pub fn default() -> Self {
+        Self {
+            decimals: launch::DEFAULT_DECIMALS,
+            curve: CurveParams::Constant {
+                supply: launch::DEFAULT_SUPPLY,
+                total_base_sell: launch::DEFAULT_TOTAL_BASE_SELL,
+                total_quote_fund_raising: launch::DEFAULT_TOTAL_QUOTE_FUND_RAISING,
+                migrate_type: launch::DEFAULT_MIGRATE_TYPE,
+            },
+            vesting: VestingParams::default(),
+            platform_config: PLATFORM_CONFIG,
+            auto_handle_wsol: true,
+        }
+    }
+}
+
+impl ProtocolParams for BonkCreateParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
 /// RaydiumCpmm协议特定参数
 #[derive(Clone)]
 pub struct RaydiumCpmmParams {
     /// 池子状态账户地址
+    /// 不传且未指定 amm_config 时，查找该 mint 储备最深的 WSOL 池子
     pub pool_state: Option<Pubkey>,
     /// 代币程序ID
     /// 指定代币使用的程序，通常为 spl_token::ID 或 spl_token_2022::ID
@@ -260,6 +367,9 @@ pub struct RaydiumCpmmParams {
     pub mint_token_in_pool_state_index: Option<usize>,
     pub minimum_amount_out: Option<u64>,
     pub auto_handle_wsol: bool,
+    /// 池子所属的 AmmConfig（手续费档位）
+    /// 不传时从链上池子读取，只提供 amm_config 时用它推导池子地址
+    pub amm_config: Option<Pubkey>,
 }
 
 impl RaydiumCpmmParams {
@@ -270,6 +380,7 @@ impl RaydiumCpmmParams {
             mint_token_in_pool_state_index: Some(1),
             minimum_amount_out: None,
             auto_handle_wsol: true,
+            amm_config: None,
         }
     }
 }
@@ -284,6 +395,64 @@ impl ProtocolParams for RaydiumCpmmParams {
     }
 }
 
+/// RaydiumAmmV4协议特定参数
+#[derive(Clone)]
+pub struct RaydiumAmmV4Params {
+    /// AMM 池子地址
+    /// 不传时查找该 mint 储备最深的 WSOL 池子
+    pub amm: Option<Pubkey>,
+    /// 池子和 OpenBook 市场账户
+    /// 不传时通过 RPC 读取，传入可以省去两次账户查询
+    pub pool_keys: Option<PoolKeys>,
+    /// 池子储备 (coin_reserves, pc_reserves)，不传时通过 RPC 读取
+    pub reserves: Option<(u64, u64)>,
+    pub auto_handle_wsol: bool,
+}
+
+impl RaydiumAmmV4Params {
+    pub fn default() -> Self {
+        Self { amm: None, pool_keys: None, reserves: None, auto_handle_wsol: true }
+    }
+}
+
+impl ProtocolParams for RaydiumAmmV4Params {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
+/// RaydiumClmm协议特定参数
+#[derive(Clone)]
+pub struct RaydiumClmmParams {
+    /// 池子状态账户地址
+    /// 不传时查找该 mint 的 WSOL 金库储备最深的池子
+    pub pool_state: Option<Pubkey>,
+    /// 代币程序ID
+    /// 指定代币使用的程序，通常为 spl_token::ID 或 spl_token_2022::ID，不传时读取 mint 账户的 owner
+    pub mint_token_program: Option<Pubkey>,
+    pub auto_handle_wsol: bool,
+}
+
+impl RaydiumClmmParams {
+    pub fn default() -> Self {
+        Self { pool_state: None, mint_token_program: None, auto_handle_wsol: true }
+    }
+}
+
+impl ProtocolParams for RaydiumClmmParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
 impl BuyParams {
     /// 转换为BuyWithTipParams
     pub fn with_tip(self, swqos_clients: Vec<Arc<SwqosClient>>) -> BuyWithTipParams {
@@ -291,15 +460,18 @@ impl BuyParams {
             rpc: self.rpc,
             swqos_clients,
             payer: self.payer,
+            fee_payer: self.fee_payer,
             mint: self.mint,
             creator: self.creator,
             sol_amount: self.sol_amount,
+            swap_mode: self.swap_mode,
             slippage_basis_points: self.slippage_basis_points,
             priority_fee: self.priority_fee,
             lookup_table_key: self.lookup_table_key,
             recent_blockhash: self.recent_blockhash,
             data_size_limit: self.data_size_limit,
             protocol_params: self.protocol_params,
+            context: self.context,
         }
     }
 }
@@ -311,14 +483,17 @@ impl SellParams {
             rpc: self.rpc,
             swqos_clients,
             payer: self.payer,
+            fee_payer: self.fee_payer,
             mint: self.mint,
             creator: self.creator,
             token_amount: self.token_amount,
+            swap_mode: self.swap_mode,
             slippage_basis_points: self.slippage_basis_points,
             priority_fee: self.priority_fee,
             lookup_table_key: self.lookup_table_key,
             recent_blockhash: self.recent_blockhash,
             protocol_params: self.protocol_params,
+            context: self.context,
         }
     }
 }
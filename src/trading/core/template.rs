@@ -0,0 +1,428 @@
+use anyhow::{anyhow, Result};
+use futures::future::try_join_all;
+use solana_hash::Hash;
+use solana_sdk::{
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use solana_system_interface::instruction::{advance_nonce_account, transfer};
+use std::{str::FromStr, sync::Arc};
+use tokio::task::JoinHandle;
+
+use super::{executor::MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT, params::BuyParams, timer::TradeTimer};
+use crate::{
+    common::{
+        bonding_curve::BondingCurveAccount, signer::sign_versioned_message, AsyncTradeSigner,
+        Lamports, TradeContext,
+    },
+    constants::{self, trade::trade::DEFAULT_SLIPPAGE},
+    instruction::pumpfun::get_buy_amounts,
+    swqos::{SwqosClient, TradeType},
+    trading::{
+        bonk::common::get_amount_out,
+        common::{
+            add_rpc_compute_budget_instructions, add_tip_compute_budget_instructions,
+            check_nonce_ready, get_address_lookup_table_accounts, get_fee_payer_pubkey,
+            get_transaction_blockhash,
+        },
+        core::params::BonkParams,
+        factory::{DexType, TradeFactory},
+    },
+};
+
+/// 系统程序转账指令的类型标识
+const SYSTEM_TRANSFER_TAG: [u8; 4] = [2, 0, 0, 0];
+
+/// 模板触发时需要填入的金额
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateAmounts {
+    /// 花费的SOL数量，用于WSOL包装转账
+    pub sol_amount: Lamports,
+    /// 买入指令的两个金额参数，按协议指令布局依次写入
+    ///
+    /// - PumpFun: `[token_amount, max_sol_cost]`
+    /// - Bonk: `[amount_in, minimum_amount_out]`
+    pub instruction_args: [u64; 2],
+}
+
+impl TemplateAmounts {
+    /// 根据联合曲线计算PumpFun买入金额
+    pub fn pumpfun(
+        bonding_curve: &BondingCurveAccount,
+        sol_amount: Lamports,
+        slippage_basis_points: Option<u64>,
+    ) -> Self {
+        let (token_amount, max_sol_cost) =
+            get_buy_amounts(bonding_curve, sol_amount.as_u64(), slippage_basis_points);
+        Self { sol_amount, instruction_args: [token_amount, max_sol_cost] }
+    }
+
+    /// 根据池子储备计算Bonk买入金额，`params` 中的储备必须齐全
+    pub fn bonk(
+        params: &BonkParams,
+        sol_amount: Lamports,
+        slippage_basis_points: Option<u64>,
+    ) -> Result<Self> {
+        let (Some(virtual_base), Some(virtual_quote), Some(real_base), Some(real_quote)) =
+            (params.virtual_base, params.virtual_quote, params.real_base, params.real_quote)
+        else {
+            return Err(anyhow!("Bonk pool reserves are required"));
+        };
+        let minimum_amount_out = get_amount_out(
+            sol_amount.as_u64(),
+            constants::bonk::accounts::PROTOCOL_FEE_RATE,
+            constants::bonk::accounts::PLATFORM_FEE_RATE,
+            constants::bonk::accounts::SHARE_FEE_RATE,
+            virtual_base,
+            virtual_quote,
+            real_base,
+            real_quote,
+            slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+        );
+        Ok(Self { sol_amount, instruction_args: [sol_amount.as_u64(), minimum_amount_out] })
+    }
+}
+
+/// 触发时写入的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatchSource {
+    SolAmount,
+    InstructionArg(usize),
+}
+
+/// 已编译消息中需要写入金额的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PatchSlot {
+    instruction_index: usize,
+    offset: usize,
+    source: PatchSource,
+}
+
+/// 在业务指令中定位买入指令参数和WSOL包装转账金额
+fn find_patch_slots(dex_type: &DexType, instructions: &[Instruction]) -> Result<Vec<PatchSlot>> {
+    let (program_id, discriminator) = match dex_type {
+        DexType::PumpFun => {
+            (constants::pumpfun::accounts::PUMPFUN, constants::pumpfun::BUY_DISCRIMINATOR)
+        }
+        DexType::Bonk => {
+            (constants::bonk::accounts::BONK, constants::bonk::BUY_EXECT_IN_DISCRIMINATOR)
+        }
+        _ => return Err(anyhow!("Trade template is not supported for {:?}", dex_type)),
+    };
+
+    let mut slots = vec![];
+    let mut found_buy = false;
+    for (index, instruction) in instructions.iter().enumerate() {
+        if instruction.program_id == program_id && instruction.data.starts_with(&discriminator) {
+            found_buy = true;
+            slots.push(PatchSlot {
+                instruction_index: index,
+                offset: 8,
+                source: PatchSource::InstructionArg(0),
+            });
+            slots.push(PatchSlot {
+                instruction_index: index,
+                offset: 16,
+                source: PatchSource::InstructionArg(1),
+            });
+        } else if instruction.program_id == solana_system_interface::program::ID
+            && instruction.data.starts_with(&SYSTEM_TRANSFER_TAG)
+        {
+            slots.push(PatchSlot {
+                instruction_index: index,
+                offset: 4,
+                source: PatchSource::SolAmount,
+            });
+        }
+    }
+
+    if !found_buy {
+        return Err(anyhow!("Buy instruction not found for {:?}", dex_type));
+    }
+    Ok(slots)
+}
+
+fn write_u64(
+    message: &mut v0::Message,
+    instruction_index: usize,
+    offset: usize,
+    value: u64,
+) -> Result<()> {
+    let data = &mut message
+        .instructions
+        .get_mut(instruction_index)
+        .ok_or_else(|| anyhow!("Template instruction {} out of range", instruction_index))?
+        .data;
+    data.get_mut(offset..offset + 8)
+        .ok_or_else(|| anyhow!("Template instruction {} data too short", instruction_index))?
+        .copy_from_slice(&value.to_le_bytes());
+    Ok(())
+}
+
+/// 单个服务商预编译好的消息
+struct CompiledTemplate {
+    swqos_client: Arc<SwqosClient>,
+    message: v0::Message,
+    patches: Vec<PatchSlot>,
+    /// 小费转账指令位置，不需要小费的服务商为空
+    tip_instruction_index: Option<usize>,
+    min_tip: Lamports,
+}
+
+impl CompiledTemplate {
+    fn patch(
+        &self,
+        amounts: &TemplateAmounts,
+        custom_tip_fee: Option<Lamports>,
+        blockhash: Hash,
+    ) -> Result<v0::Message> {
+        let mut message = self.message.clone();
+        for slot in &self.patches {
+            let value = match slot.source {
+                PatchSource::SolAmount => amounts.sol_amount.as_u64(),
+                PatchSource::InstructionArg(i) => amounts.instruction_args[i],
+            };
+            write_u64(&mut message, slot.instruction_index, slot.offset, value)?;
+        }
+        if let (Some(index), Some(tip_fee)) = (self.tip_instruction_index, custom_tip_fee) {
+            write_u64(&mut message, index, 4, tip_fee.max(self.min_tip).as_u64())?;
+        }
+        message.recent_blockhash = blockhash;
+        Ok(message)
+    }
+}
+
+/// 预编译的买入交易模板
+///
+/// 在拿到新币事件时（例如 `PumpFunCreateTokenEvent`）提前构建，PDA、ATA、
+/// 计算预算指令、地址查找表和账户排序都在构建时完成。触发时只写入金额、
+/// 小费和 blockhash/nonce 后签名，省去每个服务商重复编译消息的耗时。
+///
+/// 构建时的 `sol_amount` 仅作为占位，实际金额以触发时的 `TemplateAmounts` 为准。
+/// 目前支持 PumpFun 和 Bonk 买入。
+pub struct TradeTemplate {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    payer: Arc<dyn AsyncTradeSigner>,
+    fee_payer: Option<Arc<dyn AsyncTradeSigner>>,
+    context: TradeContext,
+    templates: Vec<CompiledTemplate>,
+}
+
+impl TradeTemplate {
+    /// 使用协议指令构建器生成业务指令，并为每个服务商编译消息
+    pub async fn build(
+        dex_type: DexType,
+        mut params: BuyParams,
+        swqos_clients: Vec<Arc<SwqosClient>>,
+    ) -> Result<Self> {
+        if params.data_size_limit == 0 {
+            params.data_size_limit = MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
+        }
+
+        let builder = TradeFactory::create_instruction_builder(dex_type.clone());
+        let business_instructions = builder.build_buy_instructions(&params).await?;
+        let business_patches = find_patch_slots(&dex_type, &business_instructions)?;
+
+        let address_lookup_table_accounts = get_address_lookup_table_accounts(
+            &params.context.lookup_table_cache,
+            params.lookup_table_key,
+        )
+        .await;
+        let nonce_account = params.context.nonce_cache.get_nonce_info().nonce_account;
+        // nonce 推进、小费和手续费都由手续费付款账户承担
+        let fee_payer_pubkey = get_fee_payer_pubkey(&params.payer, &params.fee_payer);
+
+        let mut templates = Vec::with_capacity(swqos_clients.len());
+        for swqos_client in swqos_clients {
+            let swqos_type = swqos_client.get_swqos_type();
+            let priority_fee = params.priority_fee.for_swqos(swqos_type);
+
+            let mut instructions = vec![];
+            if let Some(nonce_account) = nonce_account {
+                instructions.push(advance_nonce_account(&nonce_account, &fee_payer_pubkey));
+            }
+            if swqos_type.need_tip() {
+                add_tip_compute_budget_instructions(
+                    &mut instructions,
+                    &priority_fee,
+                    params.data_size_limit,
+                );
+            } else {
+                add_rpc_compute_budget_instructions(
+                    &mut instructions,
+                    &priority_fee,
+                    params.data_size_limit,
+                );
+            }
+
+            let prefix_len = instructions.len();
+            instructions.extend(business_instructions.iter().cloned());
+
+            let min_tip = swqos_client.get_min_tip();
+            let tip_instruction_index = if swqos_type.need_tip() {
+                let tip_account = Pubkey::from_str(&swqos_client.get_tip_account()?)?;
+                let tip_fee = priority_fee.buy_tip_fee.max(min_tip);
+                instructions.push(transfer(&fee_payer_pubkey, &tip_account, tip_fee.as_u64()));
+                Some(instructions.len() - 1)
+            } else {
+                None
+            };
+
+            // 编译后指令顺序不变，业务指令的位置整体偏移前置指令数量
+            let message = v0::Message::try_compile(
+                &fee_payer_pubkey,
+                &instructions,
+                &address_lookup_table_accounts,
+                params.recent_blockhash,
+            )?;
+            let patches = business_patches
+                .iter()
+                .map(|slot| PatchSlot {
+                    instruction_index: slot.instruction_index + prefix_len,
+                    ..*slot
+                })
+                .collect();
+
+            templates.push(CompiledTemplate {
+                swqos_client,
+                message,
+                patches,
+                tip_instruction_index,
+                min_tip,
+            });
+        }
+
+        Ok(Self {
+            dex_type,
+            mint: params.mint,
+            payer: params.payer,
+            fee_payer: params.fee_payer,
+            context: params.context,
+            templates,
+        })
+    }
+
+    /// 写入金额、小费和 blockhash 后签名，返回每个服务商对应的交易
+    ///
+    /// `custom_tip_fee` 为空时使用构建模板时的小费。
+    pub async fn sign(
+        &self,
+        amounts: &TemplateAmounts,
+        recent_blockhash: Hash,
+        custom_tip_fee: Option<Lamports>,
+    ) -> Result<Vec<(Arc<SwqosClient>, VersionedTransaction)>> {
+        check_nonce_ready(&self.context.nonce_cache)?;
+        let blockhash = get_transaction_blockhash(&self.context.nonce_cache, recent_blockhash);
+        try_join_all(self.templates.iter().map(|template| async move {
+            let message = template.patch(amounts, custom_tip_fee, blockhash)?;
+            let message = VersionedMessage::V0(message);
+            let transaction = match &self.fee_payer {
+                Some(fee_payer) => {
+                    sign_versioned_message(message, &[fee_payer.as_ref(), self.payer.as_ref()])
+                        .await?
+                }
+                None => sign_versioned_message(message, &[self.payer.as_ref()]).await?,
+            };
+            Ok::<_, anyhow::Error>((template.swqos_client.clone(), transaction))
+        }))
+        .await
+    }
+
+    /// 签名并通过所有服务商并行发送
+    pub async fn execute(
+        &self,
+        amounts: &TemplateAmounts,
+        recent_blockhash: Hash,
+        custom_tip_fee: Option<Lamports>,
+    ) -> Result<()> {
+        let mut timer = TradeTimer::new(format!("签名模板交易: {:?}", self.dex_type));
+        let transactions = self.sign(amounts, recent_blockhash, custom_tip_fee).await?;
+        timer.stage(format!("提交模板交易: {:?}", self.dex_type));
+
+        let mut handles: Vec<JoinHandle<Result<()>>> = vec![];
+        for (swqos_client, transaction) in transactions {
+            handles.push(tokio::spawn(async move {
+                if !swqos_client.acquire_send_permit().await {
+                    println!(" {:?}{}已限流，跳过", swqos_client.get_swqos_type(), TradeType::Buy);
+                    return Ok(());
+                }
+                swqos_client.send_transaction(TradeType::Buy, &transaction).await
+            }));
+        }
+
+        let mut errors = Vec::new();
+        for handle in handles {
+            match handle.await {
+                Ok(Ok(_)) => (),
+                Ok(Err(e)) => errors.push(format!("Task error: {}", e)),
+                Err(e) => errors.push(format!("Join error: {}", e)),
+            }
+        }
+        timer.finish();
+
+        if !errors.is_empty() {
+            for error in &errors {
+                println!("{}", error);
+            }
+            return Err(anyhow!("Some tasks failed: {:?}", errors));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    #[test]
+    fn test_patch_pumpfun_template() {
+        let payer = Pubkey::new_unique();
+        let wsol_account = Pubkey::new_unique();
+        let mut buy_data = constants::pumpfun::BUY_DISCRIMINATOR.to_vec();
+        buy_data.extend_from_slice(&1u64.to_le_bytes());
+        buy_data.extend_from_slice(&2u64.to_le_bytes());
+        let business_instructions = vec![
+            transfer(&payer, &wsol_account, 3),
+            Instruction::new_with_bytes(
+                constants::pumpfun::accounts::PUMPFUN,
+                &buy_data,
+                vec![AccountMeta::new(payer, true)],
+            ),
+        ];
+
+        let patches = find_patch_slots(&DexType::PumpFun, &business_instructions).unwrap();
+        assert_eq!(patches.len(), 3);
+
+        let message =
+            v0::Message::try_compile(&payer, &business_instructions, &[], Hash::default()).unwrap();
+        let template = CompiledTemplate {
+            swqos_client: Arc::new(crate::swqos::solana_rpc::SolRpcClient::new(Arc::new(
+                crate::common::SolanaRpcClient::new("http://127.0.0.1:8899".to_string()),
+            ))),
+            message,
+            patches,
+            tip_instruction_index: None,
+            min_tip: Lamports::ZERO,
+        };
+
+        let blockhash = Hash::new_unique();
+        let amounts = TemplateAmounts { sol_amount: Lamports::new(30), instruction_args: [10, 20] };
+        let patched = template.patch(&amounts, Some(Lamports::new(1_000)), blockhash).unwrap();
+
+        assert_eq!(patched.recent_blockhash, blockhash);
+        assert_eq!(&patched.instructions[0].data[4..12], &30u64.to_le_bytes());
+        assert_eq!(&patched.instructions[1].data[8..16], &10u64.to_le_bytes());
+        assert_eq!(&patched.instructions[1].data[16..24], &20u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_template_rejects_unsupported_dex() {
+        assert!(find_patch_slots(&DexType::PumpSwap, &[]).is_err());
+        assert!(find_patch_slots(&DexType::PumpFun, &[]).is_err());
+    }
+}
@@ -1,5 +1,5 @@
-use super::params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams};
-use anyhow::Result;
+use super::params::{BuyParams, BuyWithTipParams, CreateParams, SellParams, SellWithTipParams};
+use anyhow::{anyhow, Result};
 use solana_sdk::instruction::Instruction;
 
 /// 交易执行器trait - 定义了所有交易协议都需要实现的核心方法
@@ -17,6 +17,9 @@ pub trait TradeExecutor: Send + Sync {
     /// 使用MEV服务执行卖出交易
     async fn sell_with_tip(&self, params: SellWithTipParams) -> Result<()>;
 
+    /// 创建代币，可选在同一笔交易中完成开发者买入
+    async fn create(&self, params: CreateParams) -> Result<()>;
+
     /// 获取协议名称
     fn protocol_name(&self) -> &'static str;
 }
@@ -29,6 +32,11 @@ pub trait InstructionBuilder: Send + Sync {
 
     /// 构建卖出指令
     async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>>;
+
+    /// 构建创建代币指令，协议不支持创建时返回错误
+    async fn build_create_instructions(&self, _params: &CreateParams) -> Result<Vec<Instruction>> {
+        Err(anyhow!("Token creation is not supported by this protocol"))
+    }
 }
 
 /// 协议特定参数trait - 允许每个协议定义自己的参数
@@ -3,10 +3,14 @@ use std::sync::Arc;
 
 use crate::instruction::{
     bonk::BonkInstructionBuilder, pumpfun::PumpFunInstructionBuilder,
-    pumpswap::PumpSwapInstructionBuilder, raydium_cpmm::RaydiumCpmmInstructionBuilder,
+    pumpswap::PumpSwapInstructionBuilder, raydium_amm_v4::RaydiumAmmV4InstructionBuilder,
+    raydium_clmm::RaydiumClmmInstructionBuilder, raydium_cpmm::RaydiumCpmmInstructionBuilder,
 };
 
-use super::core::{executor::GenericTradeExecutor, traits::TradeExecutor};
+use super::core::{
+    executor::GenericTradeExecutor,
+    traits::{InstructionBuilder, TradeExecutor},
+};
 
 /// 支持的交易协议
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -15,6 +19,8 @@ pub enum DexType {
     PumpSwap,
     Bonk,
     RaydiumCpmm,
+    RaydiumAmmV4,
+    RaydiumClmm,
 }
 
 impl std::fmt::Display for DexType {
@@ -24,6 +30,8 @@ impl std::fmt::Display for DexType {
             DexType::PumpSwap => write!(f, "PumpSwap"),
             DexType::Bonk => write!(f, "Bonk"),
             DexType::RaydiumCpmm => write!(f, "RaydiumCpmm"),
+            DexType::RaydiumAmmV4 => write!(f, "RaydiumAmmV4"),
+            DexType::RaydiumClmm => write!(f, "RaydiumClmm"),
         }
     }
 }
@@ -37,6 +45,8 @@ impl std::str::FromStr for DexType {
             "pumpswap" => Ok(DexType::PumpSwap),
             "bonk" => Ok(DexType::Bonk),
             "raydiumcpmm" => Ok(DexType::RaydiumCpmm),
+            "raydiumammv4" => Ok(DexType::RaydiumAmmV4),
+            "raydiumclmm" => Ok(DexType::RaydiumClmm),
             _ => Err(anyhow!("Unsupported protocol: {}", s)),
         }
     }
@@ -68,6 +78,26 @@ impl TradeFactory {
                     "RaydiumCpmm",
                 ))
             }
+            DexType::RaydiumAmmV4 => {
+                let instruction_builder = Arc::new(RaydiumAmmV4InstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumAmmV4"))
+            }
+            DexType::RaydiumClmm => {
+                let instruction_builder = Arc::new(RaydiumClmmInstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumClmm"))
+            }
+        }
+    }
+
+    /// 创建指定协议的指令构建器
+    pub fn create_instruction_builder(dex_type: DexType) -> Arc<dyn InstructionBuilder> {
+        match dex_type {
+            DexType::PumpFun => Arc::new(PumpFunInstructionBuilder),
+            DexType::PumpSwap => Arc::new(PumpSwapInstructionBuilder),
+            DexType::Bonk => Arc::new(BonkInstructionBuilder),
+            DexType::RaydiumCpmm => Arc::new(RaydiumCpmmInstructionBuilder),
+            DexType::RaydiumAmmV4 => Arc::new(RaydiumAmmV4InstructionBuilder),
+            DexType::RaydiumClmm => Arc::new(RaydiumClmmInstructionBuilder),
         }
     }
 
@@ -78,6 +108,8 @@ impl TradeFactory {
             DexType::PumpSwap,
             DexType::Bonk,
             DexType::RaydiumCpmm,
+            DexType::RaydiumAmmV4,
+            DexType::RaydiumClmm,
         ]
     }
 
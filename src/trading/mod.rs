@@ -4,8 +4,15 @@ pub mod core;
 pub mod factory;
 pub mod pumpfun;
 pub mod pumpswap;
+pub mod raydium_amm_v4;
+pub mod raydium_clmm;
 pub mod raydium_cpmm;
 
-pub use core::params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams};
+pub use core::bundle::{BundleBuyResult, MAX_BUNDLE_TRANSACTIONS};
+pub use core::params::{
+    BonkCreateParams, BuyParams, BuyWithTipParams, CreateParams, SellParams, SellWithTipParams,
+    SwapMode, TokenMetadata,
+};
+pub use core::template::{TemplateAmounts, TradeTemplate};
 pub use core::traits::{InstructionBuilder, TradeExecutor};
 pub use factory::TradeFactory;
@@ -120,6 +120,76 @@ pub async fn get_wsol_amount(
     }
 }
 
+/// 买入 `base_amount_out` 个代币需要支付的 quote 数量（含手续费），对应 `buy` 指令的
+/// base-out 模式。与合约一致，各项手续费都向上取整。
+pub fn get_buy_quote_amount_in(
+    base_amount_out: u64,
+    pool_base_token_reserves: u64,
+    pool_quote_token_reserves: u64,
+    lp_fee_basis_points: u64,
+    protocol_fee_basis_points: u64,
+    coin_creator_fee_basis_points: u64,
+) -> Result<u64, anyhow::Error> {
+    if base_amount_out >= pool_base_token_reserves {
+        return Err(anyhow::anyhow!("Not enough base tokens in the pool"));
+    }
+    let quote_amount_in = (pool_quote_token_reserves as u128 * base_amount_out as u128)
+        .div_ceil((pool_base_token_reserves - base_amount_out) as u128);
+    let fee = |basis_points: u64| (quote_amount_in * basis_points as u128).div_ceil(10000);
+    let total = quote_amount_in
+        + fee(lp_fee_basis_points)
+        + fee(protocol_fee_basis_points)
+        + fee(coin_creator_fee_basis_points);
+    u64::try_from(total).map_err(|_| anyhow::anyhow!("Quote amount overflows u64"))
+}
+
+/// 存入流动性得到 `lp_token_amount_out` 个 LP 代币需要的 `(base, quote)` 数量，向上取整
+pub fn get_deposit_amounts(
+    lp_token_amount_out: u64,
+    pool_base_token_reserves: u64,
+    pool_quote_token_reserves: u64,
+    lp_supply: u64,
+) -> Result<(u64, u64), anyhow::Error> {
+    if lp_supply == 0 {
+        return Err(anyhow::anyhow!("Pool has no LP supply"));
+    }
+    let share = |reserves: u64| {
+        let amount = (lp_token_amount_out as u128 * reserves as u128).div_ceil(lp_supply as u128);
+        u64::try_from(amount).map_err(|_| anyhow::anyhow!("Deposit amount overflows u64"))
+    };
+    Ok((share(pool_base_token_reserves)?, share(pool_quote_token_reserves)?))
+}
+
+/// 存入 `quote_amount_in` 个 quote 代币最多能得到的 LP 代币数量，向下取整
+pub fn get_deposit_lp_amount(
+    quote_amount_in: u64,
+    pool_quote_token_reserves: u64,
+    lp_supply: u64,
+) -> Result<u64, anyhow::Error> {
+    if pool_quote_token_reserves == 0 {
+        return Err(anyhow::anyhow!("Pool has no quote reserves"));
+    }
+    let lp_amount = quote_amount_in as u128 * lp_supply as u128 / pool_quote_token_reserves as u128;
+    u64::try_from(lp_amount).map_err(|_| anyhow::anyhow!("LP amount overflows u64"))
+}
+
+/// 赎回 `lp_token_amount_in` 个 LP 代币得到的 `(base, quote)` 数量，向下取整
+pub fn get_withdraw_amounts(
+    lp_token_amount_in: u64,
+    pool_base_token_reserves: u64,
+    pool_quote_token_reserves: u64,
+    lp_supply: u64,
+) -> Result<(u64, u64), anyhow::Error> {
+    if lp_supply == 0 {
+        return Err(anyhow::anyhow!("Pool has no LP supply"));
+    }
+    if lp_token_amount_in > lp_supply {
+        return Err(anyhow::anyhow!("LP amount exceeds the pool LP supply"));
+    }
+    let share =
+        |reserves: u64| (lp_token_amount_in as u128 * reserves as u128 / lp_supply as u128) as u64;
+    Ok((share(pool_base_token_reserves), share(pool_quote_token_reserves)))
+}
 
 pub(crate) fn coin_creator_vault_authority(coin_creator: Pubkey) -> Pubkey {
     let (pump_pool_authority, _) = Pubkey::find_program_address(
@@ -166,3 +236,71 @@ pub fn get_global_volume_accumulator_pda() -> Option<Pubkey> {
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
+
+/// `create_pool` 创建的池子地址，由序号、创建者和两个 mint 决定
+pub fn get_pool_pda(
+    index: u16,
+    creator: &Pubkey,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Option<Pubkey> {
+    let index = index.to_le_bytes();
+    let seeds: &[&[u8]; 5] = &[
+        crate::constants::pumpswap::seeds::POOL_SEED,
+        &index,
+        creator.as_ref(),
+        base_mint.as_ref(),
+        quote_mint.as_ref(),
+    ];
+    let program_id: &Pubkey = &crate::constants::pumpswap::accounts::AMM_PROGRAM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+pub fn get_lp_mint_pda(pool: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] = &[crate::constants::pumpswap::seeds::POOL_LP_MINT_SEED, pool.as_ref()];
+    let program_id: &Pubkey = &crate::constants::pumpswap::accounts::AMM_PROGRAM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buy_quote_amount_in() {
+        // 50 SOL / 1e12 代币的池子买入 1% 的代币
+        let amount_in =
+            get_buy_quote_amount_in(10_000_000_000, 1_000_000_000_000, 50_000_000_000, 20, 5, 5)
+                .unwrap();
+        assert_eq!(amount_in, 506565660);
+        // 不收创作者费用时只少这一项
+        let without_creator =
+            get_buy_quote_amount_in(10_000_000_000, 1_000_000_000_000, 50_000_000_000, 20, 5, 0)
+                .unwrap();
+        assert_eq!(amount_in - without_creator, 252526);
+        assert!(get_buy_quote_amount_in(10, 10, 50_000_000_000, 20, 5, 5).is_err());
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw_amounts() {
+        let (base_reserves, quote_reserves, lp_supply) = (1_000_000_000_000, 50_000_000_000, 7_000);
+        let (base, quote) =
+            get_deposit_amounts(100, base_reserves, quote_reserves, lp_supply).unwrap();
+        // 存入时向上取整，保证池子不吃亏
+        assert_eq!(base, 14_285_714_286);
+        assert_eq!(quote, 714_285_715);
+        assert_eq!(get_deposit_lp_amount(quote, quote_reserves, lp_supply).unwrap(), 100);
+
+        let (base, quote) =
+            get_withdraw_amounts(100, base_reserves, quote_reserves, lp_supply).unwrap();
+        assert_eq!(base, 14_285_714_285);
+        assert_eq!(quote, 714_285_714);
+        assert!(
+            get_withdraw_amounts(lp_supply + 1, base_reserves, quote_reserves, lp_supply).is_err()
+        );
+        assert!(get_deposit_amounts(1, base_reserves, quote_reserves, 0).is_err());
+        assert!(get_withdraw_amounts(0, base_reserves, quote_reserves, 0).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    common::{Lamports, SolanaRpcClient},
+    constants::{self, raydium_amm_v4::accounts::WSOL_TOKEN_ACCOUNT},
+    trading::raydium_amm_v4::pool::AmmInfo,
+};
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+
+pub fn get_authority_pda() -> Option<Pubkey> {
+    let seeds: &[&[u8]; 1] = &[constants::raydium_amm_v4::seeds::AMM_AUTHORITY_SEED];
+    let program_id: &Pubkey = &constants::raydium_amm_v4::accounts::RAYDIUM_AMM_V4;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 输入 `amount_in` 得到的输出数量，对应 `swap_base_in`
+///
+/// 手续费从输入中扣除，向上取整，通常为 25/10000。
+pub fn get_amount_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    swap_fee_numerator: u64,
+    swap_fee_denominator: u64,
+) -> Result<u64, anyhow::Error> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(anyhow!("池子储备金为零，无法进行交换"));
+    }
+    if swap_fee_denominator == 0 || swap_fee_numerator >= swap_fee_denominator {
+        return Err(anyhow!("手续费率无效"));
+    }
+    let amount_in = amount_in as u128;
+    let swap_fee = (amount_in * swap_fee_numerator as u128).div_ceil(swap_fee_denominator as u128);
+    let amount_in_less_fee = amount_in - swap_fee;
+    // 恒定乘积公式: amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
+    let amount_out =
+        amount_in_less_fee * reserve_out as u128 / (reserve_in as u128 + amount_in_less_fee);
+    Ok(amount_out as u64)
+}
+
+/// 得到 `amount_out` 需要输入的数量，包含手续费，对应 `swap_base_out`
+pub fn get_amount_in(
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    swap_fee_numerator: u64,
+    swap_fee_denominator: u64,
+) -> Result<u64, anyhow::Error> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(anyhow!("池子储备金为零，无法进行交换"));
+    }
+    if amount_out >= reserve_out {
+        return Err(anyhow!("输出数量超过池子储备金"));
+    }
+    if swap_fee_denominator == 0 || swap_fee_numerator >= swap_fee_denominator {
+        return Err(anyhow!("手续费率无效"));
+    }
+    let amount_in_before_fee =
+        (amount_out as u128 * reserve_in as u128).div_ceil((reserve_out - amount_out) as u128);
+    let amount_in = (amount_in_before_fee * swap_fee_denominator as u128)
+        .div_ceil((swap_fee_denominator - swap_fee_numerator) as u128);
+    u64::try_from(amount_in).map_err(|_| anyhow!("输入数量超出 u64 范围"))
+}
+
+/// 获取池子的 coin 和 pc 储备，已扣除金库中尚未提取的协议收益
+///
+/// # 返回值
+/// 返回 coin_reserves, pc_reserves
+pub async fn get_pool_reserves(
+    rpc: &SolanaRpcClient,
+    amm: &Pubkey,
+) -> Result<(u64, u64), anyhow::Error> {
+    let amm_info = AmmInfo::fetch(rpc, amm).await?;
+    get_vault_reserves(rpc, &amm_info).await
+}
+
+/// 读取已获取的池子的金库余额，扣除尚未提取的协议收益
+async fn get_vault_reserves(
+    rpc: &SolanaRpcClient,
+    amm_info: &AmmInfo,
+) -> Result<(u64, u64), anyhow::Error> {
+    let coin_balance = rpc.get_token_account_balance(&amm_info.coin_vault).await?;
+    let pc_balance = rpc.get_token_account_balance(&amm_info.pc_vault).await?;
+
+    let coin_amount =
+        coin_balance.amount.parse::<u64>().map_err(|e| anyhow!("解析 coin 余额失败: {}", e))?;
+    let pc_amount =
+        pc_balance.amount.parse::<u64>().map_err(|e| anyhow!("解析 pc 余额失败: {}", e))?;
+
+    Ok((
+        coin_amount.saturating_sub(amm_info.state_data.need_take_pnl_coin),
+        pc_amount.saturating_sub(amm_info.state_data.need_take_pnl_pc),
+    ))
+}
+
+/// 读取池子一次，返回 `(wsol_reserves, token_reserves)` 和池子状态
+async fn get_wsol_token_reserves(
+    rpc: &SolanaRpcClient,
+    amm: &Pubkey,
+) -> Result<(u64, u64, AmmInfo), anyhow::Error> {
+    let amm_info = AmmInfo::fetch(rpc, amm).await?;
+    let (coin_reserves, pc_reserves) = get_vault_reserves(rpc, &amm_info).await?;
+    if amm_info.coin_vault_mint == WSOL_TOKEN_ACCOUNT {
+        Ok((coin_reserves, pc_reserves, amm_info))
+    } else {
+        Ok((pc_reserves, coin_reserves, amm_info))
+    }
+}
+
+/// 花费 `sol_amount` 可以买到的代币数量，已扣除手续费
+pub async fn get_buy_token_amount(
+    rpc: &SolanaRpcClient,
+    amm: &Pubkey,
+    sol_amount: Lamports,
+) -> Result<u64, anyhow::Error> {
+    let (wsol_reserves, token_reserves, amm_info) = get_wsol_token_reserves(rpc, amm).await?;
+    get_amount_out(
+        sol_amount.as_u64(),
+        wsol_reserves,
+        token_reserves,
+        amm_info.fees.swap_fee_numerator,
+        amm_info.fees.swap_fee_denominator,
+    )
+}
+
+/// 卖出 `token_amount` 个代币可以得到的SOL数量，已扣除手续费
+pub async fn get_sell_sol_amount(
+    rpc: &SolanaRpcClient,
+    amm: &Pubkey,
+    token_amount: u64,
+) -> Result<Lamports, anyhow::Error> {
+    let (wsol_reserves, token_reserves, amm_info) = get_wsol_token_reserves(rpc, amm).await?;
+    get_amount_out(
+        token_amount,
+        token_reserves,
+        wsol_reserves,
+        amm_info.fees.swap_fee_numerator,
+        amm_info.fees.swap_fee_denominator,
+    )
+    .map(Lamports::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_authority_pda() {
+        assert_eq!(get_authority_pda(), Some(constants::raydium_amm_v4::accounts::AUTHORITY));
+    }
+
+    #[test]
+    fn test_swap_amounts() {
+        let (reserve_in, reserve_out) = (50_000_000_000, 1_000_000_000_000);
+        // 10_000_000 * 25 / 10000 = 25_000 手续费
+        let amount_out = get_amount_out(10_000_000, reserve_in, reserve_out, 25, 10_000).unwrap();
+        assert_eq!(amount_out as u128, 9_975_000u128 * reserve_out as u128 / 50_009_975_000);
+
+        let target = 10_000_000_000;
+        let amount_in = get_amount_in(target, reserve_in, reserve_out, 25, 10_000).unwrap();
+        assert!(get_amount_out(amount_in, reserve_in, reserve_out, 25, 10_000).unwrap() >= target);
+        assert!(
+            get_amount_out(amount_in - 1, reserve_in, reserve_out, 25, 10_000).unwrap() < target
+        );
+        assert!(get_amount_in(reserve_out, reserve_in, reserve_out, 25, 10_000).is_err());
+    }
+}
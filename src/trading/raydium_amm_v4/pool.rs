@@ -0,0 +1,320 @@
+use crate::{
+    common::SolanaRpcClient, constants::raydium_amm_v4::accounts,
+    trading::common::find_deepest_vault,
+};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::pubkey::Pubkey;
+
+/// AmmInfo 账户大小
+pub const AMM_INFO_SIZE: usize = 752;
+/// 账户数据中 coin_vault_mint 的偏移，AMM v4 账户没有 discriminator
+pub const COIN_MINT_OFFSET: usize = 400;
+/// 账户数据中 pc_vault_mint 的偏移
+pub const PC_MINT_OFFSET: usize = 432;
+
+#[derive(Debug, Clone, Copy, BorshDeserialize)]
+pub struct AmmFees {
+    pub min_separate_numerator: u64,
+    pub min_separate_denominator: u64,
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub pnl_numerator: u64,
+    pub pnl_denominator: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+}
+
+#[derive(Debug, Clone, Copy, BorshDeserialize)]
+pub struct AmmStateData {
+    /// 金库中属于协议、尚未提取的收益，不参与交换
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    pub total_pnl_pc: u64,
+    pub total_pnl_coin: u64,
+    pub pool_open_time: u64,
+    pub padding: [u64; 2],
+    pub orderbook_to_init_time: u64,
+    pub swap_coin_in_amount: u128,
+    pub swap_pc_out_amount: u128,
+    pub swap_acc_pc_fee: u64,
+    pub swap_pc_in_amount: u128,
+    pub swap_coin_out_amount: u128,
+    pub swap_acc_coin_fee: u64,
+}
+
+/// AMM v4 池子状态，coin 和 pc 分别对应交易对的 base 和 quote
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AmmInfo {
+    pub status: u64,
+    pub nonce: u64,
+    pub order_num: u64,
+    pub depth: u64,
+    pub coin_decimals: u64,
+    pub pc_decimals: u64,
+    pub state: u64,
+    pub reset_flag: u64,
+    pub min_size: u64,
+    pub vol_max_cut_ratio: u64,
+    pub amount_wave: u64,
+    pub coin_lot_size: u64,
+    pub pc_lot_size: u64,
+    pub min_price_multiplier: u64,
+    pub max_price_multiplier: u64,
+    pub sys_decimal_value: u64,
+    pub fees: AmmFees,
+    pub state_data: AmmStateData,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_vault_mint: Pubkey,
+    pub pc_vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub open_orders: Pubkey,
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    pub target_orders: Pubkey,
+    pub padding1: [u64; 8],
+    pub amm_owner: Pubkey,
+    pub lp_amount: u64,
+    pub client_order_id: u64,
+    pub recent_epoch: u64,
+    pub padding2: u64,
+}
+
+/// OpenBook 市场状态，只解析交换需要的账户
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct MarketState {
+    pub account_flags: u64,
+    pub own_address: Pubkey,
+    pub vault_signer_nonce: u64,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub coin_vault: Pubkey,
+    pub coin_deposits_total: u64,
+    pub coin_fees_accrued: u64,
+    pub pc_vault: Pubkey,
+    pub pc_deposits_total: u64,
+    pub pc_fees_accrued: u64,
+    pub pc_dust_threshold: u64,
+    pub req_q: Pubkey,
+    pub event_q: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub coin_lot_size: u64,
+    pub pc_lot_size: u64,
+    pub fee_rate_bps: u64,
+    pub referrer_rebates_accrued: u64,
+}
+
+/// 构建交换指令需要的全部池子和市场账户
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub amm: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub market_program: Pubkey,
+    pub market: Pubkey,
+    pub market_bids: Pubkey,
+    pub market_asks: Pubkey,
+    pub market_event_queue: Pubkey,
+    pub market_coin_vault: Pubkey,
+    pub market_pc_vault: Pubkey,
+    pub market_vault_signer: Pubkey,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+}
+
+impl AmmInfo {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < AMM_INFO_SIZE {
+            return Err(anyhow!("AmmInfo account data is too short"));
+        }
+        let amm_info = AmmInfo::deserialize(&mut &data[..AMM_INFO_SIZE])?;
+        Ok(amm_info)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, amm: &Pubkey) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(amm).await?;
+
+        if account.owner != accounts::RAYDIUM_AMM_V4 {
+            return Err(anyhow!("Account is not owned by Raydium AMM v4 program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+
+    /// 查找 `mint` 与 WSOL 组成的 WSOL 储备最深的池子
+    pub async fn find_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Self), anyhow::Error> {
+        // mint 为 coin 时 WSOL 为 pc，反之亦然
+        let mut pools = Self::find_by_mint_offset(rpc, mint, COIN_MINT_OFFSET).await?;
+        pools.extend(Self::find_by_mint_offset(rpc, mint, PC_MINT_OFFSET).await?);
+        pools.retain(|(_, amm_info)| {
+            (amm_info.coin_vault_mint == *mint
+                && amm_info.pc_vault_mint == accounts::WSOL_TOKEN_ACCOUNT)
+                || (amm_info.pc_vault_mint == *mint
+                    && amm_info.coin_vault_mint == accounts::WSOL_TOKEN_ACCOUNT)
+        });
+        if pools.is_empty() {
+            return Err(anyhow!("No WSOL pool found for mint {}", mint));
+        }
+
+        let wsol_vaults: Vec<Pubkey> = pools
+            .iter()
+            .map(|(_, amm_info)| {
+                if amm_info.coin_vault_mint == accounts::WSOL_TOKEN_ACCOUNT {
+                    amm_info.coin_vault
+                } else {
+                    amm_info.pc_vault
+                }
+            })
+            .collect();
+        let index = find_deepest_vault(rpc, &wsol_vaults)
+            .await?
+            .ok_or_else(|| anyhow!("No WSOL pool found for mint {}", mint))?;
+        Ok(pools.swap_remove(index))
+    }
+
+    async fn find_by_mint_offset(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        offset: usize,
+    ) -> Result<Vec<(Pubkey, Self)>, anyhow::Error> {
+        let filters = vec![
+            solana_rpc_client_api::filter::RpcFilterType::DataSize(AMM_INFO_SIZE as u64),
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(offset, &mint.to_bytes()),
+            ),
+        ];
+        let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts =
+            rpc.get_program_accounts_with_config(&accounts::RAYDIUM_AMM_V4, config).await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                Self::from_bytes(&account.data).map(|amm_info| (address, amm_info)).ok()
+            })
+            .collect())
+    }
+}
+
+impl MarketState {
+    /// 市场账户以 5 字节的 "serum" 开头，末尾还有 7 字节填充
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 5 {
+            return Err(anyhow!("Market account data is too short"));
+        }
+        let market = MarketState::deserialize(&mut &data[5..])?;
+        Ok(market)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, market: &Pubkey) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(market).await?;
+        Self::from_bytes(&account.data)
+    }
+}
+
+impl PoolKeys {
+    pub fn new(
+        amm: Pubkey,
+        amm_info: &AmmInfo,
+        market: &MarketState,
+    ) -> Result<Self, anyhow::Error> {
+        let market_vault_signer = Pubkey::create_program_address(
+            &[amm_info.market.as_ref(), &market.vault_signer_nonce.to_le_bytes()],
+            &amm_info.market_program,
+        )
+        .map_err(|e| anyhow!("Failed to derive the market vault signer: {}", e))?;
+        Ok(Self {
+            amm,
+            coin_mint: amm_info.coin_vault_mint,
+            pc_mint: amm_info.pc_vault_mint,
+            coin_vault: amm_info.coin_vault,
+            pc_vault: amm_info.pc_vault,
+            open_orders: amm_info.open_orders,
+            target_orders: amm_info.target_orders,
+            market_program: amm_info.market_program,
+            market: amm_info.market,
+            market_bids: market.bids,
+            market_asks: market.asks,
+            market_event_queue: market.event_q,
+            market_coin_vault: market.coin_vault,
+            market_pc_vault: market.pc_vault,
+            market_vault_signer,
+            swap_fee_numerator: amm_info.fees.swap_fee_numerator,
+            swap_fee_denominator: amm_info.fees.swap_fee_denominator,
+        })
+    }
+
+    /// 读取池子和所属的 OpenBook 市场
+    pub async fn fetch(rpc: &SolanaRpcClient, amm: &Pubkey) -> Result<Self, anyhow::Error> {
+        let amm_info = AmmInfo::fetch(rpc, amm).await?;
+        let market = MarketState::fetch(rpc, &amm_info.market).await?;
+        Self::new(*amm, &amm_info, &market)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amm_info_layout() {
+        let (coin_mint, pc_mint, market) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; AMM_INFO_SIZE];
+        // fees.swap_fee_numerator / swap_fee_denominator
+        data[176..184].copy_from_slice(&25u64.to_le_bytes());
+        data[184..192].copy_from_slice(&10_000u64.to_le_bytes());
+        // state_data.need_take_pnl_coin
+        data[192..200].copy_from_slice(&7u64.to_le_bytes());
+        data[COIN_MINT_OFFSET..COIN_MINT_OFFSET + 32].copy_from_slice(coin_mint.as_ref());
+        data[PC_MINT_OFFSET..PC_MINT_OFFSET + 32].copy_from_slice(pc_mint.as_ref());
+        data[528..560].copy_from_slice(market.as_ref());
+        data[720..728].copy_from_slice(&42u64.to_le_bytes());
+
+        let amm_info = AmmInfo::from_bytes(&data).unwrap();
+        assert_eq!(amm_info.fees.swap_fee_numerator, 25);
+        assert_eq!(amm_info.fees.swap_fee_denominator, 10_000);
+        assert_eq!(amm_info.state_data.need_take_pnl_coin, 7);
+        assert_eq!(amm_info.coin_vault_mint, coin_mint);
+        assert_eq!(amm_info.pc_vault_mint, pc_mint);
+        assert_eq!(amm_info.market, market);
+        assert_eq!(amm_info.lp_amount, 42);
+    }
+
+    #[test]
+    fn test_market_state_layout() {
+        let (bids, asks, event_q) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; 388];
+        data[..5].copy_from_slice(b"serum");
+        data[45..53].copy_from_slice(&3u64.to_le_bytes());
+        data[253..285].copy_from_slice(event_q.as_ref());
+        data[285..317].copy_from_slice(bids.as_ref());
+        data[317..349].copy_from_slice(asks.as_ref());
+
+        let market = MarketState::from_bytes(&data).unwrap();
+        assert_eq!(market.vault_signer_nonce, 3);
+        assert_eq!(market.event_q, event_q);
+        assert_eq!(market.bids, bids);
+        assert_eq!(market.asks, asks);
+    }
+}
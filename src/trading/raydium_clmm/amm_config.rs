@@ -0,0 +1,42 @@
+use crate::{common::SolanaRpcClient, constants::raydium_clmm::accounts};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+/// 手续费率的分母，费率 2500 表示 0.25%
+pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
+
+/// 手续费档位配置，每个池子属于一个 AmmConfig，同时决定 tick_spacing
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub index: u16,
+    pub owner: Pubkey,
+    pub protocol_fee_rate: u32,
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    pub fund_fee_rate: u32,
+    pub padding_u32: u32,
+    pub fund_owner: Pubkey,
+    pub padding: [u64; 3],
+}
+
+impl AmmConfig {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 8 {
+            return Err(anyhow!("AmmConfig account data is too short"));
+        }
+        let config = AmmConfig::deserialize(&mut &data[8..])?;
+        Ok(config)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, amm_config: &Pubkey) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(amm_config).await?;
+
+        if account.owner != accounts::RAYDIUM_CLMM {
+            return Err(anyhow!("Account is not owned by Raydium Clmm program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+}
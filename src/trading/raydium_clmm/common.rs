@@ -0,0 +1,346 @@
+use crate::{
+    common::{Lamports, SolanaRpcClient},
+    constants::{
+        self,
+        raydium_clmm::{
+            accounts::WSOL_TOKEN_ACCOUNT, MAX_SQRT_PRICE_X64, MAX_TICK, MIN_SQRT_PRICE_X64,
+            MIN_TICK,
+        },
+    },
+    trading::raydium_clmm::{
+        amm_config::AmmConfig,
+        math::{compute_swap_step, get_sqrt_price_at_tick},
+        pool::PoolState,
+        tick_array::{
+            initialized_tick_array_start_indices, TickArrayBitmapExtension, TickArrayState,
+            TickState,
+        },
+    },
+};
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+
+/// 报价时最多读取的 tick array 数量
+pub const MAX_TICK_ARRAYS: usize = 8;
+
+pub fn get_pool_pda(amm_config: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Option<Pubkey> {
+    // 链上要求 token_mint_0 < token_mint_1
+    let (mint_0, mint_1) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+    let seeds: &[&[u8]; 4] = &[
+        constants::raydium_clmm::seeds::POOL_SEED,
+        amm_config.as_ref(),
+        mint_0.as_ref(),
+        mint_1.as_ref(),
+    ];
+    let program_id: &Pubkey = &constants::raydium_clmm::accounts::RAYDIUM_CLMM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 第 `index` 个手续费档位的 AmmConfig 地址
+pub fn get_amm_config_pda(index: u16) -> Option<Pubkey> {
+    let index_bytes = index.to_be_bytes();
+    let seeds: &[&[u8]; 2] = &[constants::raydium_clmm::seeds::AMM_CONFIG_SEED, &index_bytes];
+    let program_id: &Pubkey = &constants::raydium_clmm::accounts::RAYDIUM_CLMM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+pub fn get_tick_array_pda(pool_state: &Pubkey, start_tick_index: i32) -> Option<Pubkey> {
+    let start_bytes = start_tick_index.to_be_bytes();
+    let seeds: &[&[u8]; 3] =
+        &[constants::raydium_clmm::seeds::TICK_ARRAY_SEED, pool_state.as_ref(), &start_bytes];
+    let program_id: &Pubkey = &constants::raydium_clmm::accounts::RAYDIUM_CLMM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+pub fn get_tick_array_bitmap_extension_pda(pool_state: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] =
+        &[constants::raydium_clmm::seeds::TICK_ARRAY_BITMAP_EXTENSION_SEED, pool_state.as_ref()];
+    let program_id: &Pubkey = &constants::raydium_clmm::accounts::RAYDIUM_CLMM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 交换报价
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// 输入数量，包含手续费
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    /// 交换后的价格平方根
+    pub sqrt_price_x64: u128,
+    /// 交换用到的 tick array 数量，从第一个 tick array 算起
+    pub tick_arrays_used: usize,
+}
+
+/// 沿 tick 逐段计算交换结果，穿过已初始化的 tick 时更新流动性
+///
+/// `tick_arrays` 按交换方向排列，第一个是当前价格所在或之后第一个已初始化的 tick array，
+/// 与 [`initialized_tick_array_start_indices`] 的顺序一致。`zero_for_one` 表示输入 token_0。
+/// `is_base_input` 为 true 时 `amount` 是输入数量（含手续费），否则是期望的输出数量。
+/// 已读取的 tick array 不足以完成交换时返回错误。
+pub fn get_swap_quote(
+    pool: &PoolState,
+    tick_arrays: &[TickArrayState],
+    trade_fee_rate: u32,
+    amount: u64,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<SwapQuote, anyhow::Error> {
+    // 与链上 sqrt_price_limit_x64 传 0 时的默认值相同
+    let sqrt_price_limit =
+        if zero_for_one { MIN_SQRT_PRICE_X64 + 1 } else { MAX_SQRT_PRICE_X64 - 1 };
+
+    // 按交换方向排列的已初始化 tick，价格下降时包含当前 tick
+    let mut ticks: Vec<(usize, &TickState)> = vec![];
+    for (index, tick_array) in tick_arrays.iter().enumerate() {
+        let mut array_ticks: Vec<&TickState> =
+            tick_array.ticks.iter().filter(|tick| tick.is_initialized()).collect();
+        if zero_for_one {
+            array_ticks.reverse();
+        }
+        ticks.extend(array_ticks.into_iter().map(|tick| (index, tick)));
+    }
+    ticks.retain(|(_, tick)| {
+        if zero_for_one {
+            tick.tick <= pool.tick_current
+        } else {
+            tick.tick > pool.tick_current
+        }
+    });
+
+    let overflow = || anyhow!("Swap amount overflow");
+    let mut amount_remaining = amount;
+    let mut amount_calculated = 0u64;
+    let mut fee_amount = 0u64;
+    let mut sqrt_price = pool.sqrt_price_x64;
+    let mut liquidity = pool.liquidity;
+    let mut tick_arrays_used = 0;
+    for (index, tick) in ticks {
+        if amount_remaining == 0 || sqrt_price == sqrt_price_limit {
+            break;
+        }
+        let sqrt_price_next = get_sqrt_price_at_tick(tick.tick.clamp(MIN_TICK, MAX_TICK))?;
+        let sqrt_price_target = if zero_for_one {
+            sqrt_price_next.max(sqrt_price_limit)
+        } else {
+            sqrt_price_next.min(sqrt_price_limit)
+        };
+        let step = compute_swap_step(
+            sqrt_price,
+            sqrt_price_target,
+            liquidity,
+            amount_remaining,
+            trade_fee_rate,
+            is_base_input,
+            zero_for_one,
+        )?;
+        tick_arrays_used = index + 1;
+        sqrt_price = step.sqrt_price_next_x64;
+        fee_amount = fee_amount.checked_add(step.fee_amount).ok_or_else(overflow)?;
+        if is_base_input {
+            amount_remaining -= step.amount_in + step.fee_amount;
+            amount_calculated =
+                amount_calculated.checked_add(step.amount_out).ok_or_else(overflow)?;
+        } else {
+            amount_remaining -= step.amount_out;
+            amount_calculated = amount_calculated
+                .checked_add(step.amount_in + step.fee_amount)
+                .ok_or_else(overflow)?;
+        }
+        if sqrt_price != sqrt_price_next {
+            break;
+        }
+        // 穿过 tick，价格下降时流动性变化方向相反
+        let liquidity_net = if zero_for_one { -tick.liquidity_net } else { tick.liquidity_net };
+        liquidity = liquidity
+            .checked_add_signed(liquidity_net)
+            .ok_or_else(|| anyhow!("Liquidity overflow at tick {}", tick.tick))?;
+    }
+    if amount_remaining > 0 && sqrt_price != sqrt_price_limit {
+        return Err(anyhow!("Not enough liquidity in the loaded tick arrays"));
+    }
+
+    let (amount_in, amount_out) = if is_base_input {
+        (amount - amount_remaining, amount_calculated)
+    } else {
+        (amount_calculated, amount - amount_remaining)
+    };
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        fee_amount,
+        sqrt_price_x64: sqrt_price,
+        tick_arrays_used,
+    })
+}
+
+/// 报价和构建交换指令需要的链上状态
+#[derive(Debug, Clone)]
+pub struct SwapState {
+    pub pool_state: Pubkey,
+    pub pool: PoolState,
+    pub trade_fee_rate: u32,
+    /// bitmap 扩展账户不存在时为 None，此时不能传给交换指令
+    pub bitmap_extension: Option<Pubkey>,
+    /// 按交换方向排列的已初始化 tick array
+    pub tick_arrays: Vec<TickArrayState>,
+}
+
+impl SwapState {
+    /// 读取池子、AmmConfig、bitmap 扩展和交换方向上最多 [`MAX_TICK_ARRAYS`] 个 tick array
+    pub async fn fetch(
+        rpc: &SolanaRpcClient,
+        pool_state: &Pubkey,
+        zero_for_one: bool,
+    ) -> Result<Self, anyhow::Error> {
+        let pool = PoolState::fetch(rpc, pool_state).await?;
+        Self::fetch_with_pool(rpc, pool_state, pool, zero_for_one).await
+    }
+
+    /// 使用已读取的池子，读取其余的链上状态
+    pub async fn fetch_with_pool(
+        rpc: &SolanaRpcClient,
+        pool_state: &Pubkey,
+        pool: PoolState,
+        zero_for_one: bool,
+    ) -> Result<Self, anyhow::Error> {
+        let extension_address = get_tick_array_bitmap_extension_pda(pool_state).unwrap();
+        let accounts = rpc.get_multiple_accounts(&[pool.amm_config, extension_address]).await?;
+        let amm_config = accounts[0]
+            .as_ref()
+            .ok_or_else(|| anyhow!("AmmConfig {} not found", pool.amm_config))
+            .and_then(|account| AmmConfig::from_bytes(&account.data))?;
+        let extension = match &accounts[1] {
+            Some(account) => Some(TickArrayBitmapExtension::from_bytes(&account.data)?),
+            None => None,
+        };
+
+        let start_indices = initialized_tick_array_start_indices(
+            &pool,
+            extension.as_ref(),
+            zero_for_one,
+            MAX_TICK_ARRAYS,
+        );
+        let addresses: Vec<Pubkey> = start_indices
+            .iter()
+            .map(|start_index| get_tick_array_pda(pool_state, *start_index).unwrap())
+            .collect();
+        let mut tick_arrays = Vec::with_capacity(addresses.len());
+        for account in rpc.get_multiple_accounts(&addresses).await? {
+            // 必须连续，缺少的 tick array 之后的都不能使用
+            let Some(account) = account else { break };
+            tick_arrays.push(TickArrayState::from_bytes(&account.data)?);
+        }
+
+        Ok(Self {
+            pool_state: *pool_state,
+            pool,
+            trade_fee_rate: amm_config.trade_fee_rate,
+            bitmap_extension: extension.map(|_| extension_address),
+            tick_arrays,
+        })
+    }
+
+    pub fn quote(
+        &self,
+        amount: u64,
+        zero_for_one: bool,
+        is_base_input: bool,
+    ) -> Result<SwapQuote, anyhow::Error> {
+        get_swap_quote(
+            &self.pool,
+            &self.tick_arrays,
+            self.trade_fee_rate,
+            amount,
+            zero_for_one,
+            is_base_input,
+        )
+    }
+
+    /// 交换指令需要传入的 tick array 地址
+    ///
+    /// 在报价用到的基础上多传一个，避免价格在发送前变动导致账户不足。
+    pub fn tick_array_addresses(&self, quote: &SwapQuote) -> Vec<Pubkey> {
+        let count = (quote.tick_arrays_used + 1).clamp(1, self.tick_arrays.len().max(1));
+        self.tick_arrays
+            .iter()
+            .take(count)
+            .map(|tick_array| get_tick_array_pda(&self.pool_state, tick_array.start_tick_index))
+            .map(|address| address.unwrap())
+            .collect()
+    }
+}
+
+/// 花费 `sol_amount` 可以买到的代币数量，已扣除手续费
+pub async fn get_buy_token_amount(
+    rpc: &SolanaRpcClient,
+    pool_state: &Pubkey,
+    sol_amount: Lamports,
+) -> Result<u64, anyhow::Error> {
+    let pool = PoolState::fetch(rpc, pool_state).await?;
+    let zero_for_one = pool.token_mint_0 == WSOL_TOKEN_ACCOUNT;
+    let swap_state = SwapState::fetch_with_pool(rpc, pool_state, pool, zero_for_one).await?;
+    Ok(swap_state.quote(sol_amount.as_u64(), zero_for_one, true)?.amount_out)
+}
+
+/// 卖出 `token_amount` 个代币可以得到的SOL数量，已扣除手续费
+pub async fn get_sell_sol_amount(
+    rpc: &SolanaRpcClient,
+    pool_state: &Pubkey,
+    token_amount: u64,
+) -> Result<Lamports, anyhow::Error> {
+    let pool = PoolState::fetch(rpc, pool_state).await?;
+    let zero_for_one = pool.token_mint_1 == WSOL_TOKEN_ACCOUNT;
+    let swap_state = SwapState::fetch_with_pool(rpc, pool_state, pool, zero_for_one).await?;
+    Ok(Lamports::new(swap_state.quote(token_amount, zero_for_one, true)?.amount_out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::raydium_clmm::tick_array::{test_tick_array, tick_count};
+
+    fn pool(liquidity: u128) -> PoolState {
+        let mut data = vec![0u8; crate::trading::raydium_clmm::pool::POOL_STATE_SIZE];
+        data[235..237].copy_from_slice(&10u16.to_le_bytes());
+        data[237..253].copy_from_slice(&liquidity.to_le_bytes());
+        data[253..269].copy_from_slice(&(1u128 << 64).to_le_bytes());
+        let mut pool = PoolState::from_bytes(&data).unwrap();
+        pool.tick_current = 0;
+        pool
+    }
+
+    #[test]
+    fn test_swap_quote_crosses_ticks() {
+        let liquidity = 1_000_000_000_000u128;
+        let pool = pool(liquidity);
+        assert_eq!(tick_count(pool.tick_spacing), 600);
+        // 两个头寸分别覆盖 [-600, ..) 和 [-1200, ..)，价格向下穿过时流动性依次减半
+        let tick_arrays = [
+            test_tick_array(-600, &[(-600, liquidity as i128 / 2)]),
+            test_tick_array(-1200, &[(-1200, liquidity as i128 / 2)]),
+        ];
+
+        let quote = get_swap_quote(&pool, &tick_arrays, 2500, 1_000_000, true, true).unwrap();
+        assert_eq!(quote.amount_in, 1_000_000);
+        assert_eq!(quote.tick_arrays_used, 1);
+
+        // 固定输出需要的输入不超过固定输入时花费的数量
+        let exact_out =
+            get_swap_quote(&pool, &tick_arrays, 2500, quote.amount_out, true, false).unwrap();
+        assert_eq!(exact_out.amount_out, quote.amount_out);
+        assert!(exact_out.amount_in <= quote.amount_in);
+
+        // 穿过 -600 后进入第二个 tick array
+        let quote = get_swap_quote(&pool, &tick_arrays, 2500, 40_000_000_000, true, true).unwrap();
+        assert_eq!(quote.tick_arrays_used, 2);
+        assert!(quote.sqrt_price_x64 < get_sqrt_price_at_tick(-600).unwrap());
+
+        // 超过全部流动性时报错
+        assert!(get_swap_quote(&pool, &tick_arrays, 2500, 50_000_000_000, true, true).is_err());
+    }
+}
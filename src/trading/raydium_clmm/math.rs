@@ -0,0 +1,451 @@
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Sub},
+};
+
+use anyhow::anyhow;
+
+use crate::constants::raydium_clmm::{MAX_TICK, MIN_TICK};
+
+use super::amm_config::FEE_RATE_DENOMINATOR;
+
+/// 512 位无符号整数，低位在前，只用于报价计算的中间结果
+///
+/// 链上程序用 U256/U512 计算，这里按相同的取整方式实现所需的几种运算。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct U512([u64; 8]);
+
+impl U512 {
+    const ZERO: Self = Self([0; 8]);
+
+    fn from_u128(value: u128) -> Self {
+        let mut limbs = [0; 8];
+        limbs[0] = value as u64;
+        limbs[1] = (value >> 64) as u64;
+        Self(limbs)
+    }
+
+    fn to_u128(self) -> Option<u128> {
+        if self.0[2..].iter().any(|limb| *limb != 0) {
+            return None;
+        }
+        Some(self.0[0] as u128 | ((self.0[1] as u128) << 64))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|limb| *limb == 0)
+    }
+
+    fn shl1(self) -> Self {
+        let mut limbs = [0u64; 8];
+        let mut carry = 0;
+        for (limb, value) in limbs.iter_mut().zip(self.0) {
+            *limb = (value << 1) | carry;
+            carry = value >> 63;
+        }
+        Self(limbs)
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (self.0[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    fn bits(&self) -> usize {
+        (0..8)
+            .rev()
+            .find(|i| self.0[*i] != 0)
+            .map_or(0, |i| i * 64 + 64 - self.0[i].leading_zeros() as usize)
+    }
+
+    /// 逐位长除法，返回商和余数
+    fn div_rem(self, divisor: Self) -> (Self, Self) {
+        let mut quotient = Self::ZERO;
+        let mut remainder = Self::ZERO;
+        for i in (0..self.bits()).rev() {
+            remainder = remainder.shl1();
+            remainder.0[0] |= self.bit(i) as u64;
+            if remainder >= divisor {
+                remainder = remainder - divisor;
+                quotient.0[i / 64] |= 1 << (i % 64);
+            }
+        }
+        (quotient, remainder)
+    }
+
+    fn div_round(self, divisor: Self, round_up: bool) -> Self {
+        let (quotient, remainder) = self.div_rem(divisor);
+        if round_up && !remainder.is_zero() {
+            quotient + Self::from_u128(1)
+        } else {
+            quotient
+        }
+    }
+}
+
+/// 乘法，调用方保证结果不超过 512 位
+impl Mul for U512 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut limbs = [0u64; 8];
+        for (i, a) in self.0.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, b) in other.0.iter().enumerate().take(8 - i) {
+                let current = limbs[i + j] as u128 + *a as u128 * *b as u128 + carry;
+                limbs[i + j] = current as u64;
+                carry = current >> 64;
+            }
+        }
+        Self(limbs)
+    }
+}
+
+impl Add for U512 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut limbs = [0u64; 8];
+        let mut carry = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (sum, overflow_a) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflow_a || overflow_b;
+        }
+        Self(limbs)
+    }
+}
+
+/// 减法，调用方保证 `self >= other`
+impl Sub for U512 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let mut limbs = [0u64; 8];
+        let mut borrow = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (diff, borrow_a) = self.0[i].overflowing_sub(other.0[i]);
+            let (diff, borrow_b) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = borrow_a || borrow_b;
+        }
+        Self(limbs)
+    }
+}
+
+impl PartialOrd for U512 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U512 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+/// 2^64，Q64.64 中的 1
+const Q64: U512 = U512([0, 1, 0, 0, 0, 0, 0, 0]);
+
+/// tick 对应的价格平方根 `sqrt(1.0001^tick)`，Q64.64 格式
+pub fn get_sqrt_price_at_tick(tick: i32) -> Result<u128, anyhow::Error> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(anyhow!("Tick {} is out of range", tick));
+    }
+    let abs_tick = tick.unsigned_abs();
+    // 第 i 个常数为 2^64 / sqrt(1.0001)^(2^i)
+    const RATIOS: [u128; 19] = [
+        0xfffcb933bd6fb800,
+        0xfff97272373d4000,
+        0xfff2e50f5f657000,
+        0xffe5caca7e10f000,
+        0xffcb9843d60f7000,
+        0xff973b41fa98e800,
+        0xff2ea16466c9b000,
+        0xfe5dee046a9a3800,
+        0xfcbe86c7900bb000,
+        0xf987a7253ac65800,
+        0xf3392b0822bb6000,
+        0xe7159475a2caf000,
+        0xd097f3bdfd2f2000,
+        0xa9f746462d9f8000,
+        0x70d869a156f31c00,
+        0x31be135f97ed3200,
+        0x9aa508b5b85a500,
+        0x5d6af8dedc582c,
+        0x2216e584f5fa,
+    ];
+    let mut ratio = if abs_tick & 1 != 0 { RATIOS[0] } else { 1u128 << 64 };
+    for (i, factor) in RATIOS.iter().enumerate().skip(1) {
+        if abs_tick & (1 << i) != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+    if tick > 0 {
+        ratio = u128::MAX / ratio;
+    }
+    Ok(ratio)
+}
+
+/// 价格在 `sqrt_price_a` 和 `sqrt_price_b` 之间移动时 token_0 的变化量
+///
+/// `liquidity * 2^64 * (sqrt_b - sqrt_a) / (sqrt_a * sqrt_b)`，超出 u64 时返回 None。
+pub fn get_delta_amount_0(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u64> {
+    let (lower, upper) = sorted(sqrt_price_a, sqrt_price_b);
+    let numerator = U512::from_u128(liquidity) * Q64 * U512::from_u128(upper - lower);
+    let denominator = U512::from_u128(lower) * U512::from_u128(upper);
+    to_u64(numerator.div_round(denominator, round_up))
+}
+
+/// 价格在 `sqrt_price_a` 和 `sqrt_price_b` 之间移动时 token_1 的变化量
+///
+/// `liquidity * (sqrt_b - sqrt_a) / 2^64`，超出 u64 时返回 None。
+pub fn get_delta_amount_1(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u64> {
+    let (lower, upper) = sorted(sqrt_price_a, sqrt_price_b);
+    let numerator = U512::from_u128(liquidity) * U512::from_u128(upper - lower);
+    to_u64(numerator.div_round(Q64, round_up))
+}
+
+/// 输入 `amount_in` 后的价格平方根，价格取整方向对池子有利
+fn get_next_sqrt_price_from_input(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Result<u128, anyhow::Error> {
+    if zero_for_one {
+        next_sqrt_price_from_amount_0(sqrt_price, liquidity, amount_in, true)
+    } else {
+        next_sqrt_price_from_amount_1(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+/// 输出 `amount_out` 后的价格平方根，价格取整方向对池子有利
+fn get_next_sqrt_price_from_output(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_out: u64,
+    zero_for_one: bool,
+) -> Result<u128, anyhow::Error> {
+    if zero_for_one {
+        next_sqrt_price_from_amount_1(sqrt_price, liquidity, amount_out, false)
+    } else {
+        next_sqrt_price_from_amount_0(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+/// `liquidity * sqrt_price / (liquidity ± amount * sqrt_price)`，向上取整
+fn next_sqrt_price_from_amount_0(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128, anyhow::Error> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+    let numerator = U512::from_u128(liquidity) * Q64;
+    let product = U512::from_u128(amount as u128) * U512::from_u128(sqrt_price);
+    let denominator = if add {
+        numerator + product
+    } else {
+        if numerator <= product {
+            return Err(anyhow!("Not enough liquidity in the pool"));
+        }
+        numerator - product
+    };
+    (numerator * U512::from_u128(sqrt_price))
+        .div_round(denominator, true)
+        .to_u128()
+        .ok_or_else(|| anyhow!("Sqrt price overflow"))
+}
+
+/// `sqrt_price ± amount / liquidity`，向下取整
+fn next_sqrt_price_from_amount_1(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128, anyhow::Error> {
+    if liquidity == 0 {
+        return Err(anyhow!("Not enough liquidity in the pool"));
+    }
+    let shifted = (amount as u128) << 64;
+    if add {
+        sqrt_price.checked_add(shifted / liquidity).ok_or_else(|| anyhow!("Sqrt price overflow"))
+    } else {
+        sqrt_price
+            .checked_sub(shifted.div_ceil(liquidity))
+            .filter(|next| *next > 0)
+            .ok_or_else(|| anyhow!("Not enough liquidity in the pool"))
+    }
+}
+
+/// 单个价格区间内的交换结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwapStep {
+    pub sqrt_price_next_x64: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// 在 `sqrt_price_current` 到 `sqrt_price_target` 之间交换，流动性不变
+///
+/// `is_base_input` 为 true 时 `amount_remaining` 是剩余输入（含手续费），否则是剩余输出。
+/// 返回的 `amount_in` 不含手续费。
+pub fn compute_swap_step(
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u32,
+    is_base_input: bool,
+    zero_for_one: bool,
+) -> Result<SwapStep, anyhow::Error> {
+    let amount_in_to_target = |round_up| {
+        if zero_for_one {
+            get_delta_amount_0(sqrt_price_target, sqrt_price_current, liquidity, round_up)
+        } else {
+            get_delta_amount_1(sqrt_price_current, sqrt_price_target, liquidity, round_up)
+        }
+    };
+    let amount_out_to_target = || {
+        if zero_for_one {
+            get_delta_amount_1(sqrt_price_target, sqrt_price_current, liquidity, false)
+        } else {
+            get_delta_amount_0(sqrt_price_current, sqrt_price_target, liquidity, false)
+        }
+    };
+
+    let sqrt_price_next_x64 = if is_base_input {
+        let amount_remaining_less_fee = (amount_remaining as u128
+            * (FEE_RATE_DENOMINATOR - fee_rate) as u128
+            / FEE_RATE_DENOMINATOR as u128) as u64;
+        match amount_in_to_target(true) {
+            Some(amount_in) if amount_remaining_less_fee >= amount_in => sqrt_price_target,
+            _ => get_next_sqrt_price_from_input(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?,
+        }
+    } else {
+        match amount_out_to_target() {
+            Some(amount_out) if amount_remaining >= amount_out => sqrt_price_target,
+            _ => get_next_sqrt_price_from_output(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining,
+                zero_for_one,
+            )?,
+        }
+    };
+
+    let overflow = || anyhow!("Swap amount overflow");
+    let (amount_in, mut amount_out) = if zero_for_one {
+        (
+            get_delta_amount_0(sqrt_price_next_x64, sqrt_price_current, liquidity, true)
+                .ok_or_else(overflow)?,
+            get_delta_amount_1(sqrt_price_next_x64, sqrt_price_current, liquidity, false)
+                .ok_or_else(overflow)?,
+        )
+    } else {
+        (
+            get_delta_amount_1(sqrt_price_current, sqrt_price_next_x64, liquidity, true)
+                .ok_or_else(overflow)?,
+            get_delta_amount_0(sqrt_price_current, sqrt_price_next_x64, liquidity, false)
+                .ok_or_else(overflow)?,
+        )
+    };
+    if !is_base_input && amount_out > amount_remaining {
+        amount_out = amount_remaining;
+    }
+
+    // 没有到达目标价格时，剩余输入全部算作手续费
+    let fee_amount = if is_base_input && sqrt_price_next_x64 != sqrt_price_target {
+        amount_remaining - amount_in
+    } else {
+        (amount_in as u128 * fee_rate as u128).div_ceil((FEE_RATE_DENOMINATOR - fee_rate) as u128)
+            as u64
+    };
+
+    Ok(SwapStep { sqrt_price_next_x64, amount_in, amount_out, fee_amount })
+}
+
+fn sorted(a: u128, b: u128) -> (u128, u128) {
+    if a > b {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+fn to_u64(value: U512) -> Option<u64> {
+    value.to_u128().and_then(|value| u64::try_from(value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::raydium_clmm::{MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64};
+
+    #[test]
+    fn test_sqrt_price_at_tick() {
+        assert_eq!(get_sqrt_price_at_tick(0).unwrap(), 1u128 << 64);
+        assert_eq!(get_sqrt_price_at_tick(MIN_TICK).unwrap(), MIN_SQRT_PRICE_X64);
+        assert_eq!(get_sqrt_price_at_tick(MAX_TICK).unwrap(), MAX_SQRT_PRICE_X64);
+        assert!(get_sqrt_price_at_tick(MAX_TICK + 1).is_err());
+    }
+
+    #[test]
+    fn test_wide_division() {
+        let numerator = U512::from_u128(u128::MAX) * U512::from_u128(u128::MAX);
+        let (quotient, remainder) = numerator.div_rem(U512::from_u128(u128::MAX));
+        assert_eq!(quotient.to_u128(), Some(u128::MAX));
+        assert!(remainder.is_zero());
+        assert_eq!(U512::from_u128(7).div_round(U512::from_u128(2), true).to_u128(), Some(4));
+        assert_eq!(U512::from_u128(7).div_round(U512::from_u128(2), false).to_u128(), Some(3));
+    }
+
+    #[test]
+    fn test_swap_step() {
+        let liquidity = 1_000_000_000_000u128;
+        let sqrt_price = 1u128 << 64;
+        let target = get_sqrt_price_at_tick(-100).unwrap();
+
+        // 输入不足以到达目标价格，剩余部分全部算作手续费
+        let step =
+            compute_swap_step(sqrt_price, target, liquidity, 1_000_000, 2500, true, true).unwrap();
+        assert!(step.sqrt_price_next_x64 > target && step.sqrt_price_next_x64 < sqrt_price);
+        assert_eq!(step.amount_in + step.fee_amount, 1_000_000);
+        assert!(step.amount_out < step.amount_in);
+
+        // 固定输出时得到的数量恰好等于请求的数量
+        let step =
+            compute_swap_step(sqrt_price, target, liquidity, 997_000, 2500, false, true).unwrap();
+        assert_eq!(step.amount_out, 997_000);
+        assert!(step.amount_in > 997_000);
+
+        // 足够的输入会停在目标价格
+        let step = compute_swap_step(sqrt_price, target, liquidity, u64::MAX / 2, 2500, true, true)
+            .unwrap();
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert_eq!(
+            step.amount_in,
+            get_delta_amount_0(target, sqrt_price, liquidity, true).unwrap()
+        );
+    }
+}
@@ -0,0 +1,5 @@
+pub mod amm_config;
+pub mod common;
+pub mod math;
+pub mod pool;
+pub mod tick_array;
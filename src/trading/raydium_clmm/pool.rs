@@ -0,0 +1,188 @@
+use crate::{
+    common::SolanaRpcClient, constants::raydium_clmm::accounts, trading::common::find_deepest_vault,
+};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::pubkey::Pubkey;
+
+/// PoolState 账户大小，包含 8 字节 discriminator
+pub const POOL_STATE_SIZE: usize = 1544;
+/// 账户数据中 token_mint_0 的偏移，包含 8 字节 discriminator
+pub const TOKEN_MINT_0_OFFSET: usize = 73;
+/// 账户数据中 token_mint_1 的偏移
+pub const TOKEN_MINT_1_OFFSET: usize = 105;
+
+#[derive(Debug, Clone, Copy, BorshDeserialize)]
+pub struct RewardInfo {
+    pub reward_state: u8,
+    pub open_time: u64,
+    pub end_time: u64,
+    pub last_update_time: u64,
+    pub emissions_per_second_x64: u128,
+    pub reward_total_emissioned: u64,
+    pub reward_claimed: u64,
+    pub token_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub authority: Pubkey,
+    pub reward_growth_global_x64: u128,
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct PoolState {
+    pub bump: [u8; 1],
+    pub amm_config: Pubkey,
+    pub owner: Pubkey,
+    /// token_mint_0 的地址总是小于 token_mint_1
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub observation_key: Pubkey,
+    pub mint_decimals_0: u8,
+    pub mint_decimals_1: u8,
+    pub tick_spacing: u16,
+    /// 当前价格区间内的流动性
+    pub liquidity: u128,
+    /// 当前价格 token_1/token_0 的平方根，Q64.64 格式
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    pub padding3: u16,
+    pub padding4: u16,
+    pub fee_growth_global_0_x64: u128,
+    pub fee_growth_global_1_x64: u128,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub swap_in_amount_token_0: u128,
+    pub swap_out_amount_token_1: u128,
+    pub swap_in_amount_token_1: u128,
+    pub swap_out_amount_token_0: u128,
+    pub status: u8,
+    pub padding: [u8; 7],
+    pub reward_infos: [RewardInfo; 3],
+    /// 已初始化的 tick array，覆盖起始 tick 附近正负各 512 个 tick array
+    pub tick_array_bitmap: [u64; 16],
+    pub total_fees_token_0: u64,
+    pub total_fees_claimed_token_0: u64,
+    pub total_fees_token_1: u64,
+    pub total_fees_claimed_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub open_time: u64,
+    pub recent_epoch: u64,
+    pub padding1: [u64; 24],
+    pub padding2: [u64; 32],
+}
+
+impl PoolState {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 8 {
+            return Err(anyhow!("PoolState account data is too short"));
+        }
+        let pool = PoolState::deserialize(&mut &data[8..])?;
+        Ok(pool)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, pool_state: &Pubkey) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(pool_state).await?;
+
+        if account.owner != accounts::RAYDIUM_CLMM {
+            return Err(anyhow!("Account is not owned by Raydium Clmm program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+
+    /// 查找 `mint` 与 WSOL 组成的 WSOL 储备最深的池子
+    pub async fn find_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Self), anyhow::Error> {
+        let mut pools = Self::find_by_mint_offset(rpc, mint, TOKEN_MINT_0_OFFSET).await?;
+        pools.extend(Self::find_by_mint_offset(rpc, mint, TOKEN_MINT_1_OFFSET).await?);
+        pools.retain(|(_, pool)| pool.wsol_vault().is_some());
+        if pools.is_empty() {
+            return Err(anyhow!("No WSOL pool found for mint {}", mint));
+        }
+
+        let wsol_vaults: Vec<Pubkey> =
+            pools.iter().filter_map(|(_, pool)| pool.wsol_vault()).collect();
+        let index = find_deepest_vault(rpc, &wsol_vaults)
+            .await?
+            .ok_or_else(|| anyhow!("No WSOL pool found for mint {}", mint))?;
+        Ok(pools.swap_remove(index))
+    }
+
+    /// 池子是 WSOL 交易对时返回 WSOL 一侧的金库
+    pub fn wsol_vault(&self) -> Option<Pubkey> {
+        if self.token_mint_0 == accounts::WSOL_TOKEN_ACCOUNT {
+            Some(self.token_vault_0)
+        } else if self.token_mint_1 == accounts::WSOL_TOKEN_ACCOUNT {
+            Some(self.token_vault_1)
+        } else {
+            None
+        }
+    }
+
+    async fn find_by_mint_offset(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        offset: usize,
+    ) -> Result<Vec<(Pubkey, Self)>, anyhow::Error> {
+        let filters = vec![
+            solana_rpc_client_api::filter::RpcFilterType::DataSize(POOL_STATE_SIZE as u64),
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(offset, &mint.to_bytes()),
+            ),
+        ];
+        let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts =
+            rpc.get_program_accounts_with_config(&accounts::RAYDIUM_CLMM, config).await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                Self::from_bytes(&account.data).map(|pool| (address, pool)).ok()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_state_layout() {
+        let (mint_0, mint_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; POOL_STATE_SIZE];
+        data[TOKEN_MINT_0_OFFSET..TOKEN_MINT_0_OFFSET + 32].copy_from_slice(mint_0.as_ref());
+        data[TOKEN_MINT_1_OFFSET..TOKEN_MINT_1_OFFSET + 32].copy_from_slice(mint_1.as_ref());
+        // tick_spacing / liquidity / sqrt_price_x64 / tick_current
+        data[235..237].copy_from_slice(&60u16.to_le_bytes());
+        data[237..253].copy_from_slice(&1_000u128.to_le_bytes());
+        data[253..269].copy_from_slice(&(1u128 << 64).to_le_bytes());
+        data[269..273].copy_from_slice(&(-120i32).to_le_bytes());
+        // tick_array_bitmap[15]
+        data[1024..1032].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        let pool = PoolState::from_bytes(&data).unwrap();
+        assert_eq!(pool.token_mint_0, mint_0);
+        assert_eq!(pool.token_mint_1, mint_1);
+        assert_eq!(pool.tick_spacing, 60);
+        assert_eq!(pool.liquidity, 1_000);
+        assert_eq!(pool.sqrt_price_x64, 1u128 << 64);
+        assert_eq!(pool.tick_current, -120);
+        assert_eq!(pool.tick_array_bitmap[15], u64::MAX);
+        assert_eq!(pool.wsol_vault(), None);
+    }
+}
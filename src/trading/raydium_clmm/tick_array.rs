@@ -0,0 +1,231 @@
+use crate::{
+    common::SolanaRpcClient,
+    constants::raydium_clmm::{
+        accounts, EXTENSION_TICKARRAY_BITMAP_SIZE, MAX_TICK, MIN_TICK, TICK_ARRAY_BITMAP_SIZE,
+        TICK_ARRAY_SIZE,
+    },
+};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use super::pool::PoolState;
+
+#[derive(Debug, Clone, Copy, BorshDeserialize)]
+pub struct TickState {
+    pub tick: i32,
+    /// 从左向右穿过该 tick 时流动性的变化量
+    pub liquidity_net: i128,
+    /// 以该 tick 为边界的头寸流动性总和，为 0 表示未初始化
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_1_x64: u128,
+    pub reward_growths_outside_x64: [u128; 3],
+    pub padding: [u32; 13],
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct TickArrayState {
+    pub pool_id: Pubkey,
+    pub start_tick_index: i32,
+    pub ticks: [TickState; TICK_ARRAY_SIZE as usize],
+    pub initialized_tick_count: u8,
+    pub recent_epoch: u64,
+    pub padding: [u8; 107],
+}
+
+/// 池子账户的 bitmap 覆盖不到的 tick array，正负方向各 14 个 512 位的 bitmap
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct TickArrayBitmapExtension {
+    pub pool_id: Pubkey,
+    pub positive_tick_array_bitmap: [[u64; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+    pub negative_tick_array_bitmap: [[u64; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+}
+
+impl TickState {
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross != 0
+    }
+}
+
+impl TickArrayState {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 8 {
+            return Err(anyhow!("TickArrayState account data is too short"));
+        }
+        let tick_array = TickArrayState::deserialize(&mut &data[8..])?;
+        Ok(tick_array)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, tick_array: &Pubkey) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(tick_array).await?;
+
+        if account.owner != accounts::RAYDIUM_CLMM {
+            return Err(anyhow!("Account is not owned by Raydium Clmm program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+}
+
+impl TickArrayBitmapExtension {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 8 {
+            return Err(anyhow!("TickArrayBitmapExtension account data is too short"));
+        }
+        let extension = TickArrayBitmapExtension::deserialize(&mut &data[8..])?;
+        Ok(extension)
+    }
+
+    /// 起始 tick 为 `start_index` 的 tick array 是否已初始化
+    ///
+    /// 只处理池子 bitmap 覆盖范围以外的 tick array，超出扩展范围时返回 false。
+    fn is_initialized(&self, start_index: i32, tick_spacing: u16) -> bool {
+        let ticks_in_bitmap = max_tick_in_default_bitmap(tick_spacing);
+        let abs_index = start_index.abs();
+        let mut offset = abs_index / ticks_in_bitmap - 1;
+        if start_index < 0 && abs_index % ticks_in_bitmap == 0 {
+            offset -= 1;
+        }
+        if offset < 0 || offset as usize >= EXTENSION_TICKARRAY_BITMAP_SIZE {
+            return false;
+        }
+        let remainder = abs_index % ticks_in_bitmap;
+        let mut bit = remainder / tick_count(tick_spacing);
+        if start_index < 0 && remainder != 0 {
+            bit = TICK_ARRAY_BITMAP_SIZE - bit;
+        }
+        let bitmap = if start_index < 0 {
+            &self.negative_tick_array_bitmap[offset as usize]
+        } else {
+            &self.positive_tick_array_bitmap[offset as usize]
+        };
+        bitmap_bit(bitmap, bit as usize)
+    }
+}
+
+/// 一个 tick array 覆盖的 tick 数量
+pub fn tick_count(tick_spacing: u16) -> i32 {
+    TICK_ARRAY_SIZE * tick_spacing as i32
+}
+
+/// 池子 bitmap 覆盖的正方向最大 tick，负方向对称
+fn max_tick_in_default_bitmap(tick_spacing: u16) -> i32 {
+    TICK_ARRAY_BITMAP_SIZE * tick_count(tick_spacing)
+}
+
+/// 包含 `tick` 的 tick array 的起始 tick
+pub fn get_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = tick_count(tick_spacing);
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// 起始 tick 为 `start_index` 的 tick array 是否已初始化，需要时查询 bitmap 扩展
+pub fn is_tick_array_initialized(
+    pool: &PoolState,
+    extension: Option<&TickArrayBitmapExtension>,
+    start_index: i32,
+) -> bool {
+    let max_tick = max_tick_in_default_bitmap(pool.tick_spacing);
+    if start_index >= max_tick || start_index < -max_tick {
+        return extension
+            .is_some_and(|extension| extension.is_initialized(start_index, pool.tick_spacing));
+    }
+    let bit = start_index / tick_count(pool.tick_spacing) + TICK_ARRAY_BITMAP_SIZE;
+    bitmap_bit(&pool.tick_array_bitmap, bit as usize)
+}
+
+/// 从当前价格所在的 tick array 开始，按交换方向列出已初始化的 tick array 起始 tick
+///
+/// `zero_for_one` 为 true 时价格下降，依次向左查找。最多返回 `max_count` 个。
+pub fn initialized_tick_array_start_indices(
+    pool: &PoolState,
+    extension: Option<&TickArrayBitmapExtension>,
+    zero_for_one: bool,
+    max_count: usize,
+) -> Vec<i32> {
+    let ticks_in_array = tick_count(pool.tick_spacing);
+    let min_start = get_array_start_index(MIN_TICK, pool.tick_spacing);
+    let max_start = get_array_start_index(MAX_TICK, pool.tick_spacing);
+    let mut start_index = get_array_start_index(pool.tick_current, pool.tick_spacing);
+    let mut start_indices = vec![];
+    while start_indices.len() < max_count && (min_start..=max_start).contains(&start_index) {
+        if is_tick_array_initialized(pool, extension, start_index) {
+            start_indices.push(start_index);
+        }
+        start_index += if zero_for_one { -ticks_in_array } else { ticks_in_array };
+    }
+    start_indices
+}
+
+/// 低位在前的 bitmap 中第 `bit` 位是否为 1
+fn bitmap_bit(bitmap: &[u64], bit: usize) -> bool {
+    bitmap.get(bit / 64).is_some_and(|word| word & (1 << (bit % 64)) != 0)
+}
+
+/// 测试用的 tick array，`ticks` 为 tick spacing 10 下已初始化的 `(tick, liquidity_net)`
+#[cfg(test)]
+pub(crate) fn test_tick_array(start_tick_index: i32, ticks: &[(i32, i128)]) -> TickArrayState {
+    let empty = TickState {
+        tick: 0,
+        liquidity_net: 0,
+        liquidity_gross: 0,
+        fee_growth_outside_0_x64: 0,
+        fee_growth_outside_1_x64: 0,
+        reward_growths_outside_x64: [0; 3],
+        padding: [0; 13],
+    };
+    let mut tick_array = TickArrayState {
+        pool_id: Pubkey::default(),
+        start_tick_index,
+        ticks: [empty; TICK_ARRAY_SIZE as usize],
+        initialized_tick_count: ticks.len() as u8,
+        recent_epoch: 0,
+        padding: [0; 107],
+    };
+    for (tick, liquidity_net) in ticks {
+        let offset = ((tick - start_tick_index) / 10) as usize;
+        tick_array.ticks[offset].tick = *tick;
+        tick_array.ticks[offset].liquidity_net = *liquidity_net;
+        tick_array.ticks[offset].liquidity_gross = liquidity_net.unsigned_abs();
+    }
+    tick_array
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_bit(bitmap: &mut [u64], bit: usize) {
+        bitmap[bit / 64] |= 1 << (bit % 64);
+    }
+
+    #[test]
+    fn test_array_start_index() {
+        assert_eq!(get_array_start_index(0, 10), 0);
+        assert_eq!(get_array_start_index(599, 10), 0);
+        assert_eq!(get_array_start_index(600, 10), 600);
+        assert_eq!(get_array_start_index(-1, 10), -600);
+        assert_eq!(get_array_start_index(-600, 10), -600);
+        assert_eq!(get_array_start_index(-601, 10), -1200);
+    }
+
+    #[test]
+    fn test_bitmap_extension() {
+        let mut extension = TickArrayBitmapExtension {
+            pool_id: Pubkey::new_unique(),
+            positive_tick_array_bitmap: [[0; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+            negative_tick_array_bitmap: [[0; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE],
+        };
+        let ticks_in_bitmap = max_tick_in_default_bitmap(1);
+        // 正方向紧接着池子 bitmap 的第一个 tick array
+        set_bit(&mut extension.positive_tick_array_bitmap[0], 0);
+        assert!(extension.is_initialized(ticks_in_bitmap, 1));
+        assert!(!extension.is_initialized(ticks_in_bitmap + 60, 1));
+        // 负方向紧接着池子 bitmap 的第一个 tick array 位于最高位
+        set_bit(&mut extension.negative_tick_array_bitmap[0], 511);
+        assert!(extension.is_initialized(-ticks_in_bitmap - 60, 1));
+        set_bit(&mut extension.negative_tick_array_bitmap[1], 0);
+        assert!(extension.is_initialized(-3 * ticks_in_bitmap, 1));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{common::SolanaRpcClient, constants::raydium_cpmm::accounts};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use super::pool::Pool;
+
+/// 手续费率的分母，费率 2500 表示 0.25%
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/// 手续费档位配置，每个池子属于一个 AmmConfig
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub disable_create_pool: bool,
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub protocol_owner: Pubkey,
+    pub fund_owner: Pubkey,
+    pub creator_fee_rate: u64,
+    pub padding: [u64; 15],
+}
+
+/// 一次交换实际生效的手续费率
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwapFees {
+    pub trade_fee_rate: u64,
+    pub creator_fee_rate: u64,
+    /// 创建者费用从输入代币中收取，否则从输出代币中收取
+    pub creator_fee_on_input: bool,
+}
+
+impl AmmConfig {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 8 {
+            return Err(anyhow!("AmmConfig account data is too short"));
+        }
+        let config = AmmConfig::deserialize(&mut &data[8..])?;
+        Ok(config)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, amm_config: &Pubkey) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(amm_config).await?;
+
+        if account.owner != accounts::RAYDIUM_CPMM {
+            return Err(anyhow!("Account is not owned by Raydium Cpmm program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+
+    /// 在 `pool` 中交换时的手续费率，`input_is_token0` 表示输入代币是 token0
+    pub fn swap_fees(&self, pool: &Pool, input_is_token0: bool) -> SwapFees {
+        let creator_fee_rate = if pool.enable_creator_fee { self.creator_fee_rate } else { 0 };
+        // creator_fee_on: 0 两种代币都收，1 只收 token0，2 只收 token1
+        let creator_fee_on_input = match pool.creator_fee_on {
+            0 => true,
+            1 => input_is_token0,
+            _ => !input_is_token0,
+        };
+        SwapFees { trade_fee_rate: self.trade_fee_rate, creator_fee_rate, creator_fee_on_input }
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
     common::SolanaRpcClient,
     constants::{self, raydium_cpmm::accounts::WSOL_TOKEN_ACCOUNT},
-    trading::raydium_cpmm::pool::Pool,
+    trading::raydium_cpmm::{
+        amm_config::{AmmConfig, SwapFees, FEE_RATE_DENOMINATOR},
+        pool::Pool,
+    },
 };
 use anyhow::anyhow;
 use solana_sdk::pubkey::Pubkey;
@@ -39,100 +42,236 @@ pub fn get_observation_state_pda(pool_state: &Pubkey) -> Option<Pubkey> {
     pda.map(|pubkey| pubkey.0)
 }
 
+pub fn get_lp_mint_pda(pool_state: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] =
+        &[constants::raydium_cpmm::seeds::POOL_LP_MINT_SEED, pool_state.as_ref()];
+    let program_id: &Pubkey = &constants::raydium_cpmm::accounts::RAYDIUM_CPMM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 按编号计算 AmmConfig 地址，不同编号对应不同的手续费档位
+pub fn get_amm_config_pda(index: u16) -> Option<Pubkey> {
+    let index = index.to_be_bytes();
+    let seeds: &[&[u8]; 2] = &[constants::raydium_cpmm::seeds::AMM_CONFIG_SEED, &index];
+    let program_id: &Pubkey = &constants::raydium_cpmm::accounts::RAYDIUM_CPMM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 花费 `sol_amount` 个SOL可以买到的代币数量，已扣除手续费
 pub async fn get_buy_token_amount(
     rpc: &SolanaRpcClient,
     pool_state: &Pubkey,
     sol_amount: u64,
 ) -> Result<u64, anyhow::Error> {
+    let (reserve_in, reserve_out, fees) = get_swap_state(rpc, pool_state, true).await?;
+    get_amount_out(sol_amount, reserve_in, reserve_out, fees)
+}
+
+/// 卖出 `token_amount` 个代币可以得到的SOL数量，已扣除手续费
+pub async fn get_sell_sol_amount(
+    rpc: &SolanaRpcClient,
+    pool_state: &Pubkey,
+    token_amount: u64,
+) -> Result<u64, anyhow::Error> {
+    let (reserve_in, reserve_out, fees) = get_swap_state(rpc, pool_state, false).await?;
+    get_amount_out(token_amount, reserve_in, reserve_out, fees)
+}
+
+/// 买入 `token_amount` 个代币需要花费的SOL数量，包含手续费
+pub async fn get_buy_sol_amount(
+    rpc: &SolanaRpcClient,
+    pool_state: &Pubkey,
+    token_amount: u64,
+) -> Result<u64, anyhow::Error> {
+    let (reserve_in, reserve_out, fees) = get_swap_state(rpc, pool_state, true).await?;
+    get_amount_in_with_fees(token_amount, reserve_in, reserve_out, fees)
+}
+
+/// 卖出得到 `sol_amount` 个SOL需要卖出的代币数量，包含手续费
+pub async fn get_sell_token_amount(
+    rpc: &SolanaRpcClient,
+    pool_state: &Pubkey,
+    sol_amount: u64,
+) -> Result<u64, anyhow::Error> {
+    let (reserve_in, reserve_out, fees) = get_swap_state(rpc, pool_state, false).await?;
+    get_amount_in_with_fees(sol_amount, reserve_in, reserve_out, fees)
+}
+
+/// 读取池子、所属的 AmmConfig 和金库余额
+///
+/// # 返回值
+/// 返回扣除未领取费用后的输入储备、输出储备，以及本次交换的手续费率
+async fn get_swap_state(
+    rpc: &SolanaRpcClient,
+    pool_state: &Pubkey,
+    sol_input: bool,
+) -> Result<(u64, u64, SwapFees), anyhow::Error> {
     let pool = Pool::fetch(rpc, pool_state).await?;
-    let is_token0_input = if pool.token0_mint == WSOL_TOKEN_ACCOUNT {
-        true
-    } else {
-        false
-    };
+    let amm_config = AmmConfig::fetch(rpc, &pool.amm_config).await?;
     let (token0_balance, token1_balance) =
         get_pool_token_balances(rpc, pool_state, &pool.token0_mint, &pool.token1_mint).await?;
+    let (token0_reserves, token1_reserves) =
+        pool.vault_amounts_without_fees(token0_balance, token1_balance)?;
 
-    // 使用恒定乘积公式计算
-
-    let (reserve_in, reserve_out) = if is_token0_input {
-        (token0_balance, token1_balance)
+    let input_is_token0 = (pool.token0_mint == WSOL_TOKEN_ACCOUNT) == sol_input;
+    let fees = amm_config.swap_fees(&pool, input_is_token0);
+    if input_is_token0 {
+        Ok((token0_reserves, token1_reserves, fees))
     } else {
-        (token1_balance, token0_balance)
-    };
+        Ok((token1_reserves, token0_reserves, fees))
+    }
+}
 
+/// 输入 `amount_in` 得到的输出数量，对应 `swap_base_input`
+///
+/// 交易费从输入中扣除，向上取整；创建者费用根据 `fees.creator_fee_on_input` 从输入或输出中扣除。
+pub fn get_amount_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fees: SwapFees,
+) -> Result<u64, anyhow::Error> {
     if reserve_in == 0 || reserve_out == 0 {
         return Err(anyhow!("池子储备金为零，无法进行交换"));
     }
-
-    // 使用 u128 防止溢出
-    let amount_in_128 = sol_amount as u128;
-    let reserve_in_128 = reserve_in as u128;
-    let reserve_out_128 = reserve_out as u128;
+    let input_fee_rate =
+        if fees.creator_fee_on_input { input_fee_rate(fees)? } else { fees.trade_fee_rate };
+    let amount_in = amount_in as u128;
+    let amount_in_less_fees = amount_in
+        .checked_sub(fee_amount(amount_in, input_fee_rate))
+        .ok_or_else(|| anyhow!("手续费率无效: {}", input_fee_rate))?;
 
     // 恒定乘积公式: amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
-    let numerator = amount_in_128 * reserve_out_128;
-    let denominator = reserve_in_128 + amount_in_128;
-
-    if denominator == 0 {
-        return Err(anyhow!("分母为零，计算错误"));
+    let mut amount_out =
+        amount_in_less_fees * reserve_out as u128 / (reserve_in as u128 + amount_in_less_fees);
+    if !fees.creator_fee_on_input {
+        amount_out = amount_out
+            .checked_sub(fee_amount(amount_out, fees.creator_fee_rate))
+            .ok_or_else(|| anyhow!("手续费率无效: {}", fees.creator_fee_rate))?;
     }
 
-    let amount_out = numerator / denominator;
-
-    // 检查是否超出储备金
-    if amount_out >= reserve_out_128 {
+    if amount_out >= reserve_out as u128 {
         return Err(anyhow!("输出数量超过池子储备金"));
     }
-
     Ok(amount_out as u64)
 }
 
-pub async fn get_sell_sol_amount(
-    rpc: &SolanaRpcClient,
-    pool_state: &Pubkey,
-    token_amount: u64,
+/// 恒定乘积公式反推: 得到 `amount_out` 需要输入的数量，向上取整
+///
+/// 对应 `swap_base_output`: amount_in = ceil(amount_out * reserve_in / (reserve_out - amount_out))
+pub fn get_amount_in(
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
 ) -> Result<u64, anyhow::Error> {
-    let pool = Pool::fetch(rpc, pool_state).await?;
-    let is_token0_sol = if pool.token0_mint == WSOL_TOKEN_ACCOUNT {
-        true
-    } else {
-        false
-    };
-    let (token0_balance, token1_balance) =
-        get_pool_token_balances(rpc, pool_state, &pool.token0_mint, &pool.token1_mint).await?;
-
-    let (reserve_in, reserve_out) = if is_token0_sol {
-        (token1_balance, token0_balance)
-    } else {
-        (token0_balance, token1_balance)
-    };
-
     if reserve_in == 0 || reserve_out == 0 {
         return Err(anyhow!("池子储备金为零，无法进行交换"));
     }
+    if amount_out >= reserve_out {
+        return Err(anyhow!("输出数量超过池子储备金"));
+    }
+    let amount_in =
+        (amount_out as u128 * reserve_in as u128).div_ceil((reserve_out - amount_out) as u128);
+    u64::try_from(amount_in).map_err(|_| anyhow!("输入数量超出 u64 范围"))
+}
+
+/// 得到 `amount_out` 需要输入的数量，包含手续费，对应 `swap_base_output`
+pub fn get_amount_in_with_fees(
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fees: SwapFees,
+) -> Result<u64, anyhow::Error> {
+    if fees.creator_fee_on_input {
+        let amount_in = get_amount_in(amount_out, reserve_in, reserve_out)?;
+        pre_fee_amount(amount_in, input_fee_rate(fees)?)
+    } else {
+        let amount_out = pre_fee_amount(amount_out, fees.creator_fee_rate)?;
+        let amount_in = get_amount_in(amount_out, reserve_in, reserve_out)?;
+        pre_fee_amount(amount_in, fees.trade_fee_rate)
+    }
+}
 
-    // 使用 u128 防止溢出
-    let amount_in_128 = token_amount as u128;
-    let reserve_in_128 = reserve_in as u128;
-    let reserve_out_128 = reserve_out as u128;
+/// 交易费与创建者费用都从输入中收取时的合计费率
+fn input_fee_rate(fees: SwapFees) -> Result<u64, anyhow::Error> {
+    fees.trade_fee_rate
+        .checked_add(fees.creator_fee_rate)
+        .ok_or_else(|| anyhow!("手续费率无效: {} + {}", fees.trade_fee_rate, fees.creator_fee_rate))
+}
 
-    // 恒定乘积公式: amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
-    let numerator = amount_in_128 * reserve_out_128;
-    let denominator = reserve_in_128 + amount_in_128;
+/// 按费率计算手续费，向上取整
+fn fee_amount(amount: u128, fee_rate: u64) -> u128 {
+    (amount * fee_rate as u128).div_ceil(FEE_RATE_DENOMINATOR as u128)
+}
 
-    if denominator == 0 {
-        return Err(anyhow!("分母为零，计算错误"));
+/// 扣除费率后剩下 `amount` 所需的原始数量，向上取整
+fn pre_fee_amount(amount: u64, fee_rate: u64) -> Result<u64, anyhow::Error> {
+    if fee_rate == 0 {
+        return Ok(amount);
+    }
+    if fee_rate >= FEE_RATE_DENOMINATOR {
+        return Err(anyhow!("手续费率无效: {}", fee_rate));
     }
+    let amount = (amount as u128 * FEE_RATE_DENOMINATOR as u128)
+        .div_ceil((FEE_RATE_DENOMINATOR - fee_rate) as u128);
+    u64::try_from(amount).map_err(|_| anyhow!("输入数量超出 u64 范围"))
+}
 
-    let amount_out = numerator / denominator;
+/// 铸造 `lp_token_amount` 个 LP 需要存入的 token0 和 token1 数量，向上取整
+///
+/// 储备应为扣除未领取费用后的金库余额，见 `Pool::vault_amounts_without_fees`。
+pub fn get_deposit_amounts(
+    lp_token_amount: u64,
+    lp_supply: u64,
+    token0_reserves: u64,
+    token1_reserves: u64,
+) -> Result<(u64, u64), anyhow::Error> {
+    lp_tokens_to_trading_tokens(lp_token_amount, lp_supply, token0_reserves, token1_reserves, true)
+}
 
-    // 检查是否超出储备金
-    if amount_out >= reserve_out_128 {
-        return Err(anyhow!("输出数量超过池子储备金"));
+/// 销毁 `lp_token_amount` 个 LP 可以取回的 token0 和 token1 数量，向下取整
+pub fn get_withdraw_amounts(
+    lp_token_amount: u64,
+    lp_supply: u64,
+    token0_reserves: u64,
+    token1_reserves: u64,
+) -> Result<(u64, u64), anyhow::Error> {
+    if lp_token_amount > lp_supply {
+        return Err(anyhow!("LP 数量超过池子的 LP 总量"));
     }
+    lp_tokens_to_trading_tokens(lp_token_amount, lp_supply, token0_reserves, token1_reserves, false)
+}
 
-    Ok(amount_out as u64)
+/// 创建池子时创建者得到的 LP 数量: sqrt(amount0 * amount1) 减去永久锁定的部分
+pub fn get_initial_lp_amount(token0_amount: u64, token1_amount: u64) -> Result<u64, anyhow::Error> {
+    let liquidity = (token0_amount as u128 * token1_amount as u128).isqrt() as u64;
+    liquidity
+        .checked_sub(constants::raydium_cpmm::LOCK_LP_AMOUNT)
+        .ok_or_else(|| anyhow!("初始流动性不足以覆盖锁定的 LP 数量"))
+}
+
+fn lp_tokens_to_trading_tokens(
+    lp_token_amount: u64,
+    lp_supply: u64,
+    token0_reserves: u64,
+    token1_reserves: u64,
+    round_up: bool,
+) -> Result<(u64, u64), anyhow::Error> {
+    if lp_supply == 0 {
+        return Err(anyhow!("池子 LP 总量为零"));
+    }
+    let convert = |reserves: u64| {
+        let numerator = lp_token_amount as u128 * reserves as u128;
+        let amount = if round_up {
+            numerator.div_ceil(lp_supply as u128)
+        } else {
+            numerator / lp_supply as u128
+        };
+        u64::try_from(amount).map_err(|_| anyhow!("数量超出 u64 范围"))
+    };
+    Ok((convert(token0_reserves)?, convert(token1_reserves)?))
 }
 
 /// 获取池子中两个代币的余额
@@ -217,4 +356,60 @@ mod tests {
         let result = get_observation_state_pda(&pool_state);
         assert_eq!(result, Some(observation_state_pda));
     }
+
+    #[test]
+    fn test_amounts_with_fees() {
+        let (reserve_in, reserve_out) = (50_000_000_000, 1_000_000_000_000);
+        let fees =
+            SwapFees { trade_fee_rate: 2_500, creator_fee_rate: 500, creator_fee_on_input: true };
+        // 3_000_000 * 0.3% = 9_000 手续费
+        let amount_out = get_amount_out(3_000_000, reserve_in, reserve_out, fees).unwrap();
+        let expected = 2_991_000u128 * reserve_out as u128 / (reserve_in as u128 + 2_991_000);
+        assert_eq!(amount_out as u128, expected);
+
+        for fees in [fees, SwapFees { creator_fee_on_input: false, ..fees }] {
+            let target = 10_000_000_000;
+            let amount_in = get_amount_in_with_fees(target, reserve_in, reserve_out, fees).unwrap();
+            assert!(amount_in > get_amount_in(target, reserve_in, reserve_out).unwrap());
+            assert!(get_amount_out(amount_in, reserve_in, reserve_out, fees).unwrap() >= target);
+            assert!(get_amount_out(amount_in - 1, reserve_in, reserve_out, fees).unwrap() < target);
+        }
+
+        // 损坏的 AmmConfig 费率返回错误而不是溢出
+        let bad_fees = SwapFees { trade_fee_rate: 2 * FEE_RATE_DENOMINATOR, ..fees };
+        assert!(get_amount_out(3_000_000, reserve_in, reserve_out, bad_fees).is_err());
+        let bad_fees = SwapFees { creator_fee_rate: u64::MAX, ..fees };
+        assert!(get_amount_out(3_000_000, reserve_in, reserve_out, bad_fees).is_err());
+        assert!(get_amount_in_with_fees(1_000, reserve_in, reserve_out, bad_fees).is_err());
+    }
+
+    #[test]
+    fn test_lp_amounts() {
+        let (lp_supply, reserve0, reserve1) = (3_000, 1_000_000, 20_000_001);
+        assert_eq!(get_deposit_amounts(7, lp_supply, reserve0, reserve1).unwrap(), (2_334, 46_667));
+        assert_eq!(
+            get_withdraw_amounts(7, lp_supply, reserve0, reserve1).unwrap(),
+            (2_333, 46_666)
+        );
+        assert!(get_withdraw_amounts(lp_supply + 1, lp_supply, reserve0, reserve1).is_err());
+
+        assert_eq!(get_initial_lp_amount(1_000_000, 4_000_000).unwrap(), 1_999_900);
+        assert!(get_initial_lp_amount(10, 10).is_err());
+    }
+
+    #[test]
+    fn test_get_amount_in() {
+        let (reserve_in, reserve_out) = (50_000_000_000, 1_000_000_000_000);
+        let amount_out = 10_000_000_000;
+        let amount_in = get_amount_in(amount_out, reserve_in, reserve_out).unwrap();
+        assert_eq!(amount_in, 505050506);
+        // 正向计算刚好能得到 amount_out，少输入 1 就不够
+        let swap = |amount_in: u64| {
+            (amount_in as u128 * reserve_out as u128 / (reserve_in as u128 + amount_in as u128))
+                as u64
+        };
+        assert!(swap(amount_in) >= amount_out);
+        assert!(swap(amount_in - 1) < amount_out);
+        assert!(get_amount_in(reserve_out, reserve_in, reserve_out).is_err());
+    }
 }
@@ -1,2 +1,3 @@
+pub mod amm_config;
 pub mod common;
 pub mod pool;
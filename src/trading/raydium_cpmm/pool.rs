@@ -1,7 +1,20 @@
-use crate::{common::SolanaRpcClient, constants::raydium_cpmm::accounts};
+use crate::{
+    common::SolanaRpcClient,
+    constants::raydium_cpmm::accounts,
+    trading::common::{get_multiple_accounts, token_account_amount},
+};
 use anyhow::anyhow;
 use borsh::BorshDeserialize;
-use solana_sdk::pubkey::Pubkey;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::collections::HashMap;
+
+use super::amm_config::AmmConfig;
+
+/// 账户数据中 token0_mint 的偏移，包含 8 字节 discriminator
+pub const TOKEN0_MINT_OFFSET: usize = 168;
+/// 账户数据中 token1_mint 的偏移
+pub const TOKEN1_MINT_OFFSET: usize = 200;
 
 #[derive(Debug, Clone, BorshDeserialize)]
 pub struct Pool {
@@ -27,7 +40,12 @@ pub struct Pool {
     pub fund_fees_token1: u64,
     pub open_time: u64,
     pub recent_epoch: u64,
-    pub padding: [u64; 31],
+    pub creator_fee_on: u8,
+    pub enable_creator_fee: bool,
+    pub padding1: [u8; 6],
+    pub creator_fees_token0: u64,
+    pub creator_fees_token1: u64,
+    pub padding: [u64; 28],
 }
 
 impl Pool {
@@ -48,4 +66,215 @@ impl Pool {
 
         Self::from_bytes(&account.data)
     }
+
+    /// 查找包含 `mint` 的所有池子，附带储备、AmmConfig 和 token program
+    ///
+    /// 通过 getProgramAccounts 分别按 token0_mint 和 token1_mint 过滤，结果按 `mint`
+    /// 另一侧的储备从大到小排序。
+    pub async fn find_pools_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<Vec<PoolWithReserves>, anyhow::Error> {
+        let mut pool_accounts = Self::find_by_mint_offset(rpc, mint, TOKEN0_MINT_OFFSET).await?;
+        pool_accounts.extend(Self::find_by_mint_offset(rpc, mint, TOKEN1_MINT_OFFSET).await?);
+
+        let pools: Vec<(Pubkey, Pool)> = pool_accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                Self::from_bytes(&account.data).map(|pool| (address, pool)).ok()
+            })
+            .collect();
+        if pools.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // 一次性读取所有金库和 AmmConfig，避免逐个池子请求
+        let vaults: Vec<Pubkey> =
+            pools.iter().flat_map(|(_, pool)| [pool.token0_vault, pool.token1_vault]).collect();
+        let vault_accounts = get_multiple_accounts(rpc, &vaults).await?;
+        let mut config_keys: Vec<Pubkey> = pools.iter().map(|(_, pool)| pool.amm_config).collect();
+        config_keys.sort();
+        config_keys.dedup();
+        let configs: HashMap<Pubkey, AmmConfig> = config_keys
+            .iter()
+            .zip(get_multiple_accounts(rpc, &config_keys).await?)
+            .filter_map(|(key, account)| {
+                AmmConfig::from_bytes(&account?.data).ok().map(|config| (*key, config))
+            })
+            .collect();
+
+        let mut result = vec![];
+        for ((address, pool), vaults) in pools.into_iter().zip(vault_accounts.chunks(2)) {
+            let (Some(vault0), Some(vault1)) = (&vaults[0], &vaults[1]) else {
+                continue;
+            };
+            let Some(amm_config) = configs.get(&pool.amm_config) else {
+                continue;
+            };
+            let (Some(vault0_amount), Some(vault1_amount)) =
+                (token_account_amount(vault0), token_account_amount(vault1))
+            else {
+                continue;
+            };
+            let Ok((token0_reserves, token1_reserves)) =
+                pool.vault_amounts_without_fees(vault0_amount, vault1_amount)
+            else {
+                continue;
+            };
+            result.push(PoolWithReserves {
+                address,
+                amm_config: amm_config.clone(),
+                token0_reserves,
+                token1_reserves,
+                pool,
+            });
+        }
+        result.sort_by_key(|p| std::cmp::Reverse(p.counter_reserves(mint)));
+        Ok(result)
+    }
+
+    /// 查找 `mint` 与 WSOL 组成的储备最深的池子
+    pub async fn find_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Self), anyhow::Error> {
+        let pools = Self::find_pools_by_mint(rpc, mint).await?;
+        select_deepest_wsol_pool(&pools, mint)
+            .map(|pool| (pool.address, pool.pool.clone()))
+            .ok_or_else(|| anyhow!("No WSOL pool found for mint {}", mint))
+    }
+
+    async fn find_by_mint_offset(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        offset: usize,
+    ) -> Result<Vec<(Pubkey, Account)>, anyhow::Error> {
+        let filters = vec![solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+            solana_client::rpc_filter::Memcmp::new_base58_encoded(offset, &mint.to_bytes()),
+        )];
+        let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts =
+            rpc.get_program_accounts_with_config(&accounts::RAYDIUM_CPMM, config).await?;
+        Ok(accounts)
+    }
+
+    /// 金库余额扣除未领取的协议费、基金费和创建者费用后的部分，即参与交易和 LP 计算的储备
+    pub fn vault_amounts_without_fees(
+        &self,
+        token0_vault_amount: u64,
+        token1_vault_amount: u64,
+    ) -> Result<(u64, u64), anyhow::Error> {
+        let token0_fees = self
+            .protocol_fees_token0
+            .checked_add(self.fund_fees_token0)
+            .and_then(|fees| fees.checked_add(self.creator_fees_token0))
+            .ok_or_else(|| anyhow!("Token0 accrued fees overflow u64"))?;
+        let token1_fees = self
+            .protocol_fees_token1
+            .checked_add(self.fund_fees_token1)
+            .and_then(|fees| fees.checked_add(self.creator_fees_token1))
+            .ok_or_else(|| anyhow!("Token1 accrued fees overflow u64"))?;
+        let token0_amount = token0_vault_amount
+            .checked_sub(token0_fees)
+            .ok_or_else(|| anyhow!("Token0 vault balance is below the accrued fees"))?;
+        let token1_amount = token1_vault_amount
+            .checked_sub(token1_fees)
+            .ok_or_else(|| anyhow!("Token1 vault balance is below the accrued fees"))?;
+        Ok((token0_amount, token1_amount))
+    }
+}
+
+/// `find_pools_by_mint` 返回的池子，储备已扣除未领取的费用
+#[derive(Debug, Clone)]
+pub struct PoolWithReserves {
+    pub address: Pubkey,
+    pub pool: Pool,
+    pub amm_config: AmmConfig,
+    pub token0_reserves: u64,
+    pub token1_reserves: u64,
+}
+
+impl PoolWithReserves {
+    /// 与 `mint` 配对的另一侧代币的储备
+    pub fn counter_reserves(&self, mint: &Pubkey) -> u64 {
+        if self.pool.token0_mint == *mint {
+            self.token1_reserves
+        } else {
+            self.token0_reserves
+        }
+    }
+}
+
+/// 在 `mint`/WSOL 池子中选出 WSOL 储备最多的一个
+pub fn select_deepest_wsol_pool<'a>(
+    pools: &'a [PoolWithReserves],
+    mint: &Pubkey,
+) -> Option<&'a PoolWithReserves> {
+    pools
+        .iter()
+        .filter(|pool| {
+            let (token0_mint, token1_mint) = (&pool.pool.token0_mint, &pool.pool.token1_mint);
+            (token0_mint == mint && *token1_mint == accounts::WSOL_TOKEN_ACCOUNT)
+                || (token1_mint == mint && *token0_mint == accounts::WSOL_TOKEN_ACCOUNT)
+        })
+        .max_by_key(|pool| pool.counter_reserves(mint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_with_reserves(
+        token0_mint: Pubkey,
+        token1_mint: Pubkey,
+        token0_reserves: u64,
+        token1_reserves: u64,
+    ) -> PoolWithReserves {
+        let mut data = vec![0u8; 637];
+        data[TOKEN0_MINT_OFFSET..TOKEN0_MINT_OFFSET + 32].copy_from_slice(token0_mint.as_ref());
+        data[TOKEN1_MINT_OFFSET..TOKEN1_MINT_OFFSET + 32].copy_from_slice(token1_mint.as_ref());
+        let mut config = vec![0u8; 236];
+        config[12..20].copy_from_slice(&2_500u64.to_le_bytes());
+        PoolWithReserves {
+            address: Pubkey::new_unique(),
+            pool: Pool::from_bytes(&data).unwrap(),
+            amm_config: AmmConfig::from_bytes(&config).unwrap(),
+            token0_reserves,
+            token1_reserves,
+        }
+    }
+
+    #[test]
+    fn test_mint_offsets() {
+        let (mint0, mint1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let pool = pool_with_reserves(mint0, mint1, 0, 0);
+        assert_eq!(pool.pool.token0_mint, mint0);
+        assert_eq!(pool.pool.token1_mint, mint1);
+        assert_eq!(pool.amm_config.trade_fee_rate, 2_500);
+    }
+
+    #[test]
+    fn test_select_deepest_wsol_pool() {
+        let mint = Pubkey::new_unique();
+        let wsol = accounts::WSOL_TOKEN_ACCOUNT;
+        let pools = vec![
+            pool_with_reserves(mint, wsol, 1_000, 5_000),
+            pool_with_reserves(wsol, mint, 9_000, 1_000),
+            // 非 WSOL 池子即使更深也不会被选中
+            pool_with_reserves(mint, Pubkey::new_unique(), 1_000, 99_000),
+        ];
+        let selected = select_deepest_wsol_pool(&pools, &mint).unwrap();
+        assert_eq!(selected.address, pools[1].address);
+        assert!(select_deepest_wsol_pool(&pools[2..], &mint).is_none());
+    }
 }
@@ -1,19 +1,19 @@
+use crate::common::{AsyncTradeSigner, Lamports};
+use crate::constants::trade::trade::SIGNATURE_FEE;
 use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
 use crate::trading;
 use crate::SolanaTrade;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
-use solana_sdk::signer::Signer;
 
 impl SolanaTrade {
     #[inline]
-    pub async fn get_sol_balance(&self, payer: &Pubkey) -> Result<u64, anyhow::Error> {
-        trading::common::utils::get_sol_balance(&self.rpc, payer).await
+    pub async fn get_sol_balance(&self, payer: &Pubkey) -> Result<Lamports, anyhow::Error> {
+        trading::common::utils::get_sol_balance(&self.rpc, payer).await.map(Lamports::new)
     }
 
     #[inline]
-    pub async fn get_payer_sol_balance(&self) -> Result<u64, anyhow::Error> {
-        trading::common::utils::get_sol_balance(&self.rpc, &self.payer.pubkey()).await
+    pub async fn get_payer_sol_balance(&self) -> Result<Lamports, anyhow::Error> {
+        self.get_sol_balance(&self.payer.pubkey()).await
     }
 
     #[inline]
@@ -36,18 +36,19 @@ impl SolanaTrade {
     }
 
     #[inline]
-    pub fn get_payer(&self) -> &Keypair {
+    pub fn get_payer(&self) -> &dyn AsyncTradeSigner {
         self.payer.as_ref()
     }
 
     #[inline]
     pub async fn transfer_sol(
         &self,
-        payer: &Keypair,
+        payer: &dyn AsyncTradeSigner,
         receive_wallet: &Pubkey,
-        amount: u64,
+        amount: Lamports,
     ) -> Result<(), anyhow::Error> {
-        trading::common::utils::transfer_sol(&self.rpc, payer, receive_wallet, amount).await
+        trading::common::utils::transfer_sol(&self.rpc, payer, receive_wallet, amount.as_u64())
+            .await
     }
 
     #[inline]
@@ -55,6 +56,112 @@ impl SolanaTrade {
         trading::common::utils::close_token_account(&self.rpc, self.payer.as_ref(), mint).await
     }
 
+    // -------------------------------- Wallet Pool --------------------------------
+
+    /// 从资金钱包向钱包池中每个钱包转入相同数量的 SOL
+    ///
+    /// 返回每个钱包的转账结果，单个钱包失败不影响其他钱包。
+    pub async fn distribute_sol(
+        &self,
+        funding_wallet: &dyn AsyncTradeSigner,
+        amount_per_wallet: Lamports,
+    ) -> Result<Vec<(Pubkey, Result<(), anyhow::Error>)>, anyhow::Error> {
+        let wallets = self.wallet_pool.pubkeys();
+        let total = amount_per_wallet
+            .checked_mul(wallets.len() as u64)
+            .ok_or_else(|| anyhow::anyhow!("Distribution amount overflow"))?;
+        let balance = self.get_sol_balance(&funding_wallet.pubkey()).await?;
+        if balance < total {
+            return Err(anyhow::anyhow!(
+                "Insufficient balance: need {}, have {}",
+                total.sol(),
+                balance.sol()
+            ));
+        }
+
+        let transfers = wallets.iter().map(|wallet| {
+            trading::common::utils::transfer_sol(
+                &self.rpc,
+                funding_wallet,
+                wallet,
+                amount_per_wallet.as_u64(),
+            )
+        });
+        let results = futures::future::join_all(transfers).await;
+
+        Ok(wallets
+            .into_iter()
+            .zip(results)
+            .map(|(wallet, result)| {
+                self.wallet_pool.invalidate(&wallet);
+                (wallet, result)
+            })
+            .collect())
+    }
+
+    /// 将钱包池中每个钱包的 SOL 归集到 `receive_wallet`，每个钱包保留 `keep` 数量
+    ///
+    /// 返回每个钱包实际转出的数量，余额不足以支付手续费时转出 0。
+    pub async fn sweep_sol(
+        &self,
+        receive_wallet: &Pubkey,
+        keep: Lamports,
+    ) -> Vec<(Pubkey, Result<Lamports, anyhow::Error>)> {
+        let mut results = Vec::with_capacity(self.wallet_pool.len());
+        for index in 0..self.wallet_pool.len() {
+            let Some(wallet) = self.wallet_pool.get(index) else {
+                continue;
+            };
+            let pubkey = wallet.pubkey();
+            let result = async {
+                let balance = self.get_sol_balance(&pubkey).await?;
+                let amount = balance.saturating_sub(keep).saturating_sub(SIGNATURE_FEE);
+                if amount.is_zero() {
+                    return Ok(Lamports::ZERO);
+                }
+                trading::common::utils::transfer_sol(
+                    &self.rpc,
+                    wallet.as_ref(),
+                    receive_wallet,
+                    amount.as_u64(),
+                )
+                .await?;
+                Ok::<_, anyhow::Error>(amount)
+            }
+            .await;
+            self.wallet_pool.invalidate(&pubkey);
+            results.push((pubkey, result));
+        }
+        results
+    }
+
+    /// 将钱包池中每个钱包持有的代币转给 `receive_wallet`，并关闭代币账户回收租金
+    ///
+    /// 支持 SPL Token 和 Token-2022 代币，返回每个钱包转出的代币数量。
+    pub async fn sweep_tokens(
+        &self,
+        mint: &Pubkey,
+        receive_wallet: &Pubkey,
+    ) -> Vec<(Pubkey, Result<u64, anyhow::Error>)> {
+        let mut results = Vec::with_capacity(self.wallet_pool.len());
+        for index in 0..self.wallet_pool.len() {
+            let Some(wallet) = self.wallet_pool.get(index) else {
+                continue;
+            };
+            let pubkey = wallet.pubkey();
+            let result = trading::common::utils::transfer_all_tokens(
+                &self.rpc,
+                wallet.as_ref(),
+                mint,
+                receive_wallet,
+            )
+            .await;
+            self.wallet_pool.invalidate(&pubkey);
+            results.push((pubkey, result));
+        }
+        results
+    }
+
     // -------------------------------- PumpFun --------------------------------
 
     #[inline]
@@ -67,8 +174,12 @@ impl SolanaTrade {
     }
 
     #[inline]
-    pub fn get_pumpfun_token_buy_price(&self, amount: u64, trade_info: &PumpFunTradeEvent) -> u64 {
-        trading::pumpfun::common::get_buy_price(amount, trade_info)
+    pub fn get_pumpfun_token_buy_price(
+        &self,
+        amount: Lamports,
+        trade_info: &PumpFunTradeEvent,
+    ) -> u64 {
+        trading::pumpfun::common::get_buy_price(amount.as_u64(), trade_info)
     }
 
     #[inline]
@@ -92,13 +203,13 @@ impl SolanaTrade {
     pub async fn get_pumpfun_token_real_sol_reserves(
         &self,
         mint: &Pubkey,
-    ) -> Result<u64, anyhow::Error> {
+    ) -> Result<Lamports, anyhow::Error> {
         let (bonding_curve, _) =
             trading::pumpfun::common::get_bonding_curve_account_v2(&self.rpc, mint).await?;
 
         let actual_sol_reserves = bonding_curve.real_sol_reserves;
 
-        Ok(actual_sol_reserves)
+        Ok(Lamports::new(actual_sol_reserves))
     }
 
     #[inline]
@@ -139,12 +250,12 @@ impl SolanaTrade {
     pub async fn get_pumpswap_token_real_sol_reserves(
         &self,
         pool_address: &Pubkey,
-    ) -> Result<u64, anyhow::Error> {
+    ) -> Result<Lamports, anyhow::Error> {
         let pool = trading::pumpswap::pool::Pool::fetch(&self.rpc, pool_address).await?;
 
         let (_, quote_amount) = pool.get_token_balances(&self.rpc).await?;
 
-        Ok(quote_amount)
+        Ok(Lamports::new(quote_amount))
     }
 
     #[inline]